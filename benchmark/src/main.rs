@@ -526,7 +526,8 @@ impl Application for BenchmarkApp {
                     path.push_str(" Z");
                     
                     if mw.parse_svg_path(&mut pb, &path).is_ok() {
-                        pb.tessellate_to(mw, self.working_texture_id, 500, 500);
+                        // Снимок в формате окна, если это не RGBA8 в sRGB путь не рисуется
+                        pb.tessellate_to(mw, self.working_texture_id, 500, 500).ok();
                     }
                 },
 
@@ -0,0 +1,51 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use mlua::{UserData, UserDataMethods, Error};
+use moonwalk::AnimatedTexture;
+
+use super::MoonWalkLuaWrapper;
+
+pub struct LuaAnimatedTexture(pub AnimatedTexture);
+
+// Анимированная текстура (GIF, APNG, WebP). Время двигает движок:
+//  local anim = mw:load_animated_texture("loader.gif")
+//  mw:set_texture(rect, anim:id())
+//  -- каждый кадр
+//  mw:update_animated_textures(dt)
+impl UserData for LuaAnimatedTexture {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("id", |_, this, ()| Ok(this.0.texture_id()));
+
+        methods.add_method("play", |_, this, ()| { this.0.play(); Ok(()) });
+        methods.add_method("pause", |_, this, ()| { this.0.pause(); Ok(()) });
+        methods.add_method("stop", |_, this, ()| { this.0.stop(); Ok(()) });
+        methods.add_method("seek", |_, this, frame: usize| { this.0.seek(frame); Ok(()) });
+        methods.add_method("set_loop", |_, this, looping: bool| { this.0.set_loop(looping); Ok(()) });
+        methods.add_method("set_speed", |_, this, speed: f32| { this.0.set_speed(speed); Ok(()) });
+
+        methods.add_method("is_playing", |_, this, ()| Ok(this.0.is_playing()));
+        methods.add_method("is_looping", |_, this, ()| Ok(this.0.is_looping()));
+        methods.add_method("get_frame", |_, this, ()| Ok(this.0.frame()));
+        methods.add_method("get_frame_count", |_, this, ()| Ok(this.0.frame_count()));
+        methods.add_method("get_duration", |_, this, ()| Ok(this.0.duration()));
+
+        methods.add_method("get_size", |_, this, ()| {
+            let size = this.0.size();
+            Ok((size.x, size.y))
+        });
+    }
+}
+
+pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mut M) {
+    methods.add_method_mut("load_animated_texture", |_, this, path: String| {
+        this.get_mut().load_animated_texture(&path)
+            .map(LuaAnimatedTexture)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("update_animated_textures", |_, this, dt: f32| {
+        this.get_mut().update_animated_textures(dt);
+        Ok(())
+    });
+}
@@ -0,0 +1,94 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use mlua::{UserData, UserDataMethods, UserDataRef, Error, Table};
+use moonwalk::{ObjectId, PackedAtlas, TextureAtlas};
+
+use super::MoonWalkLuaWrapper;
+
+pub struct LuaAtlas(pub PackedAtlas);
+
+// Атлас собирается из таблицы имя -> путь к картинке:
+//  local ui = mw:build_atlas({ play = "icons/play.png", stop = "icons/stop.png" })
+//  ui:set_sprite(mw, button, "play")
+//  ui:save(mw, "cache/ui.json")
+impl UserData for LuaAtlas {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("set_sprite", |_, this, (mw, id, name): (UserDataRef<MoonWalkLuaWrapper>, usize, String)| {
+            let sprite = this.0.sprite(&name)
+                .ok_or_else(|| Error::RuntimeError(format!("Sprite {} not found", name)))?;
+
+            mw.get_mut().set_sprite(ObjectId(id), &sprite);
+            Ok(())
+        });
+
+        // Спрайт таблицей { texture, u, v, width, height } где uv в долях
+        // страницы атласа, nil если спрайта с таким именем нет
+        methods.add_method("get_sprite", |lua, this, name: String| {
+            let Some(sprite) = this.0.sprite(&name) else {
+                return Ok(None);
+            };
+
+            let table = lua.create_table()?;
+            table.set("texture", sprite.texture_id)?;
+            table.set("u", sprite.uv[0])?;
+            table.set("v", sprite.uv[1])?;
+            table.set("width", sprite.uv[2])?;
+            table.set("height", sprite.uv[3])?;
+
+            Ok(Some(table))
+        });
+
+        methods.add_method("get_names", |_, this, ()| {
+            Ok(this.0.sprites().map(|(name, _)| name.to_string()).collect::<Vec<String>>())
+        });
+
+        methods.add_method("get_pages", |_, this, ()| Ok(this.0.pages()));
+
+        methods.add_method("save", |_, this, (mw, path): (UserDataRef<MoonWalkLuaWrapper>, String)| {
+            this.0.save(mw.get(), &path).map_err(|e| Error::RuntimeError(e.to_string()))
+        });
+
+        methods.add_method("remove", |_, this, mw: UserDataRef<MoonWalkLuaWrapper>| {
+            this.0.clone().remove(mw.get_mut());
+            Ok(())
+        });
+    }
+}
+
+pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mut M) {
+    // Вторым аргументом можно передать { max_size = 2048, padding = 2, extrude = 1 }
+    methods.add_method_mut("build_atlas", |_, this, (images, options): (Table, Option<Table>)| {
+        let mut atlas = TextureAtlas::new();
+
+        if let Some(options) = options {
+            if let Some(size) = options.get::<_, Option<u32>>("max_size")? {
+                atlas = atlas.max_size(size);
+            }
+
+            if let Some(padding) = options.get::<_, Option<u32>>("padding")? {
+                atlas = atlas.padding(padding);
+            }
+
+            if let Some(extrude) = options.get::<_, Option<u32>>("extrude")? {
+                atlas = atlas.extrude(extrude);
+            }
+        }
+
+        for pair in images.pairs::<String, String>() {
+            let (name, path) = pair?;
+            atlas.add_file(this.get(), &name, &path)
+                .map_err(|e| Error::RuntimeError(e.to_string()))?;
+        }
+
+        atlas.build(this.get_mut())
+            .map(LuaAtlas)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("load_atlas", |_, this, path: String| {
+        PackedAtlas::load(this.get_mut(), &path)
+            .map(LuaAtlas)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+}
@@ -3,11 +3,13 @@
 
 use mlua::{UserData, UserDataMethods};
 use glam::{Vec2, Vec4};
-use moonwalk::{TextAlign, FontAsset};
+use moonwalk::{TextAlign, Overflow, FontStyle, FontAsset, TextDirection, TextPathOptions};
+use std::collections::HashMap;
 use moonwalk::rendering::container::RenderContainer;
 use moonwalk::objects::ObjectId;
 
 use super::MoonWalkLuaWrapper;
+use super::path::LuaPathBuilder;
 
 pub struct LuaRenderContainer(pub RenderContainer);
 
@@ -102,12 +104,118 @@ impl UserData for LuaRenderContainer {
                 "center" => TextAlign::Center,
                 "right" => TextAlign::Right,
                 "justified" => TextAlign::Justified,
+                "start" => TextAlign::Start,
+                "end" => TextAlign::End,
                 _ => TextAlign::Left,
             };
             this.0.set_text_align(ObjectId(id), align);
             Ok(())
         });
 
+        methods.add_method_mut("set_line_height", |_, this, (id, line_height): (usize, f32)| {
+            this.0.set_line_height(ObjectId(id), line_height);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_letter_spacing", |_, this, (id, spacing): (usize, f32)| {
+            this.0.set_letter_spacing(ObjectId(id), spacing);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_font_weight", |_, this, (id, weight): (usize, u16)| {
+            this.0.set_font_weight(ObjectId(id), weight);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_font_style", |_, this, (id, style_str): (usize, String)| {
+            let style = match style_str.as_str() {
+                "italic" => FontStyle::Italic,
+                "oblique" => FontStyle::Oblique,
+                _ => FontStyle::Normal,
+            };
+
+            this.0.set_font_style(ObjectId(id), style);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_font_features", |_, this, (id, features): (usize, Vec<String>)| {
+            let features: Vec<&str> = features.iter().map(|s| s.as_str()).collect();
+            this.0.set_font_features(ObjectId(id), &features);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_font_variations", |_, this, (id, axes): (usize, HashMap<String, f32>)| {
+            let axes: Vec<(&str, f32)> = axes.iter().map(|(axis, value)| (axis.as_str(), *value)).collect();
+            this.0.set_font_variations(ObjectId(id), &axes);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_text_overflow", |_, this, (id, overflow_str): (usize, String)| {
+            let overflow = match overflow_str.as_str() {
+                "clip" => Overflow::Clip,
+                "ellipsis" => Overflow::Ellipsis,
+                _ => Overflow::Visible,
+            };
+            this.0.set_text_overflow(ObjectId(id), overflow);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_text_direction", |_, this, (id, direction_str): (usize, String)| {
+            let direction = match direction_str.as_str() {
+                "ltr" => TextDirection::Ltr,
+                "rtl" => TextDirection::Rtl,
+                _ => TextDirection::Auto,
+            };
+            this.0.set_text_direction(ObjectId(id), direction);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_text_path", |_, this, (id, mut builder, start_offset, baseline_offset): (usize, mlua::UserDataRefMut<LuaPathBuilder>, Option<f32>, Option<f32>)| {
+            let inner = builder.0.take().ok_or(mlua::Error::RuntimeError("Consumed".into()))?;
+            let options = TextPathOptions {
+                start_offset: start_offset.unwrap_or(0.0),
+                baseline_offset: baseline_offset.unwrap_or(0.0),
+            };
+            this.0.set_text_path(ObjectId(id), inner, options);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_text_path_svg", |_, this, (id, data, start_offset, baseline_offset): (usize, String, Option<f32>, Option<f32>)| {
+            let options = TextPathOptions {
+                start_offset: start_offset.unwrap_or(0.0),
+                baseline_offset: baseline_offset.unwrap_or(0.0),
+            };
+            this.0.set_text_path_svg(ObjectId(id), &data, options).map_err(mlua::Error::RuntimeError)
+        });
+
+        methods.add_method_mut("set_text_path_options", |_, this, (id, start_offset, baseline_offset): (usize, f32, Option<f32>)| {
+            let options = TextPathOptions {
+                start_offset,
+                baseline_offset: baseline_offset.unwrap_or(0.0),
+            };
+            this.0.set_text_path_options(ObjectId(id), options);
+            Ok(())
+        });
+
+        methods.add_method_mut("clear_text_path", |_, this, id: usize| {
+            this.0.clear_text_path(ObjectId(id));
+            Ok(())
+        });
+
+        methods.add_method("get_text_path_length", |_, this, id: usize| {
+            Ok(this.0.get_text_path_length(ObjectId(id)))
+        });
+
+        methods.add_method_mut("set_max_lines", |_, this, (id, max_lines): (usize, usize)| {
+            this.0.set_max_lines(ObjectId(id), max_lines);
+            Ok(())
+        });
+
+        methods.add_method_mut("measure_text_object", |_, this, (mw, id): (mlua::UserDataRefMut<MoonWalkLuaWrapper>, usize)| {
+            let size = this.0.measure_text_object(mw.get_mut(), ObjectId(id));
+            Ok((size.x, size.y))
+        });
+
         methods.add_method_mut("measure_text", |_, this, (mw, text, font_id, size, max_w): (mlua::UserDataRefMut<MoonWalkLuaWrapper>, String, u64, f32, f32)| {
             let size = this.0.measure_text(mw.get_mut(), &text, FontAsset(font_id), size, max_w);
             Ok((size.x, size.y))
@@ -180,6 +288,8 @@ impl UserData for LuaRenderContainer {
                 TextAlign::Center => "center",
                 TextAlign::Right => "right",
                 TextAlign::Justified => "justified",
+                TextAlign::Start => "start",
+                TextAlign::End => "end",
             };
             
             Ok(align)
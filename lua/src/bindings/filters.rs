@@ -1,11 +1,207 @@
 // Часть проекта MoonWalk с открытым исходным кодом.
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
 
-use mlua::UserDataMethods;
-use glam::{Vec3, Vec4, Mat4};
+use mlua::{UserData, UserDataMethods, UserDataRef, Error, Table, Value};
+use glam::{Vec2, Vec3, Vec4, Mat4};
+use moonwalk::{FilterChain, FilterInput, FilterOp};
 
 use super::MoonWalkLuaWrapper;
 
+// Цепочка фильтров. Шаги задаются именем и таблицей параметров, а
+// set задаёт параметры шага заново:
+//  local chain = mw:new_filter_chain()
+//  local blur = chain:add("blur", { radius = 4 })
+//  chain:add("brightness", { value = 0.6 })
+//  mw:apply_filter_chain(chain, source, target)
+//  chain:set(blur, { radius = 8 })
+pub struct LuaFilterChain {
+    pub chain: FilterChain,
+    names: Vec<String>,
+}
+
+impl UserData for LuaFilterChain {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        // input: nil (предыдущий шаг), "source", { step = n } или { texture = id }
+        methods.add_method_mut("add", |_, this, (name, params, input): (String, Option<Table>, Value)| {
+            let op = filter_op(&this.chain, &name, params)?;
+
+            let step = match filter_input(&this.chain, input)? {
+                Some(input) => this.chain.push_from(input, op),
+                None => this.chain.push(op),
+            };
+
+            this.names.push(name);
+            Ok(step.index())
+        });
+
+        methods.add_method_mut("set", |_, this, (index, params): (usize, Option<Table>)| {
+            let step = this.chain.step(index)
+                .ok_or_else(|| Error::RuntimeError(format!("Filter step {} not found", index)))?;
+
+            let op = filter_op(&this.chain, &this.names[index], params)?;
+            this.chain.set(step, op);
+            Ok(())
+        });
+
+        methods.add_method_mut("set_output", |_, this, index: usize| {
+            let step = this.chain.step(index)
+                .ok_or_else(|| Error::RuntimeError(format!("Filter step {} not found", index)))?;
+
+            this.chain.set_output(step);
+            Ok(())
+        });
+
+        methods.add_method("len", |_, this, ()| Ok(this.chain.len()));
+    }
+}
+
+fn filter_step(chain: &FilterChain, index: usize) -> mlua::Result<FilterInput> {
+    chain.step(index)
+        .map(FilterInput::Step)
+        .ok_or_else(|| Error::RuntimeError(format!("Filter step {} not found", index)))
+}
+
+fn filter_input(chain: &FilterChain, value: Value) -> mlua::Result<Option<FilterInput>> {
+    match value {
+        Value::Nil => Ok(None),
+        Value::String(s) if s.to_str()? == "source" => Ok(Some(FilterInput::Source)),
+
+        Value::Table(table) => {
+            if let Some(step) = table.get::<_, Option<usize>>("step")? {
+                filter_step(chain, step).map(Some)
+            } else if let Some(id) = table.get::<_, Option<u32>>("texture")? {
+                Ok(Some(FilterInput::Texture(id)))
+            } else {
+                Err(Error::RuntimeError("Filter input needs step or texture".into()))
+            }
+        }
+
+        _ => Err(Error::RuntimeError("Filter input must be nil, \"source\" or a table".into())),
+    }
+}
+
+// Операция по имени и таблице параметров, отсутствующие параметры берутся
+// по умолчанию
+fn filter_op(chain: &FilterChain, name: &str, params: Option<Table>) -> mlua::Result<FilterOp> {
+    let get = |key: &str, default: f32| -> mlua::Result<f32> {
+        match &params {
+            Some(table) => Ok(table.get::<_, Option<f32>>(key)?.unwrap_or(default)),
+            None => Ok(default),
+        }
+    };
+
+    let get_bool = |key: &str| -> mlua::Result<bool> {
+        match &params {
+            Some(table) => Ok(table.get::<_, Option<bool>>(key)?.unwrap_or(false)),
+            None => Ok(false),
+        }
+    };
+
+    let get_list = |key: &str| -> mlua::Result<Option<Vec<f32>>> {
+        match &params {
+            Some(table) => table.get(key),
+            None => Ok(None),
+        }
+    };
+
+    // Цвет { r, g, b } или { r, g, b, a }
+    let get_color = |key: &str, default: Vec4| -> mlua::Result<Vec4> {
+        match get_list(key)? {
+            Some(color) if color.len() == 3 => Ok(Vec4::new(color[0], color[1], color[2], 1.0)),
+            Some(color) if color.len() == 4 => Ok(Vec4::from_slice(&color)),
+            Some(_) => Err(Error::RuntimeError(format!("{} needs 3 or 4 values", key))),
+            None => Ok(default),
+        }
+    };
+
+    let op = match name {
+        "blur" => match &params {
+            Some(table) if table.contains_key("horizontal")? => FilterOp::DirectionalBlur {
+                radius: get("radius", 4.0)?,
+                horizontal: get_bool("horizontal")?,
+            },
+
+            _ => FilterOp::blur(get("radius", 4.0)?),
+        },
+
+        "brightness" => FilterOp::brightness(get("value", 1.0)?),
+        "contrast" => FilterOp::contrast(get("value", 1.0)?),
+        "saturation" => FilterOp::saturation(get("value", 1.0)?),
+        "hue_shift" => FilterOp::hue_shift(get("degrees", 0.0)?),
+
+        "chromakey" => {
+            let color = get_list("color")?.unwrap_or_else(|| vec![0.0, 1.0, 0.0]);
+
+            if color.len() < 3 {
+                return Err(Error::RuntimeError("Chromakey color needs 3 values".into()));
+            }
+
+            FilterOp::chromakey(Vec3::new(color[0], color[1], color[2]), get("tolerance", 0.3)?)
+        }
+
+        "color_matrix" => {
+            let matrix = get_list("matrix")?.unwrap_or_else(|| Mat4::IDENTITY.to_cols_array().to_vec());
+            let offset = get_list("offset")?.unwrap_or_else(|| vec![0.0; 4]);
+
+            if matrix.len() != 16 || offset.len() != 4 {
+                return Err(Error::RuntimeError("Invalid matrix or offset size".into()));
+            }
+
+            FilterOp::ColorMatrix {
+                matrix: Mat4::from_cols_slice(&matrix),
+                offset: Vec4::from_slice(&offset),
+            }
+        }
+
+        // { mask_step = n } или { mask_texture = id }, invert
+        "mask" => {
+            let table = params.as_ref()
+                .ok_or_else(|| Error::RuntimeError("Mask needs mask_step or mask_texture".into()))?;
+
+            let mask = if let Some(step) = table.get::<_, Option<usize>>("mask_step")? {
+                filter_step(chain, step)?
+            } else if let Some(id) = table.get::<_, Option<u32>>("mask_texture")? {
+                FilterInput::Texture(id)
+            } else {
+                return Err(Error::RuntimeError("Mask needs mask_step or mask_texture".into()));
+            };
+
+            FilterOp::mask(mask, get_bool("invert")?)
+        }
+
+        "bloom" => FilterOp::Bloom {
+            threshold: get("threshold", 0.8)?,
+            intensity: get("intensity", 1.0)?,
+            radius: get("radius", 2.0)?,
+            levels: get("levels", 4.0)? as u32,
+        },
+
+        // { x, y, radius, color = { r, g, b, a } }
+        "drop_shadow" => FilterOp::drop_shadow(
+            Vec2::new(get("x", 4.0)?, get("y", 4.0)?),
+            get("radius", 4.0)?,
+            get_color("color", Vec4::new(0.0, 0.0, 0.0, 0.6))?,
+        ),
+
+        "sharpen" => FilterOp::sharpen(get("amount", 1.0)?, get("radius", 1.0)?),
+
+        "vignette" => FilterOp::Vignette {
+            strength: get("strength", 0.5)?,
+            radius: get("radius", 0.5)?,
+            softness: get("softness", 0.45)?,
+            color: get_color("color", Vec4::new(0.0, 0.0, 0.0, 1.0))?,
+        },
+
+        "pixelate" => FilterOp::pixelate(get("size", 8.0)?),
+        "film_grain" => FilterOp::film_grain(get("amount", 0.1)?, get("seed", 0.0)?),
+        "edge_detect" => FilterOp::edge_detect(get("strength", 1.0)?),
+
+        _ => return Err(Error::RuntimeError(format!("Unknown filter: {}", name))),
+    };
+
+    Ok(op)
+}
+
 pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mut M) {
     methods.add_method_mut("blur_texture", |_, this, (id, radius, horizontal): (u32, f32, bool)| {
         this.get_mut().blur_texture(id, radius, horizontal);
@@ -53,4 +249,66 @@ pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mu
         this.get_mut().color_matrix(id, mat, off);
         Ok(())
     });
-}
\ No newline at end of file
+
+    methods.add_method_mut("bloom", |_, this, (id, threshold, intensity): (u32, f32, Option<f32>)| {
+        this.get_mut().bloom(id, threshold, intensity.unwrap_or(1.0))
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    // Цвет тени по умолчанию чёрный с непрозрачностью 0.6
+    methods.add_method_mut("drop_shadow", |_, this, (id, x, y, radius, color): (u32, f32, f32, f32, Option<Vec<f32>>)| {
+        let color = match color.as_deref() {
+            None => Vec4::new(0.0, 0.0, 0.0, 0.6),
+            Some([r, g, b]) => Vec4::new(*r, *g, *b, 1.0),
+            Some([r, g, b, a]) => Vec4::new(*r, *g, *b, *a),
+            Some(_) => return Err(Error::RuntimeError("Shadow color needs 3 or 4 values".into())),
+        };
+
+        this.get_mut().drop_shadow(id, Vec2::new(x, y), radius, color)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("sharpen", |_, this, (id, amount, radius): (u32, f32, Option<f32>)| {
+        this.get_mut().sharpen(id, amount, radius.unwrap_or(1.0))
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("vignette", |_, this, (id, strength, radius): (u32, f32, Option<f32>)| {
+        this.get_mut().vignette(id, strength, radius.unwrap_or(0.5))
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("pixelate", |_, this, (id, size): (u32, f32)| {
+        this.get_mut().pixelate(id, size)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("film_grain", |_, this, (id, amount, seed): (u32, f32, Option<f32>)| {
+        this.get_mut().film_grain(id, amount, seed.unwrap_or(0.0))
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("edge_detect", |_, this, (id, strength): (u32, Option<f32>)| {
+        this.get_mut().edge_detect(id, strength.unwrap_or(1.0))
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method("new_filter_chain", |_, _, ()| {
+        Ok(LuaFilterChain { chain: FilterChain::new(), names: Vec::new() })
+    });
+
+    methods.add_method_mut("apply_filter_chain", |_, this, (chain, source, target): (UserDataRef<LuaFilterChain>, u32, u32)| {
+        this.get_mut().apply_filter_chain(&chain.chain, source, target)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("create_filtered_texture", |_, this, (chain, source): (UserDataRef<LuaFilterChain>, u32)| {
+        this.get_mut().create_filtered_texture(&chain.chain, source)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("release_filter_textures", |_, this, ()| {
+        this.get_mut().release_filter_textures();
+        Ok(())
+    });
+}
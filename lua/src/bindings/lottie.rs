@@ -0,0 +1,63 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use mlua::{UserData, UserDataMethods, UserDataRef, Error};
+use glam::Vec2;
+use moonwalk::LottiePlayer;
+
+use super::MoonWalkLuaWrapper;
+
+pub struct LuaLottiePlayer(pub LottiePlayer);
+
+// Плеер живёт в скрипте отдельно от движка, поэтому методы рисования
+// принимают mw первым аргументом:
+//  local player = mw:load_lottie("loader.json")
+//  player:play()
+//  if player:update(dt) then player:render_to_texture(mw, 256, 256) end
+impl UserData for LuaLottiePlayer {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method_mut("play", |_, this, ()| { this.0.play(); Ok(()) });
+        methods.add_method_mut("pause", |_, this, ()| { this.0.pause(); Ok(()) });
+        methods.add_method_mut("stop", |_, this, ()| { this.0.stop(); Ok(()) });
+        methods.add_method_mut("seek", |_, this, frame: f32| { this.0.seek(frame); Ok(()) });
+        methods.add_method_mut("seek_time", |_, this, seconds: f32| { this.0.seek_time(seconds); Ok(()) });
+        methods.add_method_mut("set_loop", |_, this, looping: bool| { this.0.set_loop(looping); Ok(()) });
+        methods.add_method_mut("set_speed", |_, this, speed: f32| { this.0.set_speed(speed); Ok(()) });
+        methods.add_method_mut("update", |_, this, dt: f32| Ok(this.0.update(dt)));
+
+        methods.add_method("is_playing", |_, this, ()| Ok(this.0.is_playing()));
+        methods.add_method("get_frame", |_, this, ()| Ok(this.0.frame()));
+        methods.add_method("get_frame_range", |_, this, ()| Ok(this.0.frame_range()));
+        methods.add_method("get_duration", |_, this, ()| Ok(this.0.duration()));
+
+        methods.add_method("get_size", |_, this, ()| {
+            let size = this.0.size();
+            Ok((size.x, size.y))
+        });
+
+        methods.add_method_mut("render_to_texture", |_, this, (mw, w, h): (UserDataRef<MoonWalkLuaWrapper>, u32, u32)| {
+            Ok(this.0.render_to_texture(mw.get_mut(), w, h))
+        });
+
+        methods.add_method_mut("render_to_objects", |_, this, (mw, x, y, w, h, z): (UserDataRef<MoonWalkLuaWrapper>, f32, f32, f32, f32, Option<f32>)| {
+            this.0.render_to_objects(mw.get_mut(), Vec2::new(x, y), Vec2::new(w, h), z.unwrap_or(0.0));
+            Ok(())
+        });
+
+        methods.add_method("get_objects", |_, this, ()| {
+            Ok(this.0.objects().iter().map(|id| id.0).collect::<Vec<usize>>())
+        });
+
+        methods.add_method_mut("clear", |_, this, mw: UserDataRef<MoonWalkLuaWrapper>| {
+            this.0.clear(mw.get_mut());
+            Ok(())
+        });
+    }
+}
+
+pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mut M) {
+    methods.add_method("load_lottie", |_, this, path: String| {
+        let player = this.get().load_lottie(&path).map_err(|e| Error::RuntimeError(e.to_string()))?;
+        Ok(LuaLottiePlayer(player))
+    });
+}
@@ -8,6 +8,9 @@ pub mod brush;
 pub mod path;
 pub mod container;
 pub mod lunar;
+pub mod lottie;
+pub mod atlas;
+pub mod animated;
 
 use mlua::UserData;
 use moonwalk::MoonWalk;
@@ -34,6 +37,9 @@ impl UserData for MoonWalkLuaWrapper {
         path::register(methods);
         container::register(methods);
         lunar::register(methods);
+        lottie::register(methods);
+        atlas::register(methods);
+        animated::register(methods);
 
         methods.add_method_mut("set_viewport", |_, this, (w, h): (u32, u32)| {
             this.get_mut().set_viewport(w, h);
@@ -5,9 +5,21 @@ use mlua::UserDataMethods;
 use glam::{Vec2, Vec4};
 use moonwalk::objects::ObjectId;
 use moonwalk::TextAlign;
+use moonwalk::Overflow;
+use moonwalk::FontStyle;
+use moonwalk::TextDirection;
+use std::collections::HashMap;
 
 use super::MoonWalkLuaWrapper;
 
+fn overflow(name: &str) -> Overflow {
+    match name {
+        "clip" => Overflow::Clip,
+        "ellipsis" => Overflow::Ellipsis,
+        _ => Overflow::Visible,
+    }
+}
+
 pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mut M) {
     methods.add_method_mut("new_rect", |_, this, ()| {
         Ok(this.get_mut().new_rect().0)
@@ -113,6 +125,8 @@ pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mu
             "center" => TextAlign::Center,
             "right" => TextAlign::Right,
             "justified" => TextAlign::Justified,
+            "start" => TextAlign::Start,
+            "end" => TextAlign::End,
             _ => TextAlign::Left,
         };
 
@@ -120,11 +134,82 @@ pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mu
         Ok(())
     });
 
+    methods.add_method_mut("set_line_height", |_, this, (id, line_height): (usize, f32)| {
+        this.get_mut().set_line_height(ObjectId(id), line_height);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_letter_spacing", |_, this, (id, spacing): (usize, f32)| {
+        this.get_mut().set_letter_spacing(ObjectId(id), spacing);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_font_weight", |_, this, (id, weight): (usize, u16)| {
+        this.get_mut().set_font_weight(ObjectId(id), weight);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_font_style", |_, this, (id, style_str): (usize, String)| {
+        let style = match style_str.as_str() {
+            "italic" => FontStyle::Italic,
+            "oblique" => FontStyle::Oblique,
+            _ => FontStyle::Normal,
+        };
+
+        this.get_mut().set_font_style(ObjectId(id), style);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_font_features", |_, this, (id, features): (usize, Vec<String>)| {
+        let features: Vec<&str> = features.iter().map(|s| s.as_str()).collect();
+        this.get_mut().set_font_features(ObjectId(id), &features);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_font_variations", |_, this, (id, axes): (usize, HashMap<String, f32>)| {
+        let axes: Vec<(&str, f32)> = axes.iter().map(|(axis, value)| (axis.as_str(), *value)).collect();
+        this.get_mut().set_font_variations(ObjectId(id), &axes);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_text_overflow", |_, this, (id, overflow_str): (usize, String)| {
+        this.get_mut().set_text_overflow(ObjectId(id), overflow(&overflow_str));
+        Ok(())
+    });
+
+    methods.add_method_mut("set_text_direction", |_, this, (id, direction_str): (usize, String)| {
+        let direction = match direction_str.as_str() {
+            "ltr" => TextDirection::Ltr,
+            "rtl" => TextDirection::Rtl,
+            _ => TextDirection::Auto,
+        };
+
+        this.get_mut().set_text_direction(ObjectId(id), direction);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_max_lines", |_, this, (id, max_lines): (usize, usize)| {
+        this.get_mut().set_max_lines(ObjectId(id), max_lines);
+        Ok(())
+    });
+
+    methods.add_method_mut("measure_text_object", |_, this, id: usize| {
+        let size = this.get_mut().measure_text_object(ObjectId(id));
+        Ok((size.x, size.y))
+    });
+
     methods.add_method_mut("measure_text", |_, this, (text, font_id, size, max_w): (String, u64, f32, f32)| {
         let size = this.get_mut().measure_text(&text, moonwalk::FontAsset(font_id), size, max_w);
         Ok((size.x, size.y))
     });
 
+    // Размер с лимитом строк и переполнением как в set_max_lines и set_text_overflow
+    methods.add_method_mut("measure_text_truncated", |_, this, (text, font_id, size, max_w, max_lines, overflow_str): (String, u64, f32, f32, usize, String)| {
+        let font = moonwalk::FontAsset(font_id);
+        let size = this.get_mut().measure_text_truncated(&text, font, size, max_w, max_lines, overflow(&overflow_str));
+        Ok((size.x, size.y))
+    });
+
     methods.add_method_mut("set_hit_group", |_, this, (id, group): (usize, u16)| {
         this.get_mut().set_hit_group(ObjectId(id), group);
         Ok(())
@@ -191,6 +276,8 @@ pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mu
             TextAlign::Center => "center",
             TextAlign::Right => "right",
             TextAlign::Justified => "justified",
+            TextAlign::Start => "start",
+            TextAlign::End => "end",
         };
         
         Ok(align)
@@ -1,14 +1,71 @@
 // Часть проекта MoonWalk с открытым исходным кодом.
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
 
-use mlua::{UserData, UserDataMethods, Error};
-use glam::Vec4;
+use mlua::{UserData, UserDataMethods, Error, Table};
+use glam::{Vec2, Vec4};
 use moonwalk::path::PathBuilder;
-use moonwalk::path::{LineCap, LineJoin, FillRule};
+use moonwalk::path::{LineCap, LineJoin, FillRule, AaMode, Paint, GradientStop, BooleanOp};
+use moonwalk::objects::ObjectId;
+use moonwalk::TextPathOptions;
 
 use super::MoonWalkLuaWrapper;
 
-pub struct LuaPathBuilder(Option<PathBuilder>);
+pub struct LuaPathBuilder(pub(crate) Option<PathBuilder>);
+
+// Заливка из таблицы. Примеры:
+//  { type = "solid", r = 1, g = 0, b = 0, a = 1 }
+//  { type = "linear", x0 = 0, y0 = 0, x1 = 100, y1 = 0, stops = { {0, 1, 0, 0, 1}, {1, 0, 0, 1, 1} } }
+//  { type = "radial", cx = 50, cy = 50, radius = 50, stops = { ... } }
+//  { type = "conic", cx = 50, cy = 50, angle = 0, stops = { ... } }
+//  { type = "texture", texture = id, x = 0, y = 0, w = 16, h = 16 }
+fn paint_from_table(t: Table) -> mlua::Result<Paint> {
+    let kind: String = t.get("type")?;
+    let num = |key: &str| -> mlua::Result<f32> { Ok(t.get::<_, Option<f32>>(key)?.unwrap_or(0.0)) };
+
+    let stops = || -> mlua::Result<Vec<GradientStop>> {
+        let list: Option<Vec<Vec<f32>>> = t.get("stops")?;
+
+        Ok(list.unwrap_or_default().iter()
+            .filter(|s| s.len() >= 5)
+            .map(|s| GradientStop::new(s[0], Vec4::new(s[1], s[2], s[3], s[4])))
+            .collect())
+    };
+
+    let paint = match kind.as_str() {
+        "linear" => Paint::Linear {
+            start: Vec2::new(num("x0")?, num("y0")?),
+            end: Vec2::new(num("x1")?, num("y1")?),
+            stops: stops()?,
+        },
+
+        "radial" => Paint::Radial {
+            center: Vec2::new(num("cx")?, num("cy")?),
+            radius: num("radius")?,
+            stops: stops()?,
+        },
+
+        "conic" => Paint::Conic {
+            center: Vec2::new(num("cx")?, num("cy")?),
+            angle: num("angle")?,
+            stops: stops()?,
+        },
+
+        "texture" => Paint::Texture {
+            texture_id: t.get("texture")?,
+            origin: Vec2::new(num("x")?, num("y")?),
+            size: Vec2::new(num("w")?, num("h")?),
+        },
+
+        _ => Paint::Solid(Vec4::new(
+            num("r")?,
+            num("g")?,
+            num("b")?,
+            t.get::<_, Option<f32>>("a")?.unwrap_or(1.0),
+        )),
+    };
+
+    Ok(paint)
+}
 
 impl UserData for LuaPathBuilder {
     fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
@@ -28,6 +85,82 @@ impl UserData for LuaPathBuilder {
         });
 
         methods.add_method_mut("set_stroke", |_, this, w| get_b(this, |b| b.set_stroke(w)));
+
+        methods.add_method_mut("set_dash", |_, this, (pattern, offset): (Vec<f32>, Option<f32>)| {
+            get_b(this, |b| b.set_dash(&pattern, offset.unwrap_or(0.0)))
+        });
+
+        methods.add_method_mut("trim", |_, this, (start, end): (f32, f32)| get_b(this, |b| b.trim(start, end)));
+
+        // Измерение пути. Каждый вызов измеряет путь заново, для частых
+        // выборок по длинному пути лучше кэшировать результат в скрипте
+        methods.add_method_mut("get_length", |_, this, ()| get_b(this, |b| b.measure().length()));
+
+        // Точка и касательная на расстоянии distance таблицей { x, y, tx, ty },
+        // nil если путь пустой
+        methods.add_method_mut("sample_at", |lua, this, distance: f32| {
+            let Some((point, tangent)) = get_b(this, |b| b.measure().sample(distance))? else {
+                return Ok(None);
+            };
+
+            let table = lua.create_table()?;
+            table.set("x", point.x)?;
+            table.set("y", point.y)?;
+            table.set("tx", tangent.x)?;
+            table.set("ty", tangent.y)?;
+
+            Ok(Some(table))
+        });
+
+        methods.add_method_mut("split_at", |_, this, distance: f32| {
+            let (head, tail) = get_b(this, |b| b.measure().split(distance))?;
+            Ok((LuaPathBuilder(Some(head)), LuaPathBuilder(Some(tail))))
+        });
+
+        methods.add_method_mut("segment", |_, this, (start, end): (f32, f32)| {
+            let piece = get_b(this, |b| b.measure().segment(start, end))?;
+            Ok(LuaPathBuilder(Some(piece)))
+        });
+
+        // Булевы операции и геометрия. Исходные билдеры не расходуются,
+        // результат это новый билдер
+        fn boolean(this: &LuaPathBuilder, other: &LuaPathBuilder, op: BooleanOp) -> mlua::Result<LuaPathBuilder> {
+            match (&this.0, &other.0) {
+                (Some(a), Some(b)) => Ok(LuaPathBuilder(Some(a.boolean(b, op)))),
+                _ => Err(Error::RuntimeError("PathBuilder consumed".into())),
+            }
+        }
+
+        methods.add_method("union", |_, this, other: mlua::UserDataRef<LuaPathBuilder>| boolean(this, &other, BooleanOp::Union));
+        methods.add_method("intersection", |_, this, other: mlua::UserDataRef<LuaPathBuilder>| boolean(this, &other, BooleanOp::Intersection));
+        methods.add_method("difference", |_, this, other: mlua::UserDataRef<LuaPathBuilder>| boolean(this, &other, BooleanOp::Difference));
+        methods.add_method("xor", |_, this, other: mlua::UserDataRef<LuaPathBuilder>| boolean(this, &other, BooleanOp::Xor));
+
+        methods.add_method_mut("offset", |_, this, (distance, join): (f32, Option<String>)| {
+            let join = match join.as_deref() {
+                Some("round") => LineJoin::Round,
+                Some("bevel") => LineJoin::Bevel,
+                _ => LineJoin::Miter
+            };
+
+            get_b(this, |b| LuaPathBuilder(Some(b.offset(distance, join))))
+        });
+
+        methods.add_method_mut("simplify", |_, this, tolerance: f32| {
+            get_b(this, |b| LuaPathBuilder(Some(b.simplify(tolerance))))
+        });
+
+        methods.add_method_mut("to_svg", |_, this, ()| get_b(this, |b| b.to_svg_path_string()));
+
+        methods.add_method_mut("set_fill_paint", |_, this, t: Table| {
+            let paint = paint_from_table(t)?;
+            get_b(this, |b| b.set_fill_paint(paint))
+        });
+
+        methods.add_method_mut("set_stroke_paint", |_, this, t: Table| {
+            let paint = paint_from_table(t)?;
+            get_b(this, |b| b.set_stroke_paint(paint))
+        });
         
         methods.add_method_mut("set_line_cap", |_, this, s: String| {
             let cap = match s.as_str() { 
@@ -59,6 +192,15 @@ impl UserData for LuaPathBuilder {
         });
         
         methods.add_method_mut("set_tolerance", |_, this, t: f32| get_b(this, |b| b.set_tolerance(t)));
+
+        methods.add_method_mut("set_antialias", |_, this, s: String| {
+            let mode = match s.as_str() {
+                "none" => AaMode::None,
+                _ => AaMode::Msaa4
+            };
+
+            get_b(this, |b| b.set_antialias(mode))
+        });
     }
 }
 
@@ -83,7 +225,57 @@ pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mu
     
     methods.add_method_mut("tessellate_to", |_, this, (mut builder, tid, w, h): (mlua::UserDataRefMut<LuaPathBuilder>, u32, u32, u32)| {
         let inner = builder.0.take().ok_or(Error::RuntimeError("Consumed".into()))?;
-        inner.tessellate_to(this.get_mut(), tid, w, h);
+        inner.tessellate_to(this.get_mut(), tid, w, h)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("new_path", |_, this, mut builder: mlua::UserDataRefMut<LuaPathBuilder>| {
+        let inner = builder.0.take().ok_or(Error::RuntimeError("Consumed".into()))?;
+        Ok(this.get_mut().new_path(inner).0)
+    });
+
+    methods.add_method_mut("set_path", |_, this, (id, mut builder): (usize, mlua::UserDataRefMut<LuaPathBuilder>)| {
+        let inner = builder.0.take().ok_or(Error::RuntimeError("Consumed".into()))?;
+        this.get_mut().set_path(ObjectId(id), inner);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_text_path", |_, this, (id, mut builder, start_offset, baseline_offset): (usize, mlua::UserDataRefMut<LuaPathBuilder>, Option<f32>, Option<f32>)| {
+        let inner = builder.0.take().ok_or(Error::RuntimeError("Consumed".into()))?;
+        let options = TextPathOptions {
+            start_offset: start_offset.unwrap_or(0.0),
+            baseline_offset: baseline_offset.unwrap_or(0.0),
+        };
+
+        this.get_mut().set_text_path(ObjectId(id), inner, options);
+        Ok(())
+    });
+
+    methods.add_method_mut("set_text_path_svg", |_, this, (id, data, start_offset, baseline_offset): (usize, String, Option<f32>, Option<f32>)| {
+        let options = TextPathOptions {
+            start_offset: start_offset.unwrap_or(0.0),
+            baseline_offset: baseline_offset.unwrap_or(0.0),
+        };
+
+        this.get_mut().set_text_path_svg(ObjectId(id), &data, options).map_err(Error::RuntimeError)
+    });
+
+    methods.add_method_mut("set_text_path_options", |_, this, (id, start_offset, baseline_offset): (usize, f32, Option<f32>)| {
+        let options = TextPathOptions {
+            start_offset,
+            baseline_offset: baseline_offset.unwrap_or(0.0),
+        };
+
+        this.get_mut().set_text_path_options(ObjectId(id), options);
+        Ok(())
+    });
+
+    methods.add_method_mut("clear_text_path", |_, this, id: usize| {
+        this.get_mut().clear_text_path(ObjectId(id));
         Ok(())
     });
+
+    methods.add_method("get_text_path_length", |_, this, id: usize| {
+        Ok(this.get().get_text_path_length(ObjectId(id)))
+    });
 }
\ No newline at end of file
@@ -1,21 +1,197 @@
 // Часть проекта MoonWalk с открытым исходным кодом.
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
 
-use mlua::{UserDataMethods, Error};
+use mlua::{UserData, UserDataMethods, Error, Lua, Table};
+use glam::Vec2;
+use moonwalk::{SvgObject, TextureHandle, TextureOptions, TextureFilter, TextureWrap, TextureFormat};
+use moonwalk::vfs::MountId;
 
 use super::MoonWalkLuaWrapper;
 
+// Список объектов из svg в виде таблиц { name, id, texture }
+fn svg_objects_table<'lua>(lua: &'lua Lua, objects: Vec<SvgObject>) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+
+    for (i, svg) in objects.into_iter().enumerate() {
+        let entry = lua.create_table()?;
+        entry.set("name", svg.name)?;
+        entry.set("id", svg.object.0)?;
+        entry.set("texture", svg.texture_id)?;
+        table.set(i + 1, entry)?;
+    }
+
+    Ok(table)
+}
+
+// Параметры текстуры из таблицы { filter = "nearest", mipmaps = true,
+// wrap = "repeat", wrap_u = "mirror", wrap_v = "clamp", srgb = false }
+fn texture_options(table: Option<Table>) -> mlua::Result<TextureOptions> {
+    let mut options = TextureOptions::default();
+
+    let Some(table) = table else {
+        return Ok(options);
+    };
+
+    let wrap = |name: String| match name.as_str() {
+        "repeat" => TextureWrap::Repeat,
+        "mirror" => TextureWrap::MirrorRepeat,
+        _ => TextureWrap::ClampToEdge,
+    };
+
+    if let Some(filter) = table.get::<_, Option<String>>("filter")? {
+        options.filter = match filter.as_str() {
+            "nearest" => TextureFilter::Nearest,
+            _ => TextureFilter::Linear,
+        };
+    }
+
+    if let Some(mode) = table.get::<_, Option<String>>("wrap")? {
+        options = options.wrap(wrap(mode));
+    }
+
+    if let Some(mode) = table.get::<_, Option<String>>("wrap_u")? {
+        options.wrap_u = wrap(mode);
+    }
+
+    if let Some(mode) = table.get::<_, Option<String>>("wrap_v")? {
+        options.wrap_v = wrap(mode);
+    }
+
+    if let Some(mipmaps) = table.get::<_, Option<bool>>("mipmaps")? {
+        options.mipmaps = mipmaps;
+    }
+
+    if let Some(srgb) = table.get::<_, Option<bool>>("srgb")? {
+        options.srgb = srgb;
+    }
+
+    Ok(options)
+}
+
+pub struct LuaTextureHandle(pub TextureHandle);
+
+// Ручка фоновой загрузки текстуры:
+//  local bg = mw:load_texture_background("background.png")
+//  mw:set_texture(rect, bg:id())
+//  if bg:is_ready() then ... end
+impl UserData for LuaTextureHandle {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("id", |_, this, ()| Ok(this.0.id()));
+        methods.add_method("is_ready", |_, this, ()| Ok(this.0.is_ready()));
+        methods.add_method("is_finished", |_, this, ()| Ok(this.0.is_finished()));
+        methods.add_method("error", |_, this, ()| Ok(this.0.error()));
+    }
+}
+
 pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mut M) {
-    methods.add_method_mut("load_texture", |_, this, path: String| {
-        this.get_mut().load_texture(&path)
+    // Вторым аргументом можно передать параметры, см. texture_options
+    methods.add_method_mut("load_texture", |_, this, (path, options): (String, Option<Table>)| {
+        let options = texture_options(options)?;
+
+        this.get_mut().load_texture_with_options(&path, options)
             .map_err(|e| Error::RuntimeError(e.to_string()))
     });
 
+    // Формат "rgba8" (по умолчанию), "rgba8_linear", "r8", "rg8" или "rgba16f"
+    methods.add_method_mut("create_texture", |_, this, (w, h, format): (u32, u32, Option<String>)| {
+        let format = match format.as_deref() {
+            Some("rgba8_linear") => TextureFormat::Rgba8Unorm,
+            Some("r8") => TextureFormat::R8Unorm,
+            Some("rg8") => TextureFormat::Rg8Unorm,
+            Some("rgba16f") => TextureFormat::Rgba16Float,
+            _ => TextureFormat::Rgba8UnormSrgb,
+        };
+
+        this.get_mut().create_texture(w, h, format)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    // Пиксели передаются строкой байт, например из string.pack или string.rep
+    methods.add_method_mut("update_texture_region", |_, this, (id, x, y, w, h, data): (u32, u32, u32, u32, u32, mlua::String)| {
+        this.get_mut().update_texture_region(id, x, y, w, h, data.as_bytes())
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("update_texture", |_, this, (id, data): (u32, mlua::String)| {
+        this.get_mut().update_texture(id, data.as_bytes())
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    // Вторым аргументом можно передать параметры как в load_texture
+    methods.add_method_mut("load_texture_background", |_, this, (path, options): (String, Option<Table>)| {
+        let options = texture_options(options)?;
+        Ok(LuaTextureHandle(this.get_mut().load_texture_background_with_options(&path, options)))
+    });
+
     methods.add_method_mut("remove_texture", |_, this, tex_id: u32| {
         this.get_mut().remove_texture(tex_id);
         Ok(())
     });
 
+    // Расход видеопамяти в байтах таблицей { textures, texture_count, total, ... }
+    methods.add_method("memory_stats", |lua, this, ()| {
+        let stats = this.get().memory_stats();
+        let table = lua.create_table()?;
+
+        table.set("textures", stats.textures)?;
+        table.set("texture_count", stats.texture_count)?;
+        table.set("evicted_textures", stats.evicted_textures)?;
+        table.set("render_targets", stats.render_targets)?;
+        table.set("filters", stats.filters)?;
+        table.set("glyph_atlases", stats.glyph_atlases)?;
+        table.set("staging", stats.staging)?;
+        table.set("budget", stats.budget)?;
+        table.set("evictions", stats.evictions)?;
+        table.set("reloads", stats.reloads)?;
+        table.set("total", stats.total())?;
+
+        Ok(table)
+    });
+
+    // Бюджет видеопамяти в байтах, nil выключает
+    methods.add_method_mut("set_texture_budget", |_, this, bytes: Option<u64>| {
+        this.get_mut().set_texture_budget(bytes);
+        Ok(())
+    });
+
+    methods.add_method_mut("restore_texture", |_, this, tex_id: u32| {
+        Ok(this.get_mut().restore_texture(tex_id))
+    });
+
+    methods.add_method_mut("load_svg", |_, this, (path, w, h): (String, Option<f32>, Option<f32>)| {
+        let size = Vec2::new(w.unwrap_or(0.0), h.unwrap_or(0.0));
+        this.get_mut().load_svg(&path, size)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    // svg документ строкой, например из файла прочитанного через io
+    methods.add_method_mut("load_svg_data", |_, this, (data, w, h): (mlua::String, Option<f32>, Option<f32>)| {
+        let size = Vec2::new(w.unwrap_or(0.0), h.unwrap_or(0.0));
+        this.get_mut().load_svg_from_bytes(data.as_bytes(), size)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("load_svg_objects", |lua, this, (path, w, h): (String, Option<f32>, Option<f32>)| {
+        let size = Vec2::new(w.unwrap_or(0.0), h.unwrap_or(0.0));
+        let objects = this.get_mut().load_svg_objects(&path, size)
+            .map_err(|e| Error::RuntimeError(e.to_string()))?;
+
+        svg_objects_table(lua, objects)
+    });
+
+    methods.add_method_mut("load_svg_objects_data", |lua, this, (data, w, h): (mlua::String, Option<f32>, Option<f32>)| {
+        let size = Vec2::new(w.unwrap_or(0.0), h.unwrap_or(0.0));
+        let objects = this.get_mut().load_svg_objects_from_bytes(data.as_bytes(), size)
+            .map_err(|e| Error::RuntimeError(e.to_string()))?;
+
+        svg_objects_table(lua, objects)
+    });
+
+    methods.add_method_mut("resize_svg", |_, this, (tex_id, w, h): (u32, f32, f32)| {
+        this.get_mut().resize_svg(tex_id, Vec2::new(w, h))
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
     methods.add_method_mut("load_font", |_, this, (path, name): (String, String)| {
         let font_asset = this.get_mut().load_font(&path, &name)
             .map_err(|e| Error::RuntimeError(e.to_string()))?;
@@ -33,4 +209,29 @@ pub fn register<'lua, M: UserDataMethods<'lua, MoonWalkLuaWrapper>>(methods: &mu
             None => Ok(None),
         }
     });
-}
\ No newline at end of file
+
+    // Монтирование папок и архивов. Возвращает айди для unmount
+    methods.add_method_mut("mount_dir", |_, this, (point, dir, priority): (String, String, Option<i32>)| {
+        this.get_mut().resources.mount_dir(&point, &dir, priority.unwrap_or(0))
+            .map(|id| id.0)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("mount_zip", |_, this, (point, path, priority): (String, String, Option<i32>)| {
+        this.get_mut().resources.mount_zip(&point, &path, priority.unwrap_or(0))
+            .map(|id| id.0)
+            .map_err(|e| Error::RuntimeError(e.to_string()))
+    });
+
+    methods.add_method_mut("unmount", |_, this, id: u32| {
+        Ok(this.get_mut().resources.unmount(MountId(id)))
+    });
+
+    methods.add_method("list_files", |_, this, dir: Option<String>| {
+        Ok(this.get().resources.list_files(dir.as_deref().unwrap_or("")))
+    });
+
+    methods.add_method("file_exists", |_, this, path: String| {
+        Ok(this.get().resources.exists(&path))
+    });
+}
@@ -469,6 +469,8 @@ pub struct RenderConfig {
     pub depth_test: bool,
     /// Включить запись глубины
     pub depth_write: bool,
+    /// Количество сэмплов на пиксель (1 без MSAA)
+    pub sample_count: u32,
 }
 
 impl Default for RenderConfig {
@@ -480,6 +482,7 @@ impl Default for RenderConfig {
             topology: Topology::TriangleList,
             depth_test: false,
             depth_write: false,
+            sample_count: 1,
         }
     }
 }
@@ -517,6 +520,12 @@ impl MoonPipeline {
         }
     }
 
+    /// Метод чтобы заменить исходный код шейдера
+    pub fn shader_source(mut self, shader_source: &str) -> Self {
+        self.shader_source = shader_source.to_string();
+        self
+    }
+
     /// Метод чтобы установить точку входа вершинного шейдера
     pub fn vertex_shader(mut self, entry: &str) -> Self {
         self.vertex_shader = entry.to_string();
@@ -577,6 +586,13 @@ impl MoonPipeline {
         self
     }
 
+    /// Установить количество сэмплов на пиксель для MSAA. Без дополнительных
+    /// фич устройства гарантированно поддерживаются только 1 и 4
+    pub fn sample_count(mut self, count: u32) -> Self {
+        self.render_config.sample_count = count.max(1);
+        self
+    }
+
     /// Установить стратегию фалбек
     pub fn fallback_strategy(mut self, strategy: FallbackStrategy) -> Self {
         self.fallback_strategy = strategy;
@@ -593,6 +609,19 @@ impl MoonPipeline {
     /// специфичным для стандартного батчинга (UberBatch) мунволка и
     /// может устареть. При изменении сигнатуры shape.wgsl нужно изменить
     /// и этот метод
+    ///
+    /// [!] С версии 0.58 uv (@location(2)) передаются как vec4<u32> (Uint16x4,
+    /// раньше Unorm16x4) в упаковке ObjectInstance::pack_uv. Шейдеру нужно
+    /// распаковать их так же как shape.wgsl:
+    ///
+    /// ```wgsl
+    /// fn unpack_uv(packed: vec4<u32>) -> vec4<f32> {
+    ///     let wide = (packed & vec4<u32>(0x8000u)) != vec4<u32>(0u);
+    ///     let value = vec4<f32>(packed & vec4<u32>(0x7FFFu));
+    ///
+    ///     return select(value / 32767.0, value / 128.0 - 128.0, wide);
+    /// }
+    /// ```
     pub fn create_rect_instance_layout() -> VertexLayout {
         VertexLayout::new()
             .stride(64)
@@ -602,7 +631,7 @@ impl MoonPipeline {
                 .location(1)
                 .offset(0))
             .add_attr(VertexAttr::new()
-                .format(Format::Unorm16x4)
+                .format(Format::Uint16x4)
                 .location(2)
                 .offset(16))
             .add_attr(VertexAttr::new()
@@ -645,7 +674,7 @@ impl MoonPipeline {
         // Валидация конфигурации
         self.validate()?;
 
-        let cache_key = self.create_cache_key(ctx);
+        let cache_key = self.create_cache_key(ctx, wgpu_format);
 
         if let Some(cached) = PIPELINE_CACHE.lock().get(&cache_key).cloned() {
             return Ok(PipelineResult {
@@ -719,7 +748,7 @@ impl MoonPipeline {
         Ok(())
     }
 
-    fn create_cache_key(&self, _ctx: &Context) -> PipelineCacheKey {
+    fn create_cache_key(&self, _ctx: &Context, wgpu_format: wgpu::TextureFormat) -> PipelineCacheKey {
         use std::hash::{Hash, Hasher};
         use std::collections::hash_map::DefaultHasher;
 
@@ -768,6 +797,11 @@ impl MoonPipeline {
         (self.render_config.topology as u8).hash(&mut hasher);
         self.render_config.depth_test.hash(&mut hasher);
         self.render_config.depth_write.hash(&mut hasher);
+        self.render_config.sample_count.hash(&mut hasher);
+
+        // Один и тот же шейдер может собираться под разные цели (поверхность
+        // окна и текстура), конвейеры для них несовместимы
+        wgpu_format.hash(&mut hasher);
         let format_hash = hasher.finish();
 
         PipelineCacheKey {
@@ -1159,6 +1193,7 @@ impl MoonPipeline {
             Topology::TriangleStrip => wgpu::PrimitiveTopology::TriangleStrip,
         };
         builder = builder.with_topology(topology);
+        builder = builder.with_sample_count(self.render_config.sample_count);
 
         // Тест глубины
         if self.render_config.depth_test {
@@ -1197,6 +1232,15 @@ impl MoonPipeline {
             .ok_or_else(|| MoonWalkError::ShaderError("Reduce strategy failed".into()))
     }
 
+    /// Убирает из кэша пайплайн собранный из этого описания. Нужно перед
+    /// пересборкой из изменённого шейдера, иначе старый вариант так и
+    /// останется в кэше
+    #[cfg(feature = "hot-reload")]
+    pub(crate) fn forget_cached(&self, ctx: &Context, wgpu_format: wgpu::TextureFormat) {
+        let cache_key = self.create_cache_key(ctx, wgpu_format);
+        PIPELINE_CACHE.lock().remove(&cache_key);
+    }
+
     /// Очистить кэш пайплайнов для всех устройств
     pub fn clear_pipeline_cache() {
         PIPELINE_CACHE.lock().clear();
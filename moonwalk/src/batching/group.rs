@@ -3,33 +3,66 @@
 
 use std::collections::HashMap;
 
-use crate::gpu::Context;
+use crate::gpu::{Context, Pipeline};
 use crate::batching::shapes::uber::UberBatch;
+use crate::batching::shapes::path::PathBatch;
 use crate::objects::store::ObjectStore;
 use crate::rendering::texture::Texture;
+use crate::error::MoonWalkError;
 
 pub struct BatchGroup {
     pub objects: UberBatch,
+    pub paths: PathBatch,
 }
 
 impl BatchGroup {
-    pub fn new(ctx: &Context) -> Self {
-        Self {
+    pub fn new(ctx: &Context) -> Result<Self, MoonWalkError> {
+        Ok(Self {
             objects: UberBatch::new(ctx),
-        }
+            paths: PathBatch::new(ctx)?,
+        })
     }
 
-    pub fn prepare(&mut self, ctx: &Context, store: &ObjectStore, text_engine: &mut crate::textware::TextWare) {
-        self.objects.prepare(ctx, store, text_engine);
+    pub fn prepare(
+        &mut self,
+        ctx: &Context,
+        store: &ObjectStore,
+        text_engine: &mut crate::textware::TextWare,
+        white_texture: &Texture,
+        textures: &HashMap<u32, Texture>,
+    ) {
+        self.paths.prepare(ctx, store, white_texture, textures);
+
+        let path_z: Vec<f32> = self.paths.order().iter().map(|(z, _)| *z).collect();
+        self.objects.prepare(ctx, store, text_engine, &path_z);
     }
 
+    /// Рисует все объекты по z индексу. Пути рисуются своим конвейером между
+    /// командами батча прямоугольников
     pub fn render<'a>(
         &'a self,
         pass: &mut crate::gpu::RenderPass<'a>,
+        rect_pipeline: &'a Pipeline,
         white_texture: &'a Texture,
         textures: &'a HashMap<u32, Texture>,
         atlas_bg: Option<&'a wgpu::BindGroup>,
+        sdf_bg: Option<&'a wgpu::BindGroup>,
     ) {
-        self.objects.render(pass, white_texture, textures, atlas_bg);
+        let mut start = 0;
+
+        for (&command, &(_, idx)) in self.objects.path_breaks().iter().zip(self.paths.order()) {
+            let command = command as usize;
+
+            if command > start {
+                pass.set_pipeline(rect_pipeline);
+                self.objects.render_range(pass, start..command, white_texture, textures, atlas_bg, sdf_bg);
+                start = command;
+            }
+
+            self.paths.render(pass, idx, white_texture, textures);
+        }
+
+        pass.set_pipeline(rect_pipeline);
+        self.objects.render_range(pass, start..self.objects.command_count(), white_texture, textures, atlas_bg, sdf_bg);
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,5 @@
 // Часть проекта MoonWalk с открытым исходным кодом.
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2025 MoonWalk
 
-pub mod uber;
\ No newline at end of file
+pub mod uber;
+pub mod path;
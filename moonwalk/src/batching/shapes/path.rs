@@ -0,0 +1,218 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Векторные пути как объекты сцены. Геометрия каждого пути лежит в своих
+// вершинных буферах и перезаливается только при смене геометрии (set_path),
+// а позиция, размер, вращение и цвет объекта идут через юниформ слоя.
+// Пути рисуются в основном проходе вперемешку с прямоугольниками в порядке
+// z индекса, поэтому сглаживания MSAA у них нет
+
+use std::collections::HashMap;
+
+use glam::{Mat2, Vec2};
+
+use crate::gpu::{Buffer, Context, Pipeline, RenderPass};
+use crate::objects::ObjectType;
+use crate::objects::store::ObjectStore;
+use crate::path::{create_path_layouts, create_path_pipeline, AaMode, Paint, PathShape, PathUniform, VectorVertex};
+use crate::rendering::texture::Texture;
+use crate::r#abstract::*;
+use crate::error::MoonWalkError;
+
+struct GpuLayer {
+    vertex_buffer: Buffer<VectorVertex>,
+    index_buffer: Buffer<u16>,
+    uniform_buffer: Buffer<PathUniform>,
+    uniform_bind_group: wgpu::BindGroup,
+    paint: Paint,
+}
+
+struct GpuPath {
+    revision: u64,
+    layers: Vec<GpuLayer>,
+}
+
+pub struct PathBatch {
+    pipeline: Pipeline,
+    uniform_layout: wgpu::BindGroupLayout,
+
+    // Буферы путей по индексу объекта в хранилище
+    paths: HashMap<usize, GpuPath>,
+
+    // Живые пути отсортированные по z индексу: (z, индекс объекта)
+    order: Vec<(f32, usize)>,
+}
+
+impl PathBatch {
+    pub fn new(ctx: &Context) -> Result<Self, MoonWalkError> {
+        // Раскладка группы 0 совпадает с проекцией из ShaderStore, так что
+        // бинд группа проекции основного прохода подходит и путям
+        let proj_layout = BindGroup::new()
+            .add_uniform(0, ShaderStage::Vertex)
+            .build(ctx)?;
+
+        let (texture_layout, uniform_layout) = create_path_layouts(ctx)?;
+
+        let pipeline = create_path_pipeline(
+            ctx,
+            ctx.config.format,
+            AaMode::None,
+            &[&proj_layout, &texture_layout, &uniform_layout]
+        )?;
+
+        Ok(Self {
+            pipeline,
+            uniform_layout,
+            paths: HashMap::new(),
+            order: Vec::new(),
+        })
+    }
+
+    /// Живые пути в порядке отрисовки (z индекс и индекс объекта)
+    pub fn order(&self) -> &[(f32, usize)] {
+        &self.order
+    }
+
+    pub fn prepare(
+        &mut self,
+        ctx: &Context,
+        store: &ObjectStore,
+        white_texture: &Texture,
+        textures: &HashMap<u32, Texture>,
+    ) {
+        if !store.dirty {
+            return;
+        }
+
+        self.order.clear();
+
+        for &global_id in store.path_ids.iter() {
+            let idx = global_id.index();
+
+            // Слот мог перейти к объекту другого типа после удаления пути
+            if !store.alive[idx] || store.object_types[idx] != ObjectType::Path {
+                continue;
+            }
+
+            let Some(shape) = store.path_shapes[idx].as_deref() else {
+                continue;
+            };
+
+            let outdated = self.paths.get(&idx).is_none_or(|gpu| gpu.revision != shape.revision);
+
+            if outdated {
+                let gpu = self.upload(ctx, shape);
+                self.paths.insert(idx, gpu);
+            }
+
+            let uniform = object_uniform(store, idx, shape);
+
+            for layer in &self.paths[&idx].layers {
+                let texture = layer.paint.texture_id()
+                    .and_then(|id| textures.get(&id))
+                    .unwrap_or(white_texture);
+
+                let texture_size = Vec2::new(texture.texture.width() as f32, texture.texture.height() as f32);
+
+                layer.uniform_buffer.update_one(ctx, &PathUniform {
+                    paint: layer.paint.to_uniform(texture_size),
+                    ..uniform
+                });
+            }
+
+            self.order.push((store.z_indices[idx], idx));
+        }
+
+        // Один слот может попасть в path_ids несколько раз при перерождении
+        self.order.sort_unstable_by_key(|(_, idx)| *idx);
+        self.order.dedup_by_key(|(_, idx)| *idx);
+        self.order.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Буферы удалённых путей больше не нужны
+        let order = &self.order;
+        self.paths.retain(|idx, _| order.iter().any(|(_, i)| i == idx));
+    }
+
+    // Создаёт буферы для всех непустых слоёв пути
+    fn upload(&self, ctx: &Context, shape: &PathShape) -> GpuPath {
+        let mut layers = Vec::with_capacity(shape.layers.len());
+
+        for (geometry, paint) in &shape.layers {
+            if geometry.vertices.is_empty() || geometry.indices.is_empty() {
+                continue;
+            }
+
+            let uniform_buffer = Buffer::uniform(ctx, &PathUniform::identity(paint.to_uniform(Vec2::ONE)));
+
+            let uniform_bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.uniform_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.raw.as_entire_binding(),
+                }],
+                label: Some("Path Uniforms"),
+            });
+
+            layers.push(GpuLayer {
+                vertex_buffer: Buffer::vertex(ctx, &geometry.vertices),
+                index_buffer: Buffer::<u16>::index_u16(ctx, &geometry.indices),
+                uniform_buffer,
+                uniform_bind_group,
+                paint: paint.clone(),
+            });
+        }
+
+        GpuPath { revision: shape.revision, layers }
+    }
+
+    /// Рисует один путь. Группа 0 (проекция) должна быть уже выставлена
+    pub fn render<'a>(
+        &'a self,
+        pass: &mut RenderPass<'a>,
+        idx: usize,
+        white_texture: &'a Texture,
+        textures: &'a HashMap<u32, Texture>,
+    ) {
+        let Some(gpu) = self.paths.get(&idx) else {
+            return;
+        };
+
+        pass.set_pipeline(&self.pipeline);
+
+        for layer in &gpu.layers {
+            let texture = layer.paint.texture_id()
+                .and_then(|id| textures.get(&id))
+                .unwrap_or(white_texture);
+
+            pass.set_bind_group(1, &texture.bind_group);
+            pass.set_bind_group(2, &layer.uniform_bind_group);
+            pass.set_vertex_buffer(0, &layer.vertex_buffer);
+            pass.set_index_buffer_u16(&layer.index_buffer);
+            pass.draw_indexed(layer.index_buffer.count);
+        }
+    }
+}
+
+// Преобразование рамки пути в прямоугольник объекта: масштаб под размер
+// объекта и вращение вокруг его центра, как у прямоугольников
+fn object_uniform(store: &ObjectStore, idx: usize, shape: &PathShape) -> PathUniform {
+    let position = store.positions[idx];
+    let size = store.sizes[idx];
+    let half = size * 0.5;
+
+    let scale = Vec2::new(
+        if shape.base_size.x > 0.0 { size.x / shape.base_size.x } else { 1.0 },
+        if shape.base_size.y > 0.0 { size.y / shape.base_size.y } else { 1.0 },
+    );
+
+    let rotation = Mat2::from_angle(store.rotations[idx]);
+    let transform = rotation * Mat2::from_diagonal(scale);
+    let translate = position + half - transform * shape.origin - rotation * half;
+
+    PathUniform {
+        transform: transform.to_cols_array(),
+        translate: [translate.x, translate.y, 0.0, 0.0],
+        tint: store.colors[idx].to_array(),
+        paint: bytemuck::Zeroable::zeroed(),
+    }
+}
@@ -5,8 +5,14 @@ use crate::gpu::{Buffer, Context, RenderPass};
 use crate::rendering::vertex::{QuadVertex, ObjectInstance};
 use crate::rendering::texture::Texture;
 use crate::objects::store::ObjectStore;
-use crate::batching::common::BatchBuffer;
-use crate::textware::TextWare;
+use crate::batching::common::{BatchBuffer, SortableInstance};
+use crate::objects::ObjectType;
+use crate::textware::{PlacedGlyph, TextWare};
+use crate::textware::sdf::SDF_BASE_SIZE;
+
+/// Шаг z индекса между слоями эффектов текста. Слои должны быть строго под
+/// основными глифами, так как сортировка батча нестабильная
+const TEXT_EFFECT_Z_STEP: f32 = 0.001;
 
 #[derive(Debug, Clone, Copy)]
 pub struct DrawCommand {
@@ -24,6 +30,9 @@ pub struct UberBatch {
     
     // Сохранение списка команд за кадр
     commands: Vec<DrawCommand>,
+
+    // Для каждого пути (в порядке z) номер команды перед которой он рисуется
+    path_breaks: Vec<u32>,
 }
 
 impl UberBatch {
@@ -52,21 +61,26 @@ impl UberBatch {
             blit_vbo,
             batch: BatchBuffer::new(),
             commands: Vec::with_capacity(32),
+            path_breaks: Vec::new(),
         }
     }
 
-    pub fn prepare(&mut self, ctx: &Context, store: &ObjectStore, text_engine: &mut TextWare) {
+    /// Собирает батч. path_z это отсортированные z индексы путей, команды
+    /// разрезаются на них чтобы пути попали между прямоугольниками
+    pub fn prepare(&mut self, ctx: &Context, store: &ObjectStore, text_engine: &mut TextWare, path_z: &[f32]) {
         if !store.dirty {
             return;
         }
 
         self.batch.clear();
         self.commands.clear();
+        self.path_breaks.clear();
         
         for &global_id in store.rect_ids.iter() {
             let idx = global_id.index();
 
-            if !store.alive[idx] {
+            // Слот мог перейти к объекту другого типа (например пути)
+            if !store.alive[idx] || store.object_types[idx] != ObjectType::Rect {
                 continue;
             }
 
@@ -117,31 +131,74 @@ impl UberBatch {
                     continue;
                 }
 
-                let align = store.text_aligns[idx];
                 let glyphs = text_engine.collect_glyphs(
                     global_id.index() as u64,
                     text,
-                    store.font_ids[idx],
-                    store.font_sizes[idx],
-                    store.text_bounds[idx].x,
-                    store.text_bounds[idx].y,
-                    align,
+                    store.text_params(idx),
+                    store.text_paths[idx].as_deref(),
                 );
 
                 let pos = store.positions[idx];
                 let color = store.colors_cache[idx];
                 let z = store.z_indices[idx];
                 let rot = store.rotations[idx];
+
+                // Режим объекта имеет приоритет, 0 означает что режим берётся
+                // из настроек шрифта
+                let use_sdf = match store.text_render_modes[idx] {
+                    1 => false,
+                    2 => true,
+                    _ => text_engine.font_system.is_sdf(store.font_ids[idx]),
+                };
+
+                let sdf_scale = store.font_sizes[idx] / SDF_BASE_SIZE;
+
+                if store.has_text_effects(idx) {
+                    push_text_effects(&mut self.batch, text_engine, store, idx, &glyphs);
+                }
                 
-                for (gx, gy, key) in glyphs {
-                    if let Some((image, uv_rect)) = text_engine.glyph_cache.get_glyph(key, &mut text_engine.font_system) {
-                        let w = image.placement.width as f32;
-                        let h = image.placement.height as f32;
-                        let left = image.placement.left as f32;
-                        let top = image.placement.top as f32;
+                for placed in glyphs {
+                    let key = placed.key;
+
+                    if use_sdf {
+                        // SDF глиф растеризован один раз в базовом размере,
+                        // поэтому квад просто масштабируется под размер шрифта.
+                        // Цветные глифы (эмодзи) в SDF невозможны, для них
+                        // ниже срабатывает обычный атлас
+                        if let Some(glyph) = text_engine.sdf_cache.get_glyph(key, &mut text_engine.font_system) {
+                            let (u, v, uw, vh) = glyph.uv;
+                            let [x, y, w, h] = glyph_rect(
+                                &placed,
+                                glyph.left * sdf_scale,
+                                glyph.top * sdf_scale,
+                                glyph.width * sdf_scale,
+                                glyph.height * sdf_scale,
+                            );
+
+                            self.batch.push(ObjectInstance {
+                                pos_size: [pos.x + x, pos.y + y, w, h],
+                                uv: ObjectInstance::pack_uv([u, v, uw, vh]),
+                                radii: ObjectInstance::pack_radii([0.0; 4]),
+                                gradient_data: store.gradient_data_cache[idx],
+                                extra: [z, rot + placed.angle],
+                                type_id: crate::textware::SDF_ATLAS_ID,
+                                color,
+                                color2: store.colors2_cache[idx],
+                                effect_data: store.effect_data_cache[idx],
+                            });
+
+                            continue;
+                        }
+                    }
 
-                        let x = pos.x + gx + left;
-                        let y = pos.y + gy - top;
+                    if let Some((image, uv_rect)) = text_engine.glyph_cache.get_glyph(key, &mut text_engine.font_system) {
+                        let [x, y, w, h] = glyph_rect(
+                            &placed,
+                            image.placement.left as f32,
+                            image.placement.top as f32,
+                            image.placement.width as f32,
+                            image.placement.height as f32,
+                        );
 
                         let (u, v, uw, vh) = uv_rect;
                         let uv_arr = [u, v, uw, vh];
@@ -152,13 +209,13 @@ impl UberBatch {
                         // это всё. Просто используем uv координаты и атлас в качестве
                         // текстуры
                         self.batch.push(ObjectInstance {
-                            pos_size: [x, y, w, h],
+                            pos_size: [pos.x + x, pos.y + y, w, h],
                             uv: ObjectInstance::pack_uv(uv_arr),
                             radii: ObjectInstance::pack_radii([0.0; 4]),
                             gradient_data: store.gradient_data_cache[idx],
-                            extra: [z, rot],
+                            extra: [z, rot + placed.angle],
                             type_id: atlas_id, 
-                            color: color,
+                            color,
                             color2: store.colors2_cache[idx],
                             effect_data: store.effect_data_cache[idx],
                         });
@@ -176,15 +233,27 @@ impl UberBatch {
             
             let mut start = 0;
             let mut count = 0;
+            let mut next_path = 0;
 
             for (i, instance) in self.batch.cpu_buffer.iter().enumerate() {
-                // Если текстура сменилась то текущая команда закрывается
-                if instance.type_id != current_tex {
-                    self.commands.push(DrawCommand {
-                        texture_id: current_tex,
-                        start_index: start,
-                        count,
-                    });
+                let z = instance.get_z_index();
+                let path_below = path_z.get(next_path).is_some_and(|pz| *pz < z);
+
+                // Если текстура сменилась или под инстансом должен быть путь
+                // то текущая команда закрывается
+                if instance.type_id != current_tex || path_below {
+                    if count > 0 {
+                        self.commands.push(DrawCommand {
+                            texture_id: current_tex,
+                            start_index: start,
+                            count,
+                        });
+                    }
+
+                    while path_z.get(next_path).is_some_and(|pz| *pz < z) {
+                        self.path_breaks.push(self.commands.len() as u32);
+                        next_path += 1;
+                    }
 
                     // Начинается новая команда
                     current_tex = instance.type_id;
@@ -209,21 +278,55 @@ impl UberBatch {
             }
         }
 
+        // Пути выше всех инстансов рисуются после последней команды
+        while self.path_breaks.len() < path_z.len() {
+            self.path_breaks.push(self.commands.len() as u32);
+        }
+
         self.batch.upload(ctx);
     }
 
+    /// Количество команд отрисовки в текущем кадре
+    pub fn command_count(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Номера команд перед которыми рисуются пути, по одному на путь
+    pub fn path_breaks(&self) -> &[u32] {
+        &self.path_breaks
+    }
+
     pub fn render<'a>(
         &'a self,
         pass: &mut RenderPass<'a>,
         white_texture: &'a Texture,
         textures: &'a std::collections::HashMap<u32, Texture>,
         atlas_bind_group: Option<&'a wgpu::BindGroup>,
+        sdf_bind_group: Option<&'a wgpu::BindGroup>,
+    ) {
+        self.render_range(pass, 0..self.commands.len(), white_texture, textures, atlas_bind_group, sdf_bind_group);
+    }
+
+    /// Рисует часть команд. Нужен чтобы вставлять между ними другие
+    /// конвейеры, конвейер прямоугольников должен быть уже выставлен
+    pub fn render_range<'a>(
+        &'a self,
+        pass: &mut RenderPass<'a>,
+        range: std::ops::Range<usize>,
+        white_texture: &'a Texture,
+        textures: &'a std::collections::HashMap<u32, Texture>,
+        atlas_bind_group: Option<&'a wgpu::BindGroup>,
+        sdf_bind_group: Option<&'a wgpu::BindGroup>,
     ) {
         // Проверка есть ли данные для рендера
-        if self.instance_vbo.is_none() || self.commands.is_empty() {
+        if self.instance_vbo.is_none() || range.is_empty() {
             return;
         }
 
+        let Some(commands) = self.commands.get(range) else {
+            return;
+        };
+
         pass.set_vertex_buffer(0, &self.static_vbo);
         pass.set_vertex_buffer(1, self.instance_vbo.as_ref().unwrap());
         pass.set_index_buffer(&self.static_ibo);
@@ -241,7 +344,7 @@ impl UberBatch {
         // что сэкономить время рендеринга из-за чего приходится жертвовать чистотой
         // кода
 
-        for cmd in &self.commands {
+        for cmd in commands {
             // Хардкод нуля как отсуствия текстуры у объекта
             if cmd.texture_id == 0 {
                 pass.set_bind_group(1, &white_texture.bind_group);
@@ -257,6 +360,14 @@ impl UberBatch {
                     // Если атлас потерялся, рисуем белым (чтобы не крашнулось)
                     pass.set_bind_group(1, &white_texture.bind_group);
                 }
+            } else if cmd.texture_id == crate::textware::SDF_ATLAS_ID {
+                // То же самое для атласа полей расстояний, шейдер отличает
+                // его по u32::MAX - 1
+                if let Some(bg) = sdf_bind_group {
+                    pass.set_bind_group(1, bg);
+                } else {
+                    pass.set_bind_group(1, &white_texture.bind_group);
+                }
             } else {
                 if let Some(tex) = textures.get(&cmd.texture_id) {
                     pass.set_bind_group(1, &tex.bind_group);
@@ -313,4 +424,121 @@ impl UberBatch {
             0,
         );
     }
-}
\ No newline at end of file
+}
+
+/// Прямоугольник квада глифа относительно объекта. Шейдер вращает квад вокруг
+/// его центра, а глиф вдоль пути повёрнут вокруг пера, поэтому центр квада
+/// поворачивается вокруг пера здесь. Без поворота это обычное смещение
+/// битмапа глифа от пера
+fn glyph_rect(glyph: &PlacedGlyph, left: f32, top: f32, w: f32, h: f32) -> [f32; 4] {
+    if glyph.angle == 0.0 {
+        return [glyph.x + left, glyph.y - top, w, h];
+    }
+
+    let (sin, cos) = glyph.angle.sin_cos();
+    let (cx, cy) = (left + w * 0.5, h * 0.5 - top);
+
+    [
+        glyph.x + cx * cos - cy * sin - w * 0.5,
+        glyph.y + cx * sin + cy * cos - h * 0.5,
+        w,
+        h,
+    ]
+}
+
+/// Слой эффекта текста. Расширение и размытие указаны в текселях SDF атласа
+struct TextEffectLayer {
+    offset: glam::Vec2,
+    dilate: f32,
+    blur: f32,
+    color: u32,
+    z: f32,
+}
+
+/// Добавляет в батч слои тени, свечения и обводки текста. Все слои берутся из
+/// SDF атласа независимо от режима рендеринга самого текста: расширение
+/// контура это сдвиг порога поля, а размытие это ширина перехода. Параметры
+/// слоя передаются шейдеру через radii, у глифов они не используются
+fn push_text_effects(
+    batch: &mut BatchBuffer<ObjectInstance>,
+    text_engine: &mut TextWare,
+    store: &ObjectStore,
+    idx: usize,
+    glyphs: &[PlacedGlyph],
+) {
+    let sdf_scale = store.font_sizes[idx] / SDF_BASE_SIZE;
+    if sdf_scale <= 0.0 {
+        return;
+    }
+
+    let z = store.z_indices[idx];
+    let outline_width = store.text_outlines[idx].map_or(0.0, |(width, _)| width.max(0.0));
+
+    // Слои идут снизу вверх: тень, свечение, обводка
+    let mut layers = Vec::with_capacity(3);
+
+    if let Some((offset, blur, color)) = store.text_shadows[idx] {
+        // Тень отбрасывает весь контур вместе с обводкой
+        layers.push(TextEffectLayer {
+            offset,
+            dilate: outline_width / sdf_scale,
+            blur: blur.max(0.0) / sdf_scale,
+            color: ObjectInstance::pack_color(color.to_array()),
+            z: z - TEXT_EFFECT_Z_STEP * 3.0,
+        });
+    }
+
+    if let Some((radius, color)) = store.text_glows[idx] {
+        let radius = radius.max(0.0) / sdf_scale;
+
+        layers.push(TextEffectLayer {
+            offset: glam::Vec2::ZERO,
+            dilate: outline_width / sdf_scale + radius * 0.5,
+            blur: radius,
+            color: ObjectInstance::pack_color(color.to_array()),
+            z: z - TEXT_EFFECT_Z_STEP * 2.0,
+        });
+    }
+
+    if let Some((_, color)) = store.text_outlines[idx] {
+        layers.push(TextEffectLayer {
+            offset: glam::Vec2::ZERO,
+            dilate: outline_width / sdf_scale,
+            blur: 0.0,
+            color: ObjectInstance::pack_color(color.to_array()),
+            z: z - TEXT_EFFECT_Z_STEP,
+        });
+    }
+
+    let pos = store.positions[idx];
+    let rot = store.rotations[idx];
+
+    for layer in &layers {
+        for placed in glyphs {
+            let Some(glyph) = text_engine.sdf_cache.get_glyph(placed.key, &mut text_engine.font_system) else {
+                continue;
+            };
+
+            let (u, v, uw, vh) = glyph.uv;
+            let [x, y, w, h] = glyph_rect(
+                placed,
+                glyph.left * sdf_scale,
+                glyph.top * sdf_scale,
+                glyph.width * sdf_scale,
+                glyph.height * sdf_scale,
+            );
+
+            batch.push(ObjectInstance {
+                pos_size: [pos.x + layer.offset.x + x, pos.y + layer.offset.y + y, w, h],
+                uv: ObjectInstance::pack_uv([u, v, uw, vh]),
+                radii: ObjectInstance::pack_radii([layer.dilate, layer.blur, 0.0, 0.0]),
+                gradient_data: ObjectInstance::pack_gradient([0.0, 0.0, -1.0, 0.0]),
+                extra: [layer.z, rot + placed.angle],
+                type_id: crate::textware::SDF_ATLAS_ID,
+                color: layer.color,
+                color2: layer.color,
+                effect_data: ObjectInstance::pack_effects(0.0, 0.0),
+            });
+        }
+    }
+}
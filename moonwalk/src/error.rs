@@ -33,12 +33,18 @@ pub enum MoonWalkError {
     #[error("Failed to load texture: {0}")]
     TextureLoading(String),
 
+    #[error("Failed to load animation: {0}")]
+    AnimationLoading(String),
+
     #[error("IO/Asset error: {0}")]
     IOError(String),
 
     #[error("Shader error: {0}")]
     ShaderError(String),
 
+    #[error("Filter error: {0}")]
+    FilterError(String),
+
     #[error("Text error: {0}")]
     TextError(#[from] crate::textware::TextError),
 }
@@ -79,12 +85,18 @@ pub enum MoonWalkError {
     #[error("Failed to load texture: {0}")]
     TextureLoading(String),
 
+    #[error("Failed to load animation: {0}")]
+    AnimationLoading(String),
+
     #[error("IO/Asset error: {0}")]
     IOError(String),
 
     #[error("Shader error: {0}")]
     ShaderError(String),
 
+    #[error("Filter error: {0}")]
+    FilterError(String),
+
     #[error("Text error: {0}")]
     TextError(#[from] crate::textware::TextError),
 }
\ No newline at end of file
@@ -0,0 +1,789 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Цепочки фильтров. FilterChain только описывает шаги и их параметры, сама
+// она видеопамяти не держит, поэтому её можно собрать один раз и применять
+// каждый кадр, меняя параметры через set или op_mut. Каждый шаг пишет в
+// свою промежуточную текстуру из пула, исходная текстура не меняется, а
+// результат копируется в целевую. Шаг может брать на вход исходную
+// текстуру, результат любого предыдущего шага или зарегистрированную
+// текстуру, так что цепочка это граф без циклов, а не только список
+
+use std::collections::HashMap;
+
+use bytemuck::bytes_of;
+use glam::{Mat4, Vec2, Vec3, Vec4};
+
+use crate::error::MoonWalkError;
+use crate::filters::color_matrix;
+use crate::filters::uniforms::*;
+use crate::filters::{FilterSystem, PassTextures};
+use crate::gpu::Context;
+use crate::rendering::texture::Texture;
+
+/// Шаг цепочки, возвращается из FilterChain::push
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FilterStep(usize);
+
+impl FilterStep {
+    /// Номер шага в цепочке начиная с нуля
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Откуда шаг берёт текстуру
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterInput {
+    /// Исходная текстура цепочки
+    Source,
+    /// Результат предыдущего шага
+    Step(FilterStep),
+    /// Любая зарегистрированная текстура по айди
+    Texture(u32),
+}
+
+/// Операция шага и её параметры
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterOp {
+    /// Размытие по Гауссу сразу по обеим осям (два прохода)
+    Blur { radius: f32 },
+    /// Один проход размытия вдоль оси
+    DirectionalBlur { radius: f32, horizontal: bool },
+    /// пиксель = matrix * пиксель + offset, как в mw.color_matrix
+    ColorMatrix { matrix: Mat4, offset: Vec4 },
+    /// Цвет key_color становится прозрачным
+    Chromakey { key_color: Vec3, tolerance: f32 },
+    /// Альфа канал mask как маска, как в mw.apply_mask
+    Mask { mask: FilterInput, invert: bool },
+    /// Свечение ярких участков: пиксели ярче threshold (0..1) размываются
+    /// пирамидой из levels уменьшенных вдвое текстур и прибавляются к
+    /// картинке. Уровней не больше 6 и не меньше 8 пикселей по стороне
+    Bloom { threshold: f32, intensity: f32, radius: f32, levels: u32 },
+    /// Размытая тень из альфа канала под картинкой. offset в пикселях
+    /// (y вниз), альфа color задаёт непрозрачность тени
+    DropShadow { offset: Vec2, radius: f32, color: Vec4 },
+    /// Нерезкая маска: к пикселю прибавляется разница с размытой копией
+    Sharpen { amount: f32, radius: f32 },
+    /// Затемнение к краям. radius и softness в долях расстояния от центра
+    /// до угла, края смешиваются с color
+    Vignette { strength: f32, radius: f32, softness: f32, color: Vec4 },
+    /// Мозаика из блоков size на size пикселей
+    Pixelate { size: f32 },
+    /// Шум плёнки, меняйте seed каждый кадр чтобы зерно двигалось
+    FilmGrain { amount: f32, seed: f32 },
+    /// Края по оператору Собеля, белые на чёрном, альфа сохраняется
+    EdgeDetect { strength: f32 },
+}
+
+// Режимы шейдера effects.wgsl
+const EFFECT_COPY: u32 = 0;
+const EFFECT_THRESHOLD: u32 = 1;
+const EFFECT_ADD: u32 = 2;
+const EFFECT_SHADOW: u32 = 3;
+const EFFECT_OVER: u32 = 4;
+const EFFECT_UNSHARP: u32 = 5;
+const EFFECT_VIGNETTE: u32 = 6;
+const EFFECT_PIXELATE: u32 = 7;
+const EFFECT_GRAIN: u32 = 8;
+const EFFECT_EDGES: u32 = 9;
+
+// Мягкость порога bloom
+const BLOOM_KNEE: f32 = 0.1;
+
+// Больше уровней пирамиды bloom не бывает, каждый уровень вдвое меньше
+const MAX_BLOOM_LEVELS: u32 = 6;
+
+// Уровни меньше этого размера не создаются, иначе на маленьких текстурах
+// свечение растекается по всей картинке
+const MIN_BLOOM_SIZE: u32 = 8;
+
+impl FilterOp {
+    pub fn blur(radius: f32) -> Self {
+        Self::Blur { radius }
+    }
+
+    pub fn brightness(factor: f32) -> Self {
+        Self::from_matrix(color_matrix::matrix_brightness(factor))
+    }
+
+    pub fn contrast(contrast: f32) -> Self {
+        Self::from_matrix(color_matrix::matrix_contrast(contrast))
+    }
+
+    pub fn saturation(sat: f32) -> Self {
+        Self::from_matrix(color_matrix::matrix_saturation(sat))
+    }
+
+    pub fn hue_shift(degrees: f32) -> Self {
+        Self::from_matrix(color_matrix::matrix_hue(degrees))
+    }
+
+    pub fn chromakey(key_color: Vec3, tolerance: f32) -> Self {
+        Self::Chromakey { key_color, tolerance }
+    }
+
+    pub fn mask(mask: FilterInput, invert: bool) -> Self {
+        Self::Mask { mask, invert }
+    }
+
+    /// Bloom с радиусом 2 и пирамидой из 4 уровней
+    pub fn bloom(threshold: f32, intensity: f32) -> Self {
+        Self::Bloom { threshold, intensity, radius: 2.0, levels: 4 }
+    }
+
+    pub fn drop_shadow(offset: Vec2, radius: f32, color: Vec4) -> Self {
+        Self::DropShadow { offset, radius, color }
+    }
+
+    pub fn sharpen(amount: f32, radius: f32) -> Self {
+        Self::Sharpen { amount, radius }
+    }
+
+    /// Чёрная виньетка с мягкостью 0.45
+    pub fn vignette(strength: f32, radius: f32) -> Self {
+        Self::Vignette { strength, radius, softness: 0.45, color: Vec4::new(0.0, 0.0, 0.0, 1.0) }
+    }
+
+    pub fn pixelate(size: f32) -> Self {
+        Self::Pixelate { size }
+    }
+
+    pub fn film_grain(amount: f32, seed: f32) -> Self {
+        Self::FilmGrain { amount, seed }
+    }
+
+    pub fn edge_detect(strength: f32) -> Self {
+        Self::EdgeDetect { strength }
+    }
+
+    fn from_matrix((matrix, offset): ([[f32; 4]; 4], [f32; 4])) -> Self {
+        Self::ColorMatrix {
+            matrix: Mat4::from_cols_array_2d(&matrix),
+            offset: Vec4::from_array(offset),
+        }
+    }
+
+    // Второй вход операции, кроме основного
+    fn extra_input(&self) -> Option<FilterInput> {
+        match self {
+            Self::Mask { mask, .. } => Some(*mask),
+            _ => None,
+        }
+    }
+
+    // Размеры промежуточных текстур которые нужны операции
+    fn temp_sizes(&self, width: u32, height: u32) -> Vec<[u32; 2]> {
+        match *self {
+            Self::Blur { .. } => vec![[width, height]],
+            Self::DropShadow { .. } | Self::Sharpen { .. } => vec![[width, height]; 2],
+
+            // Уровень пирамиды и текстура для его размытия
+            Self::Bloom { levels, .. } => (0..bloom_levels(levels, width, height))
+                .flat_map(|level| {
+                    let size = [(width >> (level + 1)).max(1), (height >> (level + 1)).max(1)];
+                    [size, size]
+                })
+                .collect(),
+
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Сколько уровней пирамиды bloom поместится в текстуру, хотя бы один
+fn bloom_levels(levels: u32, width: u32, height: u32) -> u32 {
+    let fits = (1..levels.min(MAX_BLOOM_LEVELS))
+        .take_while(|level| (width.min(height) >> (level + 1)) >= MIN_BLOOM_SIZE)
+        .count() as u32;
+
+    fits + 1
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    input: FilterInput,
+    op: FilterOp,
+}
+
+/// Описание цепочки фильтров. Пример размытия с затемнением, где исходная
+/// текстура не меняется:
+///  let mut chain = FilterChain::new();
+///  let blur = chain.push(FilterOp::blur(4.0));
+///  chain.push(FilterOp::brightness(0.6));
+///  mw.apply_filter_chain(&chain, source_id, target_id)?;
+///  // в следующем кадре
+///  chain.set(blur, FilterOp::blur(8.0));
+#[derive(Debug, Clone, Default)]
+pub struct FilterChain {
+    nodes: Vec<Node>,
+    output: Option<FilterStep>,
+}
+
+impl FilterChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет шаг который обрабатывает результат предыдущего шага
+    /// (первый шаг берёт исходную текстуру)
+    pub fn push(&mut self, op: FilterOp) -> FilterStep {
+        let input = match self.nodes.len() {
+            0 => FilterInput::Source,
+            len => FilterInput::Step(FilterStep(len - 1)),
+        };
+
+        self.push_from(input, op)
+    }
+
+    /// Добавляет шаг с явным входом, например вторую ветку от исходной
+    /// текстуры для маски
+    pub fn push_from(&mut self, input: FilterInput, op: FilterOp) -> FilterStep {
+        self.nodes.push(Node { input, op });
+        FilterStep(self.nodes.len() - 1)
+    }
+
+    /// То же что push, но для сборки цепочки одним выражением
+    pub fn then(mut self, op: FilterOp) -> Self {
+        self.push(op);
+        self
+    }
+
+    /// Заменяет операцию шага, вход остаётся прежним. false если шага нет
+    pub fn set(&mut self, step: FilterStep, op: FilterOp) -> bool {
+        match self.nodes.get_mut(step.0) {
+            Some(node) => {
+                node.op = op;
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    pub fn op(&self, step: FilterStep) -> Option<&FilterOp> {
+        self.nodes.get(step.0).map(|node| &node.op)
+    }
+
+    pub fn op_mut(&mut self, step: FilterStep) -> Option<&mut FilterOp> {
+        self.nodes.get_mut(step.0).map(|node| &mut node.op)
+    }
+
+    /// Шаг по номеру, для биндингов где шаги хранятся числами
+    pub fn step(&self, index: usize) -> Option<FilterStep> {
+        (index < self.nodes.len()).then_some(FilterStep(index))
+    }
+
+    /// Результатом цепочки будет этот шаг. По умолчанию последний
+    pub fn set_output(&mut self, step: FilterStep) {
+        self.output = Some(step);
+    }
+
+    pub fn output(&self) -> Option<FilterStep> {
+        self.output.or_else(|| self.nodes.len().checked_sub(1).map(FilterStep))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // Айди зарегистрированных текстур которые читает цепочка
+    pub(crate) fn texture_inputs(&self) -> Vec<u32> {
+        self.nodes.iter()
+            .flat_map(|node| [Some(node.input), node.op.extra_input()])
+            .filter_map(|input| match input {
+                Some(FilterInput::Texture(id)) => Some(id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Шаги могут ссылаться только на предыдущие, тогда циклов нет
+    fn validate(&self) -> Result<(), MoonWalkError> {
+        if let Some(output) = self.output {
+            if output.0 >= self.nodes.len() {
+                return Err(MoonWalkError::FilterError(format!("Output step {} is not in the chain", output.0)));
+            }
+        }
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            for input in [Some(node.input), node.op.extra_input()].into_iter().flatten() {
+                if let FilterInput::Step(step) = input {
+                    if step.0 >= i {
+                        return Err(MoonWalkError::FilterError(format!("Step {} can't read step {}", i, step.0)));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Больше свободных текстур пул не держит, лишние самые старые удаляются.
+// Хватает на bloom с наибольшей пирамидой
+const MAX_POOL_TEXTURES: usize = 16;
+
+// Свободные промежуточные текстуры фильтров
+#[derive(Default)]
+pub(crate) struct TexturePool {
+    free: Vec<Texture>,
+}
+
+impl TexturePool {
+    fn take(&mut self, ctx: &Context, width: u32, height: u32) -> Texture {
+        let position = self.free.iter()
+            .position(|texture| texture.texture.width() == width && texture.texture.height() == height);
+
+        match position {
+            Some(position) => self.free.swap_remove(position),
+            None => Texture::create_render_target(ctx, width, height, super::FILTER_FORMAT),
+        }
+    }
+
+    fn put(&mut self, texture: Texture) {
+        if self.free.len() >= MAX_POOL_TEXTURES {
+            self.free.remove(0);
+        }
+
+        self.free.push(texture);
+    }
+
+    pub fn memory_bytes(&self) -> u64 {
+        self.free.iter().map(Texture::memory_bytes).sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.free.clear();
+    }
+}
+
+// Текстуры одного шага цепочки: вход, дополнительный вход операции
+// (маска), промежуточные текстуры из temp_sizes и результат
+struct StepTextures<'a> {
+    input: &'a Texture,
+    extra: Option<&'a Texture>,
+    temps: &'a [Texture],
+    dest: &'a Texture,
+}
+
+impl FilterSystem {
+    /// Применяет цепочку к source и копирует результат в target. Размер
+    /// target должен совпадать с source, формат тот же что у текстур пула
+    /// (FILTER_FORMAT), результат копируется байтами и без перевода из sRGB
+    /// цвета бы сдвинулись. Все проходы пишутся в один энкодер, target может
+    /// быть той же текстурой что и source
+    pub(crate) fn apply_chain(
+        &mut self,
+        ctx: &Context,
+        chain: &FilterChain,
+        source: &Texture,
+        textures: &HashMap<u32, Texture>,
+        target: &Texture,
+    ) -> Result<(), MoonWalkError> {
+        let width = source.texture.width();
+        let height = source.texture.height();
+
+        if target.texture.width() != width || target.texture.height() != height {
+            return Err(MoonWalkError::FilterError(format!(
+                "Target is {}x{}, source is {}x{}", target.texture.width(), target.texture.height(), width, height
+            )));
+        }
+
+        if target.texture.format() != super::FILTER_FORMAT {
+            return Err(MoonWalkError::FilterError(format!(
+                "Can't write filter result to {:?} texture, expected {:?}", target.texture.format(), super::FILTER_FORMAT
+            )));
+        }
+
+        chain.validate()?;
+
+        // Пустая цепочка просто копирует исходную текстуру
+        let empty = FilterChain::new().then(FilterOp::brightness(1.0));
+        let chain = if chain.is_empty() { &empty } else { chain };
+        let output = chain.output().map_or(0, |step| step.0);
+
+        // После какого шага результат шага больше не нужен и текстура
+        // возвращается в пул
+        let mut last_use: Vec<usize> = (0..chain.nodes.len()).collect();
+        last_use[output] = usize::MAX;
+
+        for (i, node) in chain.nodes.iter().enumerate().take(output + 1) {
+            for input in [Some(node.input), node.op.extra_input()].into_iter().flatten() {
+                if let FilterInput::Step(step) = input {
+                    if last_use[step.0] != usize::MAX {
+                        last_use[step.0] = last_use[step.0].max(i);
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<Option<Texture>> = chain.nodes.iter().map(|_| None).collect();
+        let mut encoder = ctx.create_encoder();
+
+        for (i, node) in chain.nodes.iter().enumerate().take(output + 1) {
+            let dest = self.pool.take(ctx, width, height);
+            let temps: Vec<Texture> = node.op.temp_sizes(width, height).into_iter()
+                .map(|[w, h]| self.pool.take(ctx, w, h))
+                .collect();
+
+            {
+                let resolve = |input: FilterInput| -> Result<&Texture, MoonWalkError> {
+                    match input {
+                        FilterInput::Source => Ok(source),
+                        FilterInput::Step(step) => results[step.0].as_ref()
+                            .ok_or_else(|| MoonWalkError::FilterError(format!("Step {} result is not available", step.0))),
+                        FilterInput::Texture(id) => textures.get(&id)
+                            .ok_or_else(|| MoonWalkError::FilterError(format!("Texture {} not found", id))),
+                    }
+                };
+
+                let input = resolve(node.input)?;
+                let extra = node.op.extra_input().map(resolve).transpose()?;
+
+                let step = StepTextures { input, extra, temps: &temps, dest: &dest };
+                self.encode_op(ctx, &mut encoder, &node.op, step);
+            }
+
+            results[i] = Some(dest);
+
+            for texture in temps {
+                self.pool.put(texture);
+            }
+
+            for step in 0..=i {
+                if last_use[step] == i {
+                    if let Some(texture) = results[step].take() {
+                        self.pool.put(texture);
+                    }
+                }
+            }
+        }
+
+        let result = results[output].take()
+            .ok_or_else(|| MoonWalkError::FilterError("Chain has no output".into()))?;
+
+        self.blit_back(&mut encoder, target, &result, width, height);
+        ctx.submit(encoder);
+
+        self.pool.put(result);
+
+        Ok(())
+    }
+
+    fn encode_op(&self, ctx: &Context, encoder: &mut wgpu::CommandEncoder, op: &FilterOp, step: StepTextures) {
+        let StepTextures { input, extra, temps, dest } = step;
+        let resolution = [input.texture.width() as f32, input.texture.height() as f32];
+
+        // Проход эффекта только по входу шага
+        let single = PassTextures { source: input, second: input, dest };
+
+        match *op {
+            FilterOp::Blur { radius } => {
+                self.encode_blur(ctx, encoder, radius, input, &temps[0], dest);
+            }
+
+            FilterOp::DirectionalBlur { radius, horizontal } => {
+                let uniform = BlurUniform {
+                    direction: if horizontal { [1.0, 0.0] } else { [0.0, 1.0] },
+                    radius,
+                    _pad: 0.0,
+                    resolution,
+                };
+
+                self.execute_pass(ctx, encoder, &self.blur_pipeline, input, dest, bytes_of(&uniform));
+            }
+
+            FilterOp::ColorMatrix { matrix, offset } => {
+                let uniform = ColorMatrixUniform {
+                    matrix: matrix.to_cols_array_2d(),
+                    offset: offset.to_array(),
+                };
+
+                self.execute_pass(ctx, encoder, &self.color_pipeline, input, dest, bytes_of(&uniform));
+            }
+
+            FilterOp::Chromakey { key_color, tolerance } => {
+                let uniform = AdvancedUniform {
+                    key_color: key_color.to_array(),
+                    tolerance,
+                    params: [1.0, 0.0, 0.0, 0.0],
+                };
+
+                self.execute_advanced_pass(ctx, encoder, &self.advanced_pipeline, single, bytes_of(&uniform));
+            }
+
+            FilterOp::Mask { invert, .. } => {
+                let uniform = AdvancedUniform {
+                    key_color: [0.0; 3],
+                    tolerance: 0.0,
+                    params: [2.0, if invert { 1.0 } else { 0.0 }, 0.0, 0.0],
+                };
+
+                let textures = PassTextures { source: input, second: extra.unwrap_or(input), dest };
+                self.execute_advanced_pass(ctx, encoder, &self.advanced_pipeline, textures, bytes_of(&uniform));
+            }
+
+            FilterOp::Bloom { threshold, intensity, radius, .. } => {
+                let levels = temps.len() / 2;
+
+                // Яркие пиксели в первый уровень, каждый следующий уровень
+                // это уменьшенный вдвое предыдущий, все размываются
+                for level in 0..levels {
+                    let (current, temp) = (&temps[level * 2], &temps[level * 2 + 1]);
+
+                    if level == 0 {
+                        let textures = PassTextures { source: input, second: input, dest: current };
+                        self.encode_effect(ctx, encoder, EFFECT_THRESHOLD, [threshold, BLOOM_KNEE, 0.0, 0.0], [0.0; 4], textures);
+                    } else {
+                        let previous = &temps[(level - 1) * 2];
+                        let textures = PassTextures { source: previous, second: previous, dest: current };
+                        self.encode_effect(ctx, encoder, EFFECT_COPY, [0.0; 4], [0.0; 4], textures);
+                    }
+
+                    self.encode_blur(ctx, encoder, radius, current, temp, current);
+                }
+
+                // Обратно вверх: уровень плюс увеличенная сумма меньших
+                // уровней, сумма пишется во вторую текстуру уровня
+                let mut sum = &temps[(levels - 1) * 2];
+
+                for level in (0..levels - 1).rev() {
+                    let (current, temp) = (&temps[level * 2], &temps[level * 2 + 1]);
+
+                    let textures = PassTextures { source: current, second: sum, dest: temp };
+                    self.encode_effect(ctx, encoder, EFFECT_ADD, [1.0, 0.0, 0.0, 0.0], [0.0; 4], textures);
+                    sum = temp;
+                }
+
+                // Сумма уровней делится на их число, чтобы сила не зависела
+                // от размера пирамиды
+                let intensity = intensity / levels as f32;
+                let textures = PassTextures { source: input, second: sum, dest };
+                self.encode_effect(ctx, encoder, EFFECT_ADD, [intensity, 0.0, 0.0, 0.0], [0.0; 4], textures);
+            }
+
+            FilterOp::DropShadow { offset, radius, color } => {
+                let (shadow, temp) = (&temps[0], &temps[1]);
+
+                let textures = PassTextures { source: input, second: input, dest: shadow };
+                self.encode_effect(ctx, encoder, EFFECT_SHADOW, [offset.x, offset.y, 0.0, 0.0], color.to_array(), textures);
+                self.encode_blur(ctx, encoder, radius, shadow, temp, shadow);
+
+                let textures = PassTextures { source: input, second: shadow, dest };
+                self.encode_effect(ctx, encoder, EFFECT_OVER, [0.0; 4], [0.0; 4], textures);
+            }
+
+            FilterOp::Sharpen { amount, radius } => {
+                let (temp, blurred) = (&temps[0], &temps[1]);
+
+                self.encode_blur(ctx, encoder, radius, input, temp, blurred);
+                let textures = PassTextures { source: input, second: blurred, dest };
+                self.encode_effect(ctx, encoder, EFFECT_UNSHARP, [amount, 0.0, 0.0, 0.0], [0.0; 4], textures);
+            }
+
+            FilterOp::Vignette { strength, radius, softness, color } => {
+                self.encode_effect(ctx, encoder, EFFECT_VIGNETTE, [strength, radius, softness, 0.0], color.to_array(), single);
+            }
+
+            FilterOp::Pixelate { size } => {
+                self.encode_effect(ctx, encoder, EFFECT_PIXELATE, [size, 0.0, 0.0, 0.0], [0.0; 4], single);
+            }
+
+            FilterOp::FilmGrain { amount, seed } => {
+                self.encode_effect(ctx, encoder, EFFECT_GRAIN, [amount, seed, 0.0, 0.0], [0.0; 4], single);
+            }
+
+            FilterOp::EdgeDetect { strength } => {
+                self.encode_effect(ctx, encoder, EFFECT_EDGES, [strength, 0.0, 0.0, 0.0], [0.0; 4], single);
+            }
+        }
+    }
+
+    // Размытие по обеим осям через temp, input и dest могут совпадать
+    fn encode_blur(
+        &self,
+        ctx: &Context,
+        encoder: &mut wgpu::CommandEncoder,
+        radius: f32,
+        input: &Texture,
+        temp: &Texture,
+        dest: &Texture,
+    ) {
+        let blur = |direction: [f32; 2], texture: &Texture| BlurUniform {
+            direction,
+            radius,
+            _pad: 0.0,
+            resolution: [texture.texture.width() as f32, texture.texture.height() as f32],
+        };
+
+        self.execute_pass(ctx, encoder, &self.blur_pipeline, input, temp, bytes_of(&blur([1.0, 0.0], input)));
+        self.execute_pass(ctx, encoder, &self.blur_pipeline, temp, dest, bytes_of(&blur([0.0, 1.0], temp)));
+    }
+
+    // Проход effects.wgsl, second нужен режимам которые смешивают две текстуры
+    fn encode_effect(
+        &self,
+        ctx: &Context,
+        encoder: &mut wgpu::CommandEncoder,
+        mode: u32,
+        params: [f32; 4],
+        color: [f32; 4],
+        textures: PassTextures,
+    ) {
+        let uniform = EffectUniform {
+            params,
+            color,
+            resolution: [textures.source.texture.width() as f32, textures.source.texture.height() as f32],
+            mode,
+            _pad: 0.0,
+        };
+
+        self.execute_advanced_pass(ctx, encoder, &self.effects_pipeline, textures, bytes_of(&uniform));
+    }
+}
+
+#[test]
+fn filter_chain_effects_test() {
+    // Промежуточные текстуры: bloom уменьшает уровни вдвое пока они не
+    // меньше MIN_BLOOM_SIZE, тень и резкость берут две текстуры в полный размер
+    assert_eq!(bloom_levels(4, 64, 64), 3);
+    assert_eq!(bloom_levels(4, 8, 8), 1);
+    assert_eq!(
+        FilterOp::bloom(0.5, 1.0).temp_sizes(64, 64),
+        [[32, 32], [32, 32], [16, 16], [16, 16], [8, 8], [8, 8]]
+    );
+    assert_eq!(FilterOp::drop_shadow(Vec2::ONE, 1.0, Vec4::W).temp_sizes(64, 32), [[64, 32]; 2]);
+    assert!(FilterOp::pixelate(4.0).temp_sizes(64, 64).is_empty());
+
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).unwrap();
+    let (device, queue) = pollster::block_on(adapter.request_device(&Default::default(), None)).unwrap();
+
+    let ctx = Context {
+        config: wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: super::FILTER_FORMAT,
+            width: 1,
+            height: 1,
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: Vec::new(),
+        },
+        adapter_info: adapter.get_info(),
+        device,
+        queue,
+        surface: None,
+        adapter,
+        instance,
+    };
+
+    let mut filters = FilterSystem::new(&ctx).unwrap();
+    let textures = HashMap::new();
+
+    // Белый квадрат 8..16 на прозрачном (или чёрном) фоне 32x32
+    let square = |background: [u8; 4]| {
+        let image = image::RgbaImage::from_fn(32, 32, |x, y| {
+            let inside = (8..16).contains(&x) && (8..16).contains(&y);
+            image::Rgba(if inside { [255; 4] } else { background })
+        });
+
+        Texture::from_raw(&ctx, &image, 32, 32, "Filter test").unwrap()
+    };
+
+    let source = square([0; 4]);
+    let target = square([0; 4]);
+
+    // Тень рисуется под текстурой со сдвигом, сама текстура не меняется
+    let shadow = FilterChain::new().then(FilterOp::drop_shadow(Vec2::splat(8.0), 1.0, Vec4::new(0.0, 0.0, 0.0, 1.0)));
+    filters.apply_chain(&ctx, &shadow, &source, &textures, &target).unwrap();
+
+    let result = target.download(&ctx).unwrap();
+    assert_eq!(result.get_pixel(12, 12).0, [255; 4]);
+    assert_eq!(result.get_pixel(20, 20).0, [0, 0, 0, 255]);
+    assert_eq!(result.get_pixel(2, 2).0[3], 0);
+
+    // Промежуточные текстуры возвращаются в пул и берутся из него снова
+    let pooled = filters.pool.memory_bytes();
+    assert!(pooled > 0);
+
+    filters.apply_chain(&ctx, &shadow, &source, &textures, &target).unwrap();
+    assert_eq!(filters.pool.memory_bytes(), pooled);
+
+    // Свечение выходит за края яркого квадрата
+    let source = square([0, 0, 0, 255]);
+    let target = square([0, 0, 0, 255]);
+
+    let bloom = FilterChain::new().then(FilterOp::bloom(0.5, 1.0));
+    filters.apply_chain(&ctx, &bloom, &source, &textures, &target).unwrap();
+
+    let result = target.download(&ctx).unwrap();
+    assert_eq!(result.get_pixel(12, 12).0, [255; 4]);
+    assert!(result.get_pixel(17, 12).0[0] > 0);
+    assert_eq!(result.get_pixel(17, 12).0[3], 255);
+
+    // Выход цепочки это выбранный шаг, шаги после него не выполняются
+    let mut chain = FilterChain::new();
+    let glow = chain.push(FilterOp::bloom(0.5, 1.0));
+    chain.push(FilterOp::brightness(0.0));
+    chain.set_output(glow);
+
+    let branched = square([0, 0, 0, 255]);
+    filters.apply_chain(&ctx, &chain, &source, &textures, &branched).unwrap();
+    assert_eq!(branched.download(&ctx).unwrap(), result);
+
+    // Шаг может читать только предыдущие шаги, выход должен быть в цепочке
+    let mut chain = FilterChain::new();
+    let first = chain.push(FilterOp::blur(1.0));
+    chain.set(first, FilterOp::mask(FilterInput::Step(first), false));
+    assert!(matches!(filters.apply_chain(&ctx, &chain, &source, &textures, &target), Err(MoonWalkError::FilterError(_))));
+
+    let mut chain = FilterChain::new();
+    chain.set_output(FilterStep(3));
+    assert!(matches!(filters.apply_chain(&ctx, &chain, &source, &textures, &target), Err(MoonWalkError::FilterError(_))));
+
+    // Цель другого размера или не в sRGB
+    let small = Texture::from_raw(&ctx, &[0; 16], 2, 2, "Small").unwrap();
+    assert!(filters.apply_chain(&ctx, &shadow, &source, &textures, &small).is_err());
+
+    let linear = crate::rendering::texture::TextureOptions::default().srgb(false);
+    let linear = Texture::from_raw_with_options(&ctx, &[0; 32 * 32 * 4], 32, 32, "Linear", &linear).unwrap();
+    assert!(matches!(filters.apply_chain(&ctx, &shadow, &source, &textures, &linear), Err(MoonWalkError::FilterError(_))));
+
+    // Старые фильтры тоже не трогают линейную текстуру, а sRGB размывают
+    let pixels = linear.download(&ctx).unwrap();
+
+    filters.apply_blur(&ctx, &linear, 2.0, true);
+    filters.apply_color_matrix(&ctx, &linear, [[0.0; 4]; 4], [0.0; 4]);
+    filters.apply_chromakey(&ctx, &linear, [0.0; 3], 0.5);
+    filters.apply_stencil(&ctx, &linear, &source, false);
+    assert_eq!(linear.download(&ctx).unwrap(), pixels);
+
+    let blurred = square([0, 0, 0, 255]);
+    filters.apply_blur(&ctx, &blurred, 2.0, true);
+    assert!(blurred.read_pixel(&ctx, 17, 12).unwrap()[0] > 0);
+
+    // Текстуры из create_texture в других форматах тоже пропускаются
+    use crate::rendering::texture::TextureFormat;
+
+    for format in [TextureFormat::R8Unorm, TextureFormat::Rg8Unorm, TextureFormat::Rgba16Float] {
+        let texture = Texture::create_dynamic(&ctx, 32, 32, format, "Dynamic").unwrap();
+
+        filters.apply_blur(&ctx, &texture, 2.0, false);
+        filters.apply_color_matrix(&ctx, &texture, [[1.0; 4]; 4], [0.0; 4]);
+        filters.apply_chromakey(&ctx, &texture, [0.0; 3], 0.5);
+        filters.apply_stencil(&ctx, &texture, &source, true);
+    }
+
+    // Промежуточная текстура того же размера пересоздаётся под другой формат
+    filters.ensure_swap_texture(&ctx, 32, 32, wgpu::TextureFormat::Rgba16Float);
+    assert_eq!(filters.swap_texture.as_ref().unwrap().texture.format(), wgpu::TextureFormat::Rgba16Float);
+
+    filters.ensure_swap_texture(&ctx, 32, 32, super::FILTER_FORMAT);
+    assert_eq!(filters.swap_texture.as_ref().unwrap().texture.format(), super::FILTER_FORMAT);
+}
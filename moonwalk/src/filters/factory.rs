@@ -16,7 +16,7 @@ pub fn create_blur_pipeline(
         .add_vertex_layout(VertexLayout::new().stride(0).step_mode(StepMode::Vertex))
         .blend(BlendMode::None)
         .label("blur_filter")
-        .build(ctx, wgpu::TextureFormat::Rgba8UnormSrgb, &[uniform_layout, texture_layout])?;
+        .build(ctx, super::FILTER_FORMAT, &[uniform_layout, texture_layout])?;
 
     Ok(result.pipeline.raw)
 }
@@ -32,7 +32,7 @@ pub fn create_color_pipeline(
         .add_vertex_layout(VertexLayout::new().stride(0).step_mode(StepMode::Vertex))
         .blend(BlendMode::None)
         .label("color_filter")
-        .build(ctx, wgpu::TextureFormat::Rgba8UnormSrgb, &[uniform_layout, texture_layout])?;
+        .build(ctx, super::FILTER_FORMAT, &[uniform_layout, texture_layout])?;
 
     Ok(result.pipeline.raw)
 }
@@ -48,7 +48,23 @@ pub fn create_advanced_pipeline(
         .add_vertex_layout(VertexLayout::new().stride(0).step_mode(StepMode::Vertex))
         .blend(BlendMode::None)
         .label("advanced_filter")
-        .build(ctx, wgpu::TextureFormat::Rgba8UnormSrgb, &[uniform_layout, advanced_layout])?;
+        .build(ctx, super::FILTER_FORMAT, &[uniform_layout, advanced_layout])?;
+
+    Ok(result.pipeline.raw)
+}
+
+pub fn create_effects_pipeline(
+    ctx: &Context,
+    uniform_layout: &wgpu::BindGroupLayout,
+    advanced_layout: &wgpu::BindGroupLayout,
+) -> Result<wgpu::RenderPipeline, MoonWalkError> {
+    let result = MoonPipeline::new(include_str!("shaders/effects.wgsl"))
+        .vertex_shader("vs_main")
+        .fragment_shader("fs_main")
+        .add_vertex_layout(VertexLayout::new().stride(0).step_mode(StepMode::Vertex))
+        .blend(BlendMode::None)
+        .label("effects_filter")
+        .build(ctx, super::FILTER_FORMAT, &[uniform_layout, advanced_layout])?;
 
     Ok(result.pipeline.raw)
 }
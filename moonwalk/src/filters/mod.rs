@@ -4,6 +4,7 @@
 pub mod color_matrix;
 pub mod uniforms;
 pub mod factory;
+pub mod chain;
 
 use wgpu::util::DeviceExt;
 use bytemuck::bytes_of;
@@ -15,13 +16,29 @@ use crate::r#abstract::*;
 use crate::error::MoonWalkError;
 
 use self::uniforms::*;
+use self::chain::TexturePool;
+
+// Формат под который собраны пайплайны фильтров
+pub(crate) const FILTER_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+// Текстуры прохода с двумя входами: основной, второй (маска или слой для
+// смешивания, иначе тот же что и основной) и результат
+struct PassTextures<'a> {
+    source: &'a Texture,
+    second: &'a Texture,
+    dest: &'a Texture,
+}
 
 pub struct FilterSystem {
     swap_texture: Option<Texture>,
+
+    // Промежуточные текстуры цепочек фильтров
+    pool: TexturePool,
     
     blur_pipeline: wgpu::RenderPipeline,
     color_pipeline: wgpu::RenderPipeline,
     advanced_pipeline: wgpu::RenderPipeline,
+    effects_pipeline: wgpu::RenderPipeline,
     
     uniform_layout: wgpu::BindGroupLayout,
     texture_layout: wgpu::BindGroupLayout,
@@ -53,12 +70,15 @@ impl FilterSystem {
         let blur_pipeline = factory::create_blur_pipeline(ctx, &uniform_layout, &texture_layout)?;
         let color_pipeline = factory::create_color_pipeline(ctx, &uniform_layout, &texture_layout)?;
         let advanced_pipeline = factory::create_advanced_pipeline(ctx, &uniform_layout, &advanced_texture_layout)?;
+        let effects_pipeline = factory::create_effects_pipeline(ctx, &uniform_layout, &advanced_texture_layout)?;
 
         Ok(Self {
             swap_texture: None,
+            pool: TexturePool::default(),
             blur_pipeline,
             color_pipeline,
             advanced_pipeline,
+            effects_pipeline,
             uniform_layout,
             texture_layout,
             advanced_texture_layout,
@@ -67,6 +87,10 @@ impl FilterSystem {
     }
 
     pub fn apply_blur(&mut self, ctx: &Context, target_texture: &Texture, radius: f32, horizontal: bool) {
+        if !supports_target(target_texture) {
+            return;
+        }
+
         let width = target_texture.texture.width();
         let height = target_texture.texture.height();
         
@@ -82,15 +106,19 @@ impl FilterSystem {
             resolution: [width as f32, height as f32],
         };
 
+        let mut encoder = ctx.create_encoder();
+
         self.execute_pass(
             ctx,
+            &mut encoder,
             &self.blur_pipeline,
             target_texture,
             swap,
             bytes_of(&uniform_data)
         );
 
-        self.blit_back(ctx, target_texture, swap, width, height);
+        self.blit_back(&mut encoder, target_texture, swap, width, height);
+        ctx.submit(encoder);
     }
 
     pub fn apply_color_matrix(
@@ -100,6 +128,10 @@ impl FilterSystem {
         matrix: [[f32; 4]; 4],
         offset: [f32; 4]
     ) {
+        if !supports_target(target_texture) {
+            return;
+        }
+
         let width = target_texture.texture.width();
         let height = target_texture.texture.height();
         
@@ -108,15 +140,19 @@ impl FilterSystem {
 
         let uniform_data = ColorMatrixUniform { matrix, offset };
 
+        let mut encoder = ctx.create_encoder();
+
         self.execute_pass(
             ctx,
+            &mut encoder,
             &self.color_pipeline,
             target_texture,
             swap,
             bytes_of(&uniform_data)
         );
 
-        self.blit_back(ctx, target_texture, swap, width, height);
+        self.blit_back(&mut encoder, target_texture, swap, width, height);
+        ctx.submit(encoder);
     }
 
     pub fn apply_chromakey(
@@ -126,6 +162,10 @@ impl FilterSystem {
         key_color: [f32; 3],
         tolerance: f32
     ) {
+        if !supports_target(target_texture) {
+            return;
+        }
+
         let width = target_texture.texture.width();
         let height = target_texture.texture.height();
         
@@ -138,15 +178,18 @@ impl FilterSystem {
             params: [1.0, 0.0, 0.0, 0.0],
         };
 
+        let mut encoder = ctx.create_encoder();
+
         self.execute_advanced_pass(
             ctx,
-            target_texture,
-            target_texture,
-            swap,
+            &mut encoder,
+            &self.advanced_pipeline,
+            PassTextures { source: target_texture, second: target_texture, dest: swap },
             bytes_of(&uniform_data)
         );
 
-        self.blit_back(ctx, target_texture, swap, width, height);
+        self.blit_back(&mut encoder, target_texture, swap, width, height);
+        ctx.submit(encoder);
     }
 
     pub fn apply_stencil(
@@ -156,6 +199,10 @@ impl FilterSystem {
         mask_texture: &Texture,
         invert: bool
     ) {
+        if !supports_target(target_texture) {
+            return;
+        }
+
         let width = target_texture.texture.width();
         let height = target_texture.texture.height();
         
@@ -168,28 +215,42 @@ impl FilterSystem {
             params: [2.0, if invert { 1.0 } else { 0.0 }, 0.0, 0.0],
         };
 
+        let mut encoder = ctx.create_encoder();
+
         self.execute_advanced_pass(
             ctx,
-            target_texture,
-            mask_texture,
-            swap,
+            &mut encoder,
+            &self.advanced_pipeline,
+            PassTextures { source: target_texture, second: mask_texture, dest: swap },
             bytes_of(&uniform_data)
         );
 
-        self.blit_back(ctx, target_texture, swap, width, height);
+        self.blit_back(&mut encoder, target_texture, swap, width, height);
+        ctx.submit(encoder);
+    }
+
+    /// Размер промежуточных текстур в байтах
+    pub(crate) fn memory_bytes(&self) -> u64 {
+        self.swap_texture.as_ref().map_or(0, Texture::memory_bytes) + self.pool.memory_bytes()
+    }
+
+    /// Освобождает промежуточные текстуры, при следующем применении
+    /// фильтров они создадутся заново
+    pub(crate) fn release_textures(&mut self) {
+        self.swap_texture = None;
+        self.pool.clear();
     }
 
     fn ensure_swap_texture(&mut self, ctx: &Context, w: u32, h: u32, format: wgpu::TextureFormat) {
         let need_create = self.swap_texture.as_ref()
-            .map_or(true, |t| t.texture.width() != w || t.texture.height() != h);
+            .is_none_or(|t| t.texture.width() != w || t.texture.height() != h || t.texture.format() != format);
 
         if need_create {
             self.swap_texture = Some(Texture::create_render_target(ctx, w, h, format));
         }
     }
 
-    fn blit_back(&self, ctx: &Context, target: &Texture, source: &Texture, width: u32, height: u32) {
-        let mut encoder = ctx.create_encoder();
+    fn blit_back(&self, encoder: &mut wgpu::CommandEncoder, target: &Texture, source: &Texture, width: u32, height: u32) {
         encoder.copy_texture_to_texture(
             wgpu::TexelCopyTextureInfo {
                 texture: &source.texture,
@@ -205,12 +266,12 @@ impl FilterSystem {
             },
             wgpu::Extent3d { width, height, depth_or_array_layers: 1 }
         );
-        ctx.submit(encoder);
     }
 
     fn execute_pass(
         &self,
         ctx: &Context,
+        encoder: &mut wgpu::CommandEncoder,
         pipeline: &wgpu::RenderPipeline,
         source: &Texture,
         dest: &Texture,
@@ -237,17 +298,19 @@ impl FilterSystem {
             ],
         });
 
-        self.run_pipeline(ctx, pipeline, dest, &uniform_bg, &texture_bg);
+        self.run_pipeline(encoder, pipeline, dest, &uniform_bg, &texture_bg);
     }
 
     fn execute_advanced_pass(
         &self,
         ctx: &Context,
-        source: &Texture,
-        mask: &Texture,
-        dest: &Texture,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        textures: PassTextures,
         uniform_bytes: &[u8]
     ) {
+        let PassTextures { source, second, dest } = textures;
+
         let uniform_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Advanced Filter Uniform Buffer"),
             contents: uniform_bytes,
@@ -266,45 +329,53 @@ impl FilterSystem {
             entries: &[
                 wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&source.view) },
                 wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&source.sampler) },
-                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(&mask.view) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(&second.view) },
             ],
         });
 
-        self.run_pipeline(ctx, &self.advanced_pipeline, dest, &uniform_bg, &texture_bg);
+        self.run_pipeline(encoder, pipeline, dest, &uniform_bg, &texture_bg);
     }
 
     fn run_pipeline(
         &self, 
-        ctx: &Context, 
+        encoder: &mut wgpu::CommandEncoder, 
         pipeline: &wgpu::RenderPipeline, 
         dest: &Texture, 
         bg0: &wgpu::BindGroup, 
         bg1: &wgpu::BindGroup
     ) {
-        let mut encoder = ctx.create_encoder();
-        {
-            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Filter Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &dest.view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-
-            pass.set_pipeline(pipeline);
-            pass.set_bind_group(0, bg0, &[]);
-            pass.set_bind_group(1, bg1, &[]);
-            pass.set_vertex_buffer(0, self.dummy_vbo.raw.slice(..));
-            pass.draw(0..3, 0..1);
-        }
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Filter Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &dest.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
 
-        ctx.submit(encoder);
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, bg0, &[]);
+        pass.set_bind_group(1, bg1, &[]);
+        pass.set_vertex_buffer(0, self.dummy_vbo.raw.slice(..));
+        pass.draw(0..3, 0..1);
+    }
+}
+
+// Старые фильтры рисуют пайплайнами под FILTER_FORMAT и копируют результат
+// обратно байтами, текстуры в другом формате не меняются
+fn supports_target(target: &Texture) -> bool {
+    let format = target.texture.format();
+
+    if format != FILTER_FORMAT {
+        log::error!("Can't apply filter to {:?} texture, expected {:?}", format, FILTER_FORMAT);
+        return false;
     }
+
+    true
 }
@@ -0,0 +1,144 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Эффекты цепочек фильтров. Один пайплайн, режим выбирается полем mode:
+// 0 - копия (уменьшение для пирамиды bloom)
+// 1 - яркие пиксели для bloom, params.x порог, params.y мягкость
+// 2 - сложение второй текстуры, params.x сила
+// 3 - тень из альфы, params.xy смещение в пикселях, color цвет тени
+// 4 - исходная текстура поверх второй (тени)
+// 5 - нерезкая маска, вторая текстура размытая, params.x сила
+// 6 - виньетка, params.x сила, params.y радиус, params.z мягкость
+// 7 - пикселизация, params.x размер блока в пикселях
+// 8 - зерно плёнки, params.x сила, params.y зерно случайности
+// 9 - выделение краёв (Собель), params.x сила
+
+struct Uniforms {
+    params: vec4<f32>,
+    color: vec4<f32>,
+    resolution: vec2<f32>,
+    mode: u32,
+    _pad: f32,
+};
+
+@group(0) @binding(0) var<uniform> ubo: Uniforms;
+
+@group(1) @binding(0) var t_diffuse: texture_2d<f32>;
+@group(1) @binding(1) var s_diffuse: sampler;
+
+// Второй вход для режимов которые смешивают две текстуры
+@group(1) @binding(2) var t_second: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) in_vertex_index: u32) -> VertexOutput {
+    var out: VertexOutput;
+
+    let x = f32(i32(in_vertex_index) & 1);
+    let y = f32(i32(in_vertex_index >> 1));
+
+    out.uv = vec2<f32>(x * 2.0, y * 2.0);
+    out.position = vec4<f32>(out.uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv.y = 1.0 - out.uv.y;
+
+    return out;
+}
+
+fn luminance(rgb: vec3<f32>) -> f32 {
+    return dot(rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+fn sample_luminance(uv: vec2<f32>) -> f32 {
+    return luminance(textureSampleLevel(t_diffuse, s_diffuse, uv, 0.0).rgb);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / ubo.resolution;
+
+    // Все выборки в начале, в ветках только выборки с явным уровнем
+    let color = textureSample(t_diffuse, s_diffuse, in.uv);
+    let second = textureSample(t_second, s_diffuse, in.uv);
+
+    switch ubo.mode {
+        case 1u: {
+            let knee = max(ubo.params.y, 0.0001);
+            let weight = smoothstep(ubo.params.x - knee, ubo.params.x + knee, luminance(color.rgb));
+            return vec4<f32>(color.rgb * weight, color.a * weight);
+        }
+
+        case 2u: {
+            let glow = second * ubo.params.x;
+            return vec4<f32>(color.rgb + glow.rgb, max(color.a, min(glow.a, 1.0)));
+        }
+
+        case 3u: {
+            let shifted = in.uv - ubo.params.xy * texel;
+            let alpha = textureSampleLevel(t_diffuse, s_diffuse, shifted, 0.0).a;
+            return vec4<f32>(ubo.color.rgb, alpha * ubo.color.a);
+        }
+
+        case 4u: {
+            let alpha = color.a + second.a * (1.0 - color.a);
+
+            if (alpha <= 0.0) {
+                return vec4<f32>(0.0);
+            }
+
+            let rgb = (color.rgb * color.a + second.rgb * second.a * (1.0 - color.a)) / alpha;
+            return vec4<f32>(rgb, alpha);
+        }
+
+        case 5u: {
+            let sharp = color.rgb + (color.rgb - second.rgb) * ubo.params.x;
+            return vec4<f32>(clamp(sharp, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+        }
+
+        case 6u: {
+            // Расстояние от центра, в углу 1.0
+            let dist = distance(in.uv, vec2<f32>(0.5)) * 1.41421356;
+            let factor = smoothstep(ubo.params.y, ubo.params.y + ubo.params.z, dist) * ubo.params.x;
+            return vec4<f32>(mix(color.rgb, ubo.color.rgb, clamp(factor * ubo.color.a, 0.0, 1.0)), color.a);
+        }
+
+        case 7u: {
+            let block = max(ubo.params.x, 1.0);
+            let pixel = (floor(in.uv * ubo.resolution / block) + 0.5) * block;
+            return textureSampleLevel(t_diffuse, s_diffuse, min(pixel, ubo.resolution - 0.5) * texel, 0.0);
+        }
+
+        case 8u: {
+            let noise = hash(floor(in.uv * ubo.resolution) + vec2<f32>(ubo.params.y * 17.0, ubo.params.y * 31.0)) - 0.5;
+            return vec4<f32>(clamp(color.rgb + noise * ubo.params.x, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+        }
+
+        case 9u: {
+            let tl = sample_luminance(in.uv + vec2<f32>(-texel.x, -texel.y));
+            let t = sample_luminance(in.uv + vec2<f32>(0.0, -texel.y));
+            let tr = sample_luminance(in.uv + vec2<f32>(texel.x, -texel.y));
+            let l = sample_luminance(in.uv + vec2<f32>(-texel.x, 0.0));
+            let r = sample_luminance(in.uv + vec2<f32>(texel.x, 0.0));
+            let bl = sample_luminance(in.uv + vec2<f32>(-texel.x, texel.y));
+            let b = sample_luminance(in.uv + vec2<f32>(0.0, texel.y));
+            let br = sample_luminance(in.uv + vec2<f32>(texel.x, texel.y));
+
+            let gx = (tr + 2.0 * r + br) - (tl + 2.0 * l + bl);
+            let gy = (bl + 2.0 * b + br) - (tl + 2.0 * t + tr);
+            let edge = clamp(length(vec2<f32>(gx, gy)) * ubo.params.x, 0.0, 1.0);
+
+            return vec4<f32>(vec3<f32>(edge), color.a);
+        }
+
+        default: {
+            return color;
+        }
+    }
+}
@@ -32,3 +32,13 @@ pub struct AdvancedUniform {
     pub tolerance: f32,
     pub params: [f32; 4],
 }
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct EffectUniform {
+    pub params: [f32; 4],
+    pub color: [f32; 4],
+    pub resolution: [f32; 2],
+    pub mode: u32,
+    pub _pad: f32,
+}
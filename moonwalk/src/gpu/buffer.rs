@@ -27,6 +27,15 @@ impl<T: Pod> Buffer<T> {
         Buffer { raw, count: data.len() as u32, _marker: PhantomData }
     }
 
+    pub fn index_u16(ctx: &Context, data: &[u16]) -> Buffer<u16> {
+        let raw = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Index Buffer"),
+            contents: bytemuck::cast_slice(data),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+        });
+        Buffer { raw, count: data.len() as u32, _marker: PhantomData }
+    }
+
     pub fn uniform(ctx: &Context, data: &T) -> Self {
         Self::create(ctx, &[*data], wgpu::BufferUsages::UNIFORM, "Uniform Buffer")
     }
@@ -54,6 +54,10 @@ impl<'a> RenderPass<'a> {
         self.raw.set_index_buffer(buffer.raw.slice(..), wgpu::IndexFormat::Uint32);
     }
 
+    pub fn set_index_buffer_u16(&mut self, buffer: &'a Buffer<u16>) {
+        self.raw.set_index_buffer(buffer.raw.slice(..), wgpu::IndexFormat::Uint16);
+    }
+
     pub fn set_scissor(&mut self, x: u32, y: u32, w: u32, h: u32) {
         self.raw.set_scissor_rect(x, y, w, h);
     }
@@ -22,6 +22,7 @@ pub struct PipelineBuilder<'a> {
     front_face: wgpu::FrontFace,
     blend: Option<wgpu::BlendState>,
     depth_stencil: Option<wgpu::DepthStencilState>,
+    sample_count: u32,
 }
 
 impl<'a> PipelineBuilder<'a> {
@@ -38,6 +39,7 @@ impl<'a> PipelineBuilder<'a> {
             front_face: wgpu::FrontFace::Ccw,
             blend: Some(wgpu::BlendState::ALPHA_BLENDING),
             depth_stencil: None,
+            sample_count: 1,
         }
     }
 
@@ -91,6 +93,13 @@ impl<'a> PipelineBuilder<'a> {
         self
     }
 
+    /// Количество сэмплов на пиксель (MSAA). Цель рендера должна иметь
+    /// такое же количество сэмплов
+    pub fn with_sample_count(mut self, count: u32) -> Self {
+        self.sample_count = count.max(1);
+        self
+    }
+
     pub fn build(self, target_format: wgpu::TextureFormat, bind_group_layouts: &[&wgpu::BindGroupLayout]) -> Pipeline {
         let shader = self.ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Shader Module"),
@@ -132,7 +141,10 @@ impl<'a> PipelineBuilder<'a> {
                 conservative: false,
             },
             depth_stencil: self.depth_stencil,
-            multisample: wgpu::MultisampleState::default(),
+            multisample: wgpu::MultisampleState {
+                count: self.sample_count,
+                ..Default::default()
+            },
             multiview: None,
             cache: None,
         });
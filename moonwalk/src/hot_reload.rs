@@ -0,0 +1,145 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Слежение за файлами ресурсов во время разработки (фича hot-reload). Файлы
+// опрашиваются по времени изменения не чаще чем раз в interval, без фоновых
+// потоков и системных вотчеров, поэтому работает одинаково на всех платформах.
+// Отслеживаются только ресурсы у которых есть настоящий путь: файлы из zip
+// архивов, из памяти и из assets на android не меняются во время работы
+
+use std::path::{Path, PathBuf};
+use std::sync::Weak;
+use std::time::{Duration, Instant, SystemTime};
+
+use parking_lot::RwLock;
+
+use crate::r#abstract::MoonPipeline;
+use crate::textware::FontId;
+
+// Интервал опроса по умолчанию
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Что перезагрузить при изменении файла
+#[derive(Clone)]
+pub(crate) enum ReloadTarget {
+    Texture(u32),
+    Font(FontId),
+
+    /// Пайплайн пересобирается из описания с новым исходным кодом. Описание
+    /// хранит текущий код, по нему старый вариант убирается из кэша
+    Pipeline {
+        pipeline: Weak<RwLock<wgpu::RenderPipeline>>,
+        desc: MoonPipeline,
+        layouts: Vec<wgpu::BindGroupLayout>,
+    },
+}
+
+pub(crate) struct Watch {
+    /// Логический путь, по нему файл читается заново через ResourceManager
+    pub path: String,
+    pub target: ReloadTarget,
+
+    /// false если ресурс удалён и следить за ним больше не нужно
+    pub alive: bool,
+
+    real: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+}
+
+pub(crate) struct Watcher {
+    watches: Vec<Watch>,
+    interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+impl Default for Watcher {
+    fn default() -> Self {
+        Self {
+            watches: Vec::new(),
+            interval: DEFAULT_INTERVAL,
+            last_poll: None,
+        }
+    }
+}
+
+impl Watcher {
+    pub fn watch(&mut self, path: &str, real: PathBuf, target: ReloadTarget) {
+        self.watches.push(Watch {
+            path: path.to_string(),
+            target,
+            alive: true,
+            stamp: file_stamp(&real),
+            real,
+        });
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Файлы изменившиеся с прошлой проверки. Без force проверка делается не
+    /// чаще чем раз в interval. Время изменения запоминается сразу, так что
+    /// неудачная перезагрузка повторится только после следующего сохранения
+    pub fn poll(&mut self, force: bool) -> Vec<&mut Watch> {
+        let now = Instant::now();
+
+        if !force && self.last_poll.is_some_and(|last| now.duration_since(last) < self.interval) {
+            return Vec::new();
+        }
+
+        self.last_poll = Some(now);
+        self.watches.retain(|watch| watch.alive);
+
+        self.watches.iter_mut()
+            .filter_map(|watch| {
+                // Пока редактор пересохраняет файл его может не быть на месте,
+                // такая проверка просто пропускается
+                let stamp = file_stamp(&watch.real)?;
+
+                let changed = watch.stamp != Some(stamp);
+                watch.stamp = Some(stamp);
+
+                changed.then_some(watch)
+            })
+            .collect()
+    }
+}
+
+// Время изменения и размер файла. Размер нужен на файловых системах с
+// грубым временем (секунда и больше), где два сохранения подряд не различить
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+#[test]
+fn watcher_poll_test() {
+    let real = std::env::temp_dir().join(format!("moonwalk_watch_{}.txt", std::process::id()));
+    std::fs::write(&real, "a").unwrap();
+
+    let mut watcher = Watcher::default();
+    watcher.watch("watch.txt", real.clone(), ReloadTarget::Texture(1));
+
+    // Без изменений ничего не сообщается
+    assert!(watcher.poll(true).is_empty());
+
+    // Размер другой, так что изменение видно даже при грубом времени
+    std::fs::write(&real, "abc").unwrap();
+
+    let changed = watcher.poll(true);
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].path, "watch.txt");
+    assert!(matches!(changed[0].target, ReloadTarget::Texture(1)));
+
+    // Одно сохранение сообщается ровно один раз
+    assert!(watcher.poll(true).is_empty());
+
+    // Без force опрос не чаще чем раз в interval
+    std::fs::write(&real, "abcde").unwrap();
+    assert!(watcher.poll(false).is_empty());
+    assert_eq!(watcher.poll(true).len(), 1);
+
+    // Пропавший на время файл пропускается, а не считается изменением
+    std::fs::remove_file(&real).unwrap();
+    assert!(watcher.poll(true).is_empty());
+}
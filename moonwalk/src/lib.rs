@@ -17,7 +17,9 @@ pub mod error;
 pub mod rendering;
 pub mod objects;
 pub mod resource_manager;
+pub mod vfs;
 pub mod path;
+pub mod lottie;
 
 // abstract зарезервирован в расте поэтому нужно экранирование
 pub mod r#abstract;
@@ -28,12 +30,16 @@ mod debug;
 mod filters;
 mod painting;
 
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use resource_manager::ResourceManager;
 use path::PathBuilder;
 
 pub use crate::objects::ObjectId;
 pub use crate::public::brush::BlendMode;
+pub use crate::public::{TextRenderMode, Overflow, FontStyle, TextDirection, TextPathOptions, SvgObject};
 pub use crate::rendering::custom::{
     CustomPaint, MoonRenderPass, MoonBuffer, MoonBindGroup, MoonBindGroupLayout, CustomPipeline
 };
@@ -42,6 +48,13 @@ pub use crate::r#abstract::{
     BindGroup, ShaderStage, TextureType, SamplerType, CullMode, Topology, FallbackStrategy
 };
 pub use crate::public::custom::BindResource;
+pub use crate::lottie::LottiePlayer;
+pub use crate::rendering::loader::{TextureHandle, TextureLoadState, TextureCallback};
+pub use crate::rendering::texture::{TextureOptions, TextureFilter, TextureWrap, TextureFormat};
+pub use crate::rendering::atlas::{TextureAtlas, PackedAtlas, AtlasSprite};
+pub use crate::rendering::animated::{AnimatedTexture, AnimationFrame};
+pub use crate::rendering::memory::MemoryStats;
+pub use crate::filters::chain::{FilterChain, FilterStep, FilterInput, FilterOp};
 
 #[cfg(feature = "video")]
 pub use crate::rendering::video::MoonVideo;
@@ -72,13 +85,16 @@ pub struct MoonWalk {
 pub struct FontAsset(pub u64);
 
 /// Типы выранивания текста. Влево, вправо, по центру и по ширине (строки растягиваются так, чтобы
-/// касаться и левого и правого края блока)
+/// касаться и левого и правого края блока). Left и Right абсолютные, Start и End зависят от
+/// направления абзаца: для арабского и иврита Start это правый край
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TextAlign {
     Left,
     Center,
     Right,
     Justified,
+    Start,
+    End,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,283 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Анимируемые свойства Lottie. Свойство это либо постоянное значение
+// {"a": 0, "k": значение}, либо список ключевых кадров {"a": 1, "k": [...]}.
+// Между кадрами значение интерполируется по кубической кривой Безье из
+// полей o и i (как easing в CSS), кадр с h = 1 держит значение до следующего
+
+use serde_json::Value;
+
+/// Значение которое умеет плавно переходить в другое такое же
+pub(crate) trait Interpolate: Clone {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for Vec<f32> {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self.iter()
+            .zip(other.iter())
+            .map(|(a, b)| a + (b - a) * t)
+            .collect()
+    }
+}
+
+/// Кривая из свойства формы (sh и маски): вершины и касательные к ним.
+/// Касательные заданы относительно своих вершин
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Bezier {
+    pub closed: bool,
+    pub vertices: Vec<[f32; 2]>,
+    pub in_tangents: Vec<[f32; 2]>,
+    pub out_tangents: Vec<[f32; 2]>,
+}
+
+impl Interpolate for Bezier {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        // Кривые с разным числом вершин не смешиваются, берётся ближайшая
+        if self.vertices.len() != other.vertices.len() {
+            return if t < 1.0 { self.clone() } else { other.clone() };
+        }
+
+        let mix = |a: &[[f32; 2]], b: &[[f32; 2]]| -> Vec<[f32; 2]> {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t])
+                .collect()
+        };
+
+        Bezier {
+            closed: self.closed,
+            vertices: mix(&self.vertices, &other.vertices),
+            in_tangents: mix(&self.in_tangents, &other.in_tangents),
+            out_tangents: mix(&self.out_tangents, &other.out_tangents),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Keyframe<T> {
+    time: f32,
+    start: T,
+
+    // Конечное значение из старого формата (поле e). Если его нет то
+    // значение идёт к началу следующего кадра
+    end: Option<T>,
+    hold: bool,
+
+    // Точки кривой смягчения: выход из этого кадра и вход в следующий
+    ease_out: [f32; 2],
+    ease_in: [f32; 2],
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Animated<T> {
+    Static(T),
+    Keyframes(Vec<Keyframe<T>>),
+}
+
+impl<T: Interpolate> Animated<T> {
+    /// Разбирает свойство. Если его нет или значение не читается то
+    /// свойство постоянно равно default
+    pub fn parse(value: Option<&Value>, parse: impl Fn(&Value) -> Option<T>, default: T) -> Self {
+        let Some(k) = value.and_then(|v| v.get("k")) else {
+            return Animated::Static(default);
+        };
+
+        // Поле a есть не у всех экспортёров, поэтому кадры узнаются по виду
+        let is_keyframes = k.as_array()
+            .and_then(|list| list.first())
+            .is_some_and(|first| first.get("t").is_some());
+
+        if !is_keyframes {
+            return Animated::Static(parse(k).unwrap_or(default));
+        }
+
+        let mut keyframes: Vec<Keyframe<T>> = Vec::new();
+
+        for item in k.as_array().into_iter().flatten() {
+            let time = item.get("t").and_then(Value::as_f64).unwrap_or(0.0) as f32;
+            let end = item.get("e").and_then(&parse);
+
+            // У последнего кадра часто нет значения, только время: он
+            // продолжает конец предыдущего
+            let start = match item.get("s").and_then(&parse) {
+                Some(start) => start,
+
+                None => match keyframes.last() {
+                    Some(prev) => prev.end.clone().unwrap_or_else(|| prev.start.clone()),
+                    None => default.clone(),
+                },
+            };
+
+            keyframes.push(Keyframe {
+                time,
+                start,
+                end,
+                hold: item.get("h").and_then(Value::as_f64).is_some_and(|h| h != 0.0),
+                ease_out: ease_point(item.get("o"), [0.0, 0.0]),
+                ease_in: ease_point(item.get("i"), [1.0, 1.0]),
+            });
+        }
+
+        match keyframes.is_empty() {
+            true => Animated::Static(default),
+            false => Animated::Keyframes(keyframes),
+        }
+    }
+
+    /// Значение свойства на кадре frame
+    pub fn at(&self, frame: f32) -> T {
+        let keyframes = match self {
+            Animated::Static(value) => return value.clone(),
+            Animated::Keyframes(keyframes) => keyframes,
+        };
+
+        let first = &keyframes[0];
+
+        if frame <= first.time {
+            return first.start.clone();
+        }
+
+        let Some(i) = keyframes.windows(2).position(|w| frame < w[1].time) else {
+            let last = &keyframes[keyframes.len() - 1];
+            return last.start.clone();
+        };
+
+        let (current, next) = (&keyframes[i], &keyframes[i + 1]);
+
+        if current.hold {
+            return current.start.clone();
+        }
+
+        let end = current.end.as_ref().unwrap_or(&next.start);
+        let span = next.time - current.time;
+        let progress = if span > 0.0 { (frame - current.time) / span } else { 1.0 };
+
+        let t = cubic_ease(current.ease_out, current.ease_in, progress);
+        current.start.lerp(end, t)
+    }
+}
+
+// Точка смягчения {"x": [..], "y": [..]} или {"x": число, "y": число}. Для
+// многомерных значений берётся первая компонента, она же общая у экспортёров
+fn ease_point(value: Option<&Value>, default: [f32; 2]) -> [f32; 2] {
+    let component = |key: &str, fallback: f32| -> f32 {
+        let Some(v) = value.and_then(|v| v.get(key)) else {
+            return fallback;
+        };
+
+        let number = match v {
+            Value::Array(list) => list.first().and_then(Value::as_f64),
+            other => other.as_f64(),
+        };
+
+        number.map_or(fallback, |n| n as f32)
+    };
+
+    [component("x", default[0]), component("y", default[1])]
+}
+
+// Кривая смягчения от (0, 0) до (1, 1) с контрольными точками p1 и p2. По
+// доле времени x ищется параметр кривой (Ньютон с запасной бисекцией), по нему
+// берётся доля значения y
+pub(crate) fn cubic_ease(p1: [f32; 2], p2: [f32; 2], x: f32) -> f32 {
+    let x = x.clamp(0.0, 1.0);
+
+    // Линейное смягчение встречается чаще всего, его не нужно решать
+    if p1[0] == p1[1] && p2[0] == p2[1] {
+        return x;
+    }
+
+    let bezier = |a: f32, b: f32, s: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * a + 3.0 * inv * s * s * b + s * s * s
+    };
+
+    let derivative = |a: f32, b: f32, s: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * a + 6.0 * inv * s * (b - a) + 3.0 * s * s * (1.0 - b)
+    };
+
+    let (px1, px2) = (p1[0].clamp(0.0, 1.0), p2[0].clamp(0.0, 1.0));
+    let mut s = x;
+
+    for _ in 0..8 {
+        let error = bezier(px1, px2, s) - x;
+
+        if error.abs() < 1e-5 {
+            return bezier(p1[1], p2[1], s);
+        }
+
+        let slope = derivative(px1, px2, s);
+
+        if slope.abs() < 1e-6 {
+            break;
+        }
+
+        s = (s - error / slope).clamp(0.0, 1.0);
+    }
+
+    let (mut low, mut high) = (0.0f32, 1.0f32);
+    s = x;
+
+    for _ in 0..32 {
+        let value = bezier(px1, px2, s);
+
+        if (value - x).abs() < 1e-5 {
+            break;
+        }
+
+        if value < x { low = s } else { high = s }
+        s = (low + high) * 0.5;
+    }
+
+    bezier(p1[1], p2[1], s)
+}
+
+/// Число или массив чисел. Lottie пишет скаляры и как 5, и как [5]
+pub(crate) fn parse_numbers(value: &Value) -> Option<Vec<f32>> {
+    match value {
+        Value::Number(n) => Some(vec![n.as_f64()? as f32]),
+
+        Value::Array(list) => list.iter()
+            .map(|v| v.as_f64().map(|n| n as f32))
+            .collect(),
+
+        _ => None,
+    }
+}
+
+/// Кривая формы. В кадрах она завёрнута в массив из одного элемента
+pub(crate) fn parse_bezier(value: &Value) -> Option<Bezier> {
+    if let Value::Array(list) = value {
+        return list.first().and_then(parse_bezier);
+    }
+
+    let points = |key: &str| -> Vec<[f32; 2]> {
+        value.get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|p| {
+                let p = parse_numbers(p)?;
+                Some([*p.first()?, *p.get(1)?])
+            })
+            .collect()
+    };
+
+    let vertices = points("v");
+    let count = vertices.len();
+
+    let mut in_tangents = points("i");
+    let mut out_tangents = points("o");
+    in_tangents.resize(count, [0.0, 0.0]);
+    out_tangents.resize(count, [0.0, 0.0]);
+
+    Some(Bezier {
+        closed: value.get("c").and_then(Value::as_bool).unwrap_or(false),
+        vertices,
+        in_tangents,
+        out_tangents,
+    })
+}
@@ -0,0 +1,270 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Проигрывание анимаций Lottie. Файл разбирается один раз, каждый кадр
+// заново вычисляется в набор векторных путей (PathBuilder) и рисуется через
+// VectorSystem в текстуру или через живые объекты путей сцены. Поддерживаются
+// слои фигур, сплошные слои, прекомпозиции с перераспределением времени,
+// родители, маски, обрезка путей, сплошные и градиентные заливки и обводки
+// со штрихами и смягчение ключевых кадров. Не поддерживаются выражения,
+// трек маты (источник мата просто не рисуется), эффекты слоёв, текст,
+// картинки, повторители и 3D
+
+mod animated;
+mod model;
+mod scene;
+
+use glam::{Affine2, Vec2};
+
+use crate::error::MoonWalkError;
+use crate::objects::ObjectId;
+use crate::path::{AaMode, PathBuilder, VectorLayer};
+use crate::MoonWalk;
+use model::Composition;
+
+// Шаг z индекса между путями одного кадра, чтобы порядок отрисовки совпадал
+// с порядком слоёв
+const LOTTIE_Z_STEP: f32 = 0.0001;
+
+/// Плеер анимации Lottie. Время двигается через update, кадр рисуется через
+/// render_to_texture или render_to_objects. Номера кадров абсолютные, как в
+/// файле: от in_point до out_point
+pub struct LottiePlayer {
+    composition: Composition,
+    frame: f32,
+    playing: bool,
+    looping: bool,
+    speed: f32,
+    antialias: AaMode,
+
+    // Текстура из render_to_texture: айди и размер
+    texture: Option<(u32, u32, u32)>,
+
+    // Объекты путей из render_to_objects по порядку отрисовки
+    objects: Vec<ObjectId>,
+}
+
+impl LottiePlayer {
+    /// Разбирает анимацию из строки JSON. Плеер стоит на первом кадре на
+    /// паузе, повтор включён
+    pub fn from_json(data: &str) -> Result<Self, MoonWalkError> {
+        let composition = Composition::parse(data)?;
+
+        Ok(Self {
+            frame: composition.in_point,
+            composition,
+            playing: false,
+            looping: true,
+            speed: 1.0,
+            antialias: AaMode::default(),
+            texture: None,
+            objects: Vec::new(),
+        })
+    }
+
+    /// Размер композиции в пикселях
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.composition.width, self.composition.height)
+    }
+
+    pub fn frame_rate(&self) -> f32 {
+        self.composition.frame_rate
+    }
+
+    /// Первый и последний (не включительно) кадр анимации
+    pub fn frame_range(&self) -> (f32, f32) {
+        (self.composition.in_point, self.composition.out_point)
+    }
+
+    /// Длительность в секундах при скорости 1
+    pub fn duration(&self) -> f32 {
+        (self.composition.out_point - self.composition.in_point).max(0.0) / self.composition.frame_rate
+    }
+
+    /// Текущий кадр (дробный, между кадрами значения интерполируются)
+    pub fn frame(&self) -> f32 {
+        self.frame
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Пауза и возврат на первый кадр
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.frame = self.composition.in_point;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Повтор анимации. Без повтора плеер останавливается на последнем кадре
+    /// (или на первом при обратном проигрывании)
+    pub fn set_loop(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Множитель скорости, отрицательное значение проигрывает назад
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Сглаживание для render_to_texture (по умолчанию MSAA 4x)
+    pub fn set_antialias(&mut self, mode: AaMode) {
+        self.antialias = mode;
+    }
+
+    /// Переходит на кадр frame. Значение ограничивается диапазоном анимации
+    pub fn seek(&mut self, frame: f32) {
+        let (first, last) = self.last_frames();
+        self.frame = frame.clamp(first, last);
+    }
+
+    /// Переходит на момент seconds от начала анимации
+    pub fn seek_time(&mut self, seconds: f32) {
+        self.seek(self.composition.in_point + seconds * self.composition.frame_rate);
+    }
+
+    /// Двигает время на dt секунд если плеер играет. Возвращает true если
+    /// кадр изменился и его стоит перерисовать
+    pub fn update(&mut self, dt: f32) -> bool {
+        if !self.playing || dt <= 0.0 || self.speed == 0.0 {
+            return false;
+        }
+
+        let (first, last) = self.last_frames();
+        let previous = self.frame;
+        let frame = self.frame + dt * self.composition.frame_rate * self.speed;
+        let length = self.composition.out_point - first;
+
+        self.frame = if self.looping && length > 0.0 {
+            first + (frame - first).rem_euclid(length)
+        } else {
+            if frame > last || frame < first {
+                self.playing = false;
+            }
+
+            frame.clamp(first, last)
+        };
+
+        self.frame != previous
+    }
+
+    // Первый и последний видимый кадр. out_point не включается: на нём
+    // слои уже закончились
+    fn last_frames(&self) -> (f32, f32) {
+        let first = self.composition.in_point;
+        (first, (self.composition.out_point - 1.0).max(first))
+    }
+
+    /// Текущий кадр в виде путей по порядку отрисовки (первый рисуется
+    /// первым). Композиция растягивается в прямоугольник position, size
+    pub fn frame_paths(&self, position: Vec2, size: Vec2) -> Vec<PathBuilder> {
+        let scale = size / self.size().max(Vec2::ONE);
+        let root = Affine2::from_translation(position) * Affine2::from_scale(scale);
+
+        scene::render(&self.composition, self.frame, root)
+    }
+
+    /// Рисует текущий кадр в текстуру размером width на height и возвращает
+    /// её айди. Пока размер не меняется используется одна и та же текстура
+    pub fn render_to_texture(&mut self, mw: &mut MoonWalk, width: u32, height: u32) -> u32 {
+        let (width, height) = (width.max(1), height.max(1));
+        let paths = self.frame_paths(Vec2::ZERO, Vec2::new(width as f32, height as f32));
+
+        let geometry: Vec<_> = paths.into_iter()
+            .flat_map(|mut builder| builder.tessellate_layers())
+            .collect();
+
+        let reuse = self.texture
+            .filter(|(id, w, h)| *w == width && *h == height && mw.renderer.state.textures.contains_key(id));
+
+        let state = &mw.renderer.state;
+
+        // Плеер не создаёт текстурных заливок, так что всем слоям хватает
+        // белой текстуры
+        let layers: Vec<VectorLayer> = geometry.into_iter()
+            .map(|(geometry, paint)| VectorLayer { geometry, paint, texture: &state.white_texture })
+            .collect();
+
+        if let Some((id, _, _)) = reuse {
+            let target = &state.textures[&id];
+            mw.renderer.vector_system.draw(&mw.renderer.context, &layers, width, height, self.antialias, target);
+
+            return id;
+        }
+
+        let texture = mw.renderer.vector_system.render_to_texture(
+            &mw.renderer.context,
+            &layers,
+            width,
+            height,
+            self.antialias
+        );
+
+        if let Some((old, _, _)) = self.texture.take() {
+            mw.remove_texture(old);
+        }
+
+        let id = mw.renderer.register_texture(texture);
+        self.texture = Some((id, width, height));
+
+        id
+    }
+
+    /// Рисует текущий кадр живыми объектами путей (new_path) в прямоугольнике
+    /// position, size. Объекты создаются при первом вызове и обновляются
+    /// через set_path, их z индексы начинаются с z_index. Пути рисуются без
+    /// MSAA, как и все живые пути
+    pub fn render_to_objects(&mut self, mw: &mut MoonWalk, position: Vec2, size: Vec2, z_index: f32) {
+        let paths = self.frame_paths(position, size);
+
+        // Лишние объекты от прошлого кадра больше не нужны
+        for id in self.objects.drain(paths.len().min(self.objects.len())..) {
+            mw.remove(id);
+        }
+
+        for (i, builder) in paths.into_iter().enumerate() {
+            let id = match self.objects.get(i) {
+                Some(id) => {
+                    mw.set_path(*id, builder);
+                    *id
+                }
+
+                None => {
+                    let id = mw.new_path(builder);
+                    self.objects.push(id);
+                    id
+                }
+            };
+
+            mw.set_z_index(id, z_index + i as f32 * LOTTIE_Z_STEP);
+        }
+    }
+
+    /// Объекты путей созданные render_to_objects
+    pub fn objects(&self) -> &[ObjectId] {
+        &self.objects
+    }
+
+    /// Удаляет объекты и текстуру созданные плеером
+    pub fn clear(&mut self, mw: &mut MoonWalk) {
+        for id in self.objects.drain(..) {
+            mw.remove(id);
+        }
+
+        if let Some((id, _, _)) = self.texture.take() {
+            mw.remove_texture(id);
+        }
+    }
+}
@@ -0,0 +1,383 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Модель анимации Lottie (Bodymovin JSON). Разбирается только то что умеет
+// рисовать плеер: слои фигур, заливки, прекомпозиции и пустые слои для
+// родителей. Неизвестные слои и элементы фигур пропускаются без ошибки,
+// чтобы файл из свежей версии экспортёра всё равно открывался
+
+use std::collections::HashMap;
+
+use glam::Vec4;
+use serde_json::Value;
+
+use super::animated::{parse_bezier, parse_numbers, Animated, Bezier};
+use crate::error::MoonWalkError;
+
+type Property = Animated<Vec<f32>>;
+
+pub(crate) struct Composition {
+    pub width: f32,
+    pub height: f32,
+    pub frame_rate: f32,
+    pub in_point: f32,
+    pub out_point: f32,
+    pub layers: Vec<Layer>,
+
+    // Слои прекомпозиций по айди ассета
+    pub assets: HashMap<String, Vec<Layer>>,
+}
+
+pub(crate) struct Layer {
+    pub index: Option<i64>,
+    pub parent: Option<i64>,
+    pub content: LayerContent,
+    pub transform: Transform,
+    pub masks: Vec<Mask>,
+
+    // Интервал видимости в кадрах родительской композиции, сдвиг начала и
+    // растяжение времени слоя
+    pub in_point: f32,
+    pub out_point: f32,
+    pub start_time: f32,
+    pub stretch: f32,
+
+    // Перераспределение времени прекомпозиции (в секундах)
+    pub time_remap: Option<Property>,
+
+    // Скрытые слои и источники трек матов не рисуются
+    pub hidden: bool,
+}
+
+pub(crate) enum LayerContent {
+    Shapes(Vec<Shape>),
+    Precomp(String),
+    Solid { color: Vec4, width: f32, height: f32 },
+    Null,
+}
+
+pub(crate) struct Transform {
+    pub anchor: Property,
+    pub position: Position,
+    pub scale: Property,
+    pub rotation: Property,
+    pub opacity: Property,
+}
+
+pub(crate) enum Position {
+    Combined(Property),
+    Split(Property, Property),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MaskMode {
+    Add,
+    Subtract,
+    Intersect,
+    None,
+}
+
+pub(crate) struct Mask {
+    pub mode: MaskMode,
+    pub path: Animated<Bezier>,
+    pub inverted: bool,
+}
+
+pub(crate) enum Shape {
+    Group { items: Vec<Shape>, transform: Option<Transform> },
+    Transform(Transform),
+    Path(Animated<Bezier>),
+    Rect { position: Property, size: Property, roundness: Property },
+    Ellipse { position: Property, size: Property },
+    Star(Star),
+    Painter(Painter),
+    Trim(Trim),
+}
+
+pub(crate) struct Star {
+    pub polygon: bool,
+    pub position: Property,
+    pub points: Property,
+    pub rotation: Property,
+    pub outer_radius: Property,
+    pub inner_radius: Property,
+}
+
+pub(crate) struct Trim {
+    pub start: Property,
+    pub end: Property,
+    pub offset: Property,
+
+    // Режим 2: все пути обрезаются как один общий путь
+    pub individually: bool,
+}
+
+pub(crate) struct Painter {
+    pub source: PaintSource,
+    pub opacity: Property,
+    pub even_odd: bool,
+    pub stroke: Option<Stroke>,
+}
+
+pub(crate) enum PaintSource {
+    Color(Property),
+    Gradient(Gradient),
+}
+
+pub(crate) struct Gradient {
+    pub radial: bool,
+    pub start: Property,
+    pub end: Property,
+
+    // Количество цветных точек. Значение это сначала тройки цветов
+    // [offset, r, g, b] * count, за ними пары прозрачности [offset, a]
+    pub count: usize,
+    pub stops: Property,
+}
+
+pub(crate) struct Stroke {
+    pub width: Property,
+    pub cap: u8,
+    pub join: u8,
+    pub dashes: Vec<Property>,
+    pub dash_offset: Option<Property>,
+}
+
+impl Composition {
+    pub fn parse(data: &str) -> Result<Self, MoonWalkError> {
+        let root: Value = serde_json::from_str(data)
+            .map_err(|e| MoonWalkError::AnimationLoading(e.to_string()))?;
+
+        let number = |key: &str| root.get(key).and_then(Value::as_f64).map(|n| n as f32);
+
+        let (Some(width), Some(height), Some(layers)) = (number("w"), number("h"), root.get("layers")) else {
+            return Err(MoonWalkError::AnimationLoading("Not a Lottie file: missing w, h or layers".into()));
+        };
+
+        let mut assets = HashMap::new();
+
+        for asset in root.get("assets").and_then(Value::as_array).into_iter().flatten() {
+            if let (Some(id), Some(layers)) = (asset.get("id").and_then(Value::as_str), asset.get("layers")) {
+                assets.insert(id.to_string(), parse_layers(layers));
+            }
+        }
+
+        Ok(Self {
+            width,
+            height,
+            frame_rate: number("fr").filter(|fr| *fr > 0.0).unwrap_or(30.0),
+            in_point: number("ip").unwrap_or(0.0),
+            out_point: number("op").unwrap_or(0.0),
+            layers: parse_layers(layers),
+            assets,
+        })
+    }
+}
+
+fn parse_layers(value: &Value) -> Vec<Layer> {
+    value.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(parse_layer)
+        .collect()
+}
+
+fn parse_layer(value: &Value) -> Option<Layer> {
+    let number = |key: &str| value.get(key).and_then(Value::as_f64).map(|n| n as f32);
+    let flag = |key: &str| value.get(key).is_some_and(|v| v.as_bool() == Some(true) || v.as_f64() == Some(1.0));
+
+    let content = match value.get("ty").and_then(Value::as_i64)? {
+        0 => LayerContent::Precomp(value.get("refId").and_then(Value::as_str)?.to_string()),
+
+        1 => LayerContent::Solid {
+            color: value.get("sc").and_then(Value::as_str).and_then(parse_hex_color).unwrap_or(Vec4::ONE),
+            width: number("sw").unwrap_or(0.0),
+            height: number("sh").unwrap_or(0.0),
+        },
+
+        3 => LayerContent::Null,
+        4 => LayerContent::Shapes(parse_shapes(value.get("shapes"))),
+
+        // Картинки, текст, звук и прочее пока не рисуются, но могут быть
+        // родителями других слоёв
+        _ => LayerContent::Null,
+    };
+
+    let masks = value.get("masksProperties")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|mask| Mask {
+            mode: match mask.get("mode").and_then(Value::as_str) {
+                Some("s") => MaskMode::Subtract,
+                Some("i") => MaskMode::Intersect,
+                Some("n") => MaskMode::None,
+                _ => MaskMode::Add,
+            },
+            path: Animated::parse(mask.get("pt"), parse_bezier, Bezier::default()),
+            inverted: mask.get("inv").and_then(Value::as_bool).unwrap_or(false),
+        })
+        .collect();
+
+    Some(Layer {
+        index: value.get("ind").and_then(Value::as_i64),
+        parent: value.get("parent").and_then(Value::as_i64),
+        content,
+        transform: parse_transform(value.get("ks")),
+        masks,
+        in_point: number("ip").unwrap_or(f32::MIN),
+        out_point: number("op").unwrap_or(f32::MAX),
+        start_time: number("st").unwrap_or(0.0),
+        stretch: number("sr").filter(|sr| *sr != 0.0).unwrap_or(1.0),
+        time_remap: value.get("tm").map(|tm| property(Some(tm), &[0.0])),
+        hidden: flag("hd") || flag("td"),
+    })
+}
+
+fn parse_shapes(value: Option<&Value>) -> Vec<Shape> {
+    value.and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|item| item.get("hd").and_then(Value::as_bool) != Some(true))
+        .filter_map(parse_shape)
+        .collect()
+}
+
+fn parse_shape(value: &Value) -> Option<Shape> {
+    let prop = |key: &str, default: &[f32]| property(value.get(key), default);
+    let int = |key: &str| value.get(key).and_then(Value::as_i64);
+
+    let shape = match value.get("ty").and_then(Value::as_str)? {
+        "gr" => {
+            let mut items = parse_shapes(value.get("it"));
+
+            // Преобразование группы лежит последним элементом её списка
+            let position = items.iter().position(|item| matches!(item, Shape::Transform(_)));
+            let transform = position.map(|i| match items.remove(i) {
+                Shape::Transform(transform) => transform,
+                _ => unreachable!(),
+            });
+
+            Shape::Group { items, transform }
+        }
+
+        "tr" => Shape::Transform(parse_transform(Some(value))),
+        "sh" => Shape::Path(Animated::parse(value.get("ks"), parse_bezier, Bezier::default())),
+
+        "rc" => Shape::Rect {
+            position: prop("p", &[0.0, 0.0]),
+            size: prop("s", &[0.0, 0.0]),
+            roundness: prop("r", &[0.0]),
+        },
+
+        "el" => Shape::Ellipse {
+            position: prop("p", &[0.0, 0.0]),
+            size: prop("s", &[0.0, 0.0]),
+        },
+
+        "sr" => Shape::Star(Star {
+            polygon: int("sy") == Some(2),
+            position: prop("p", &[0.0, 0.0]),
+            points: prop("pt", &[5.0]),
+            rotation: prop("r", &[0.0]),
+            outer_radius: prop("or", &[0.0]),
+            inner_radius: prop("ir", &[0.0]),
+        }),
+
+        "tm" => Shape::Trim(Trim {
+            start: prop("s", &[0.0]),
+            end: prop("e", &[100.0]),
+            offset: prop("o", &[0.0]),
+            individually: int("m") == Some(2),
+        }),
+
+        kind @ ("fl" | "st" | "gf" | "gs") => {
+            let source = match kind {
+                "fl" | "st" => PaintSource::Color(prop("c", &[0.0, 0.0, 0.0, 1.0])),
+
+                _ => {
+                    let gradient = value.get("g");
+
+                    PaintSource::Gradient(Gradient {
+                        radial: int("t") == Some(2),
+                        start: prop("s", &[0.0, 0.0]),
+                        end: prop("e", &[0.0, 0.0]),
+                        count: gradient.and_then(|g| g.get("p")).and_then(Value::as_u64).unwrap_or(0) as usize,
+                        stops: property(gradient, &[]),
+                    })
+                }
+            };
+
+            let stroke = matches!(kind, "st" | "gs").then(|| {
+                let mut dashes = Vec::new();
+                let mut dash_offset = None;
+
+                for dash in value.get("d").and_then(Value::as_array).into_iter().flatten() {
+                    let length = property(dash.get("v"), &[0.0]);
+
+                    match dash.get("n").and_then(Value::as_str) {
+                        Some("o") => dash_offset = Some(length),
+                        _ => dashes.push(length),
+                    }
+                }
+
+                Stroke {
+                    width: prop("w", &[1.0]),
+                    cap: int("lc").unwrap_or(2) as u8,
+                    join: int("lj").unwrap_or(2) as u8,
+                    dashes,
+                    dash_offset,
+                }
+            });
+
+            Shape::Painter(Painter {
+                source,
+                opacity: prop("o", &[100.0]),
+                even_odd: int("r") == Some(2),
+                stroke,
+            })
+        }
+
+        // Повторители, слияние путей, скругление углов и прочие
+        // модификаторы не поддерживаются
+        _ => return None,
+    };
+
+    Some(shape)
+}
+
+fn parse_transform(value: Option<&Value>) -> Transform {
+    let prop = |key: &str, default: &[f32]| property(value.and_then(|v| v.get(key)), default);
+
+    // Позиция может быть разделена на отдельно анимируемые x и y
+    let split = value.and_then(|v| v.get("p")).filter(|p| p.get("s").and_then(Value::as_bool) == Some(true));
+
+    let position = match split {
+        Some(p) => Position::Split(property(p.get("x"), &[0.0]), property(p.get("y"), &[0.0])),
+        None => Position::Combined(prop("p", &[0.0, 0.0])),
+    };
+
+    Transform {
+        anchor: prop("a", &[0.0, 0.0]),
+        position,
+        scale: prop("s", &[100.0, 100.0]),
+        rotation: prop("r", &[0.0]),
+        opacity: prop("o", &[100.0]),
+    }
+}
+
+fn property(value: Option<&Value>, default: &[f32]) -> Property {
+    Animated::parse(value, parse_numbers, default.to_vec())
+}
+
+// Цвет сплошного слоя в виде "#rrggbb"
+fn parse_hex_color(hex: &str) -> Option<Vec4> {
+    let hex = hex.trim_start_matches('#');
+
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(|c| c as f32 / 255.0);
+    Some(Vec4::new(channel(0)?, channel(2)?, channel(4)?, 1.0))
+}
@@ -0,0 +1,631 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Кадр анимации в виде списка путей. Слои обходятся снизу вверх (в Lottie
+// первый слой верхний), внутри группы действуют правила After Effects:
+// заливка или обводка рисует все пути выше себя в списке группы (вместе с
+// вложенными группами), обрезка меняет все пути выше себя, а элементы ниже
+// в списке рисуются раньше. Все преобразования сразу применяются к точкам,
+// поэтому готовые пути лежат в координатах цели
+
+use glam::{Affine2, Vec2, Vec4};
+use lyon::algorithms::measure::{PathMeasurements, SampleType};
+use lyon::path::{Event, Path};
+
+use super::model::*;
+use super::animated::Bezier;
+use crate::path::{FillRule, GradientStop, LineCap, LineJoin, Paint, PathBuilder};
+
+// Точность измерения длины для обрезки
+const TRIM_TOLERANCE: f32 = 0.1;
+
+// Вложенность прекомпозиций и цепочек родителей. Защита от зацикленных файлов
+const MAX_DEPTH: usize = 16;
+
+// Магическое число для четверти окружности из кубических кривых
+const KAPPA: f32 = 0.552_284_8;
+
+// Прямоугольник который точно накрывает сцену. Нужен инвертированным и
+// вычитающим маскам как "всё вокруг"
+const HUGE: f32 = 100_000.0;
+
+#[derive(Clone, Copy)]
+struct TrimValue {
+    start: f32,
+    end: f32,
+    offset: f32,
+    individually: bool,
+}
+
+// Готовый путь слоя. Обводку приходится помнить отдельно: под маской она
+// переводится в заливку
+struct Item {
+    builder: PathBuilder,
+    stroke: bool,
+}
+
+pub(crate) fn render(composition: &Composition, frame: f32, root: Affine2) -> Vec<PathBuilder> {
+    let mut items = Vec::new();
+    render_layers(composition, &composition.layers, frame, root, 1.0, 0, &mut items);
+
+    items.into_iter().map(|item| item.builder).collect()
+}
+
+fn render_layers(
+    composition: &Composition,
+    layers: &[Layer],
+    frame: f32,
+    matrix: Affine2,
+    opacity: f32,
+    depth: usize,
+    out: &mut Vec<Item>,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+
+    for layer in layers.iter().rev() {
+        if layer.hidden || frame < layer.in_point || frame >= layer.out_point {
+            continue;
+        }
+
+        let local = local_frame(layer, frame);
+        let matrix = matrix * layer_matrix(layers, layer, frame, 0);
+
+        // Прозрачность родителей не наследуется, только преобразование
+        let opacity = opacity * scalar(&layer.transform.opacity, local, 100.0) / 100.0;
+
+        if opacity <= 0.0 {
+            continue;
+        }
+
+        let mut items = Vec::new();
+
+        match &layer.content {
+            LayerContent::Shapes(shapes) => render_group(shapes, matrix, opacity, local, &[], &mut items),
+
+            LayerContent::Precomp(id) => {
+                let Some(children) = composition.assets.get(id) else {
+                    continue;
+                };
+
+                let child_frame = match &layer.time_remap {
+                    Some(remap) => scalar(remap, local, 0.0) * composition.frame_rate,
+                    None => local,
+                };
+
+                render_layers(composition, children, child_frame, matrix, opacity, depth + 1, &mut items);
+            }
+
+            LayerContent::Solid { color, width, height } => {
+                let path = rect_path(matrix, Vec2::new(width * 0.5, height * 0.5), Vec2::new(*width, *height), 0.0);
+                let paint = Paint::Solid(*color * Vec4::new(1.0, 1.0, 1.0, opacity));
+
+                items.push(Item { builder: fill_builder(&[path], paint, false), stroke: false });
+            }
+
+            LayerContent::Null => {}
+        }
+
+        if let Some(mask) = layer_mask(layer, matrix, local) {
+            for item in &mut items {
+                item.builder = match item.stroke {
+                    true => item.builder.stroke_outline().intersection(&mask),
+                    false => item.builder.intersection(&mask),
+                };
+
+                item.stroke = false;
+            }
+        }
+
+        out.extend(items);
+    }
+}
+
+// Кадр внутри слоя с учётом сдвига начала и растяжения времени
+fn local_frame(layer: &Layer, frame: f32) -> f32 {
+    (frame - layer.start_time) / layer.stretch
+}
+
+// Преобразование слоя вместе с цепочкой родителей
+fn layer_matrix(layers: &[Layer], layer: &Layer, frame: f32, depth: usize) -> Affine2 {
+    let own = transform_matrix(&layer.transform, local_frame(layer, frame));
+
+    let parent = layer.parent
+        .filter(|_| depth < MAX_DEPTH)
+        .and_then(|index| layers.iter().find(|l| l.index == Some(index)));
+
+    match parent {
+        Some(parent) => layer_matrix(layers, parent, frame, depth + 1) * own,
+        None => own,
+    }
+}
+
+fn transform_matrix(transform: &Transform, frame: f32) -> Affine2 {
+    let anchor = vec2(&transform.anchor, frame);
+    let scale = vec2(&transform.scale, frame) / 100.0;
+    let rotation = scalar(&transform.rotation, frame, 0.0).to_radians();
+
+    let position = match &transform.position {
+        Position::Combined(p) => vec2(p, frame),
+        Position::Split(x, y) => Vec2::new(scalar(x, frame, 0.0), scalar(y, frame, 0.0)),
+    };
+
+    Affine2::from_translation(position)
+        * Affine2::from_angle(rotation)
+        * Affine2::from_scale(scale)
+        * Affine2::from_translation(-anchor)
+}
+
+// Рисует группу фигур. inherited это обрезки из родительских групп,
+// которые стоят в их списках ниже этой группы
+fn render_group(
+    items: &[Shape],
+    matrix: Affine2,
+    opacity: f32,
+    frame: f32,
+    inherited: &[TrimValue],
+    out: &mut Vec<Item>,
+) {
+    let mut paths = group_paths(items, matrix, frame);
+
+    for trim in inherited {
+        let mut all: Vec<&mut Path> = paths.iter_mut().flatten().collect();
+        apply_trim(&mut all, *trim);
+    }
+
+    for (index, item) in items.iter().enumerate().rev() {
+        match item {
+            Shape::Painter(painter) => {
+                let geometry: Vec<Path> = paths[..index].iter().flatten().cloned().collect();
+
+                if let Some(item) = paint_item(painter, &geometry, matrix, opacity, frame) {
+                    out.push(item);
+                }
+            }
+
+            Shape::Group { items: children, transform } => {
+                let (child_matrix, child_opacity) = group_transform(transform.as_ref(), matrix, frame);
+
+                // Обрезки ниже группы в этом списке действуют и на неё,
+                // ближние применяются первыми
+                let trims: Vec<TrimValue> = items[index + 1..].iter()
+                    .filter_map(|item| trim_value(item, frame))
+                    .chain(inherited.iter().copied())
+                    .collect();
+
+                render_group(children, child_matrix, opacity * child_opacity, frame, &trims, out);
+            }
+
+            _ => {}
+        }
+    }
+}
+
+// Пути каждого элемента группы после обрезок этой группы. Для вложенной
+// группы это все её пути
+fn group_paths(items: &[Shape], matrix: Affine2, frame: f32) -> Vec<Vec<Path>> {
+    let mut paths: Vec<Vec<Path>> = items.iter()
+        .map(|item| match item {
+            Shape::Group { items: children, transform } => {
+                let (child_matrix, _) = group_transform(transform.as_ref(), matrix, frame);
+                group_paths(children, child_matrix, frame).into_iter().flatten().collect()
+            }
+
+            shape => geometry(shape, matrix, frame).into_iter().collect(),
+        })
+        .collect();
+
+    for (index, item) in items.iter().enumerate() {
+        if let Some(trim) = trim_value(item, frame) {
+            let mut above: Vec<&mut Path> = paths[..index].iter_mut().flatten().collect();
+            apply_trim(&mut above, trim);
+        }
+    }
+
+    paths
+}
+
+fn group_transform(transform: Option<&Transform>, matrix: Affine2, frame: f32) -> (Affine2, f32) {
+    match transform {
+        Some(t) => (matrix * transform_matrix(t, frame), scalar(&t.opacity, frame, 100.0) / 100.0),
+        None => (matrix, 1.0),
+    }
+}
+
+fn trim_value(shape: &Shape, frame: f32) -> Option<TrimValue> {
+    let Shape::Trim(trim) = shape else {
+        return None;
+    };
+
+    Some(TrimValue {
+        start: scalar(&trim.start, frame, 0.0) / 100.0,
+        end: scalar(&trim.end, frame, 100.0) / 100.0,
+        offset: scalar(&trim.offset, frame, 0.0) / 360.0,
+        individually: trim.individually,
+    })
+}
+
+// Геометрия одного элемента фигуры в координатах цели
+fn geometry(shape: &Shape, matrix: Affine2, frame: f32) -> Option<Path> {
+    match shape {
+        Shape::Path(bezier) => bezier_path(&bezier.at(frame), matrix),
+
+        Shape::Rect { position, size, roundness } => Some(rect_path(
+            matrix,
+            vec2(position, frame),
+            vec2(size, frame),
+            scalar(roundness, frame, 0.0),
+        )),
+
+        Shape::Ellipse { position, size } => Some(ellipse_path(matrix, vec2(position, frame), vec2(size, frame))),
+        Shape::Star(star) => star_path(star, matrix, frame),
+        _ => None,
+    }
+}
+
+fn bezier_path(bezier: &Bezier, matrix: Affine2) -> Option<Path> {
+    let count = bezier.vertices.len();
+
+    if count == 0 {
+        return None;
+    }
+
+    let point = |p: [f32; 2]| to_point(matrix.transform_point2(Vec2::from(p)));
+    let vertex = |i: usize| Vec2::from(bezier.vertices[i]);
+
+    let mut builder = Path::builder();
+    builder.begin(point(bezier.vertices[0]));
+
+    let segments = if bezier.closed { count } else { count - 1 };
+
+    for i in 0..segments {
+        let next = (i + 1) % count;
+
+        builder.cubic_bezier_to(
+            point((vertex(i) + Vec2::from(bezier.out_tangents[i])).to_array()),
+            point((vertex(next) + Vec2::from(bezier.in_tangents[next])).to_array()),
+            point(bezier.vertices[next]),
+        );
+    }
+
+    builder.end(bezier.closed);
+    Some(builder.build())
+}
+
+// Прямоугольник с центром в center. Как и в After Effects путь начинается
+// в правом верхнем углу и идёт по часовой стрелке
+fn rect_path(matrix: Affine2, center: Vec2, size: Vec2, roundness: f32) -> Path {
+    let half = size.abs() * 0.5;
+    let r = roundness.clamp(0.0, half.x.min(half.y));
+    let (left, top, right, bottom) = (center.x - half.x, center.y - half.y, center.x + half.x, center.y + half.y);
+
+    let p = |x: f32, y: f32| to_point(matrix.transform_point2(Vec2::new(x, y)));
+    let k = r * (1.0 - KAPPA);
+
+    let mut builder = Path::builder();
+    builder.begin(p(right, top + r));
+    builder.line_to(p(right, bottom - r));
+
+    if r > 0.0 {
+        builder.cubic_bezier_to(p(right, bottom - k), p(right - k, bottom), p(right - r, bottom));
+    }
+
+    builder.line_to(p(left + r, bottom));
+
+    if r > 0.0 {
+        builder.cubic_bezier_to(p(left + k, bottom), p(left, bottom - k), p(left, bottom - r));
+    }
+
+    builder.line_to(p(left, top + r));
+
+    if r > 0.0 {
+        builder.cubic_bezier_to(p(left, top + k), p(left + k, top), p(left + r, top));
+    }
+
+    builder.line_to(p(right - r, top));
+
+    if r > 0.0 {
+        builder.cubic_bezier_to(p(right - k, top), p(right, top + k), p(right, top + r));
+    }
+
+    builder.end(true);
+    builder.build()
+}
+
+// Эллипс из четырёх кривых, начинается сверху и идёт по часовой стрелке
+fn ellipse_path(matrix: Affine2, center: Vec2, size: Vec2) -> Path {
+    let r = size.abs() * 0.5;
+    let k = r * KAPPA;
+    let p = |x: f32, y: f32| to_point(matrix.transform_point2(center + Vec2::new(x, y)));
+
+    let mut builder = Path::builder();
+    builder.begin(p(0.0, -r.y));
+    builder.cubic_bezier_to(p(k.x, -r.y), p(r.x, -k.y), p(r.x, 0.0));
+    builder.cubic_bezier_to(p(r.x, k.y), p(k.x, r.y), p(0.0, r.y));
+    builder.cubic_bezier_to(p(-k.x, r.y), p(-r.x, k.y), p(-r.x, 0.0));
+    builder.cubic_bezier_to(p(-r.x, -k.y), p(-k.x, -r.y), p(0.0, -r.y));
+    builder.end(true);
+    builder.build()
+}
+
+// Звезда или многоугольник. Первая вершина смотрит вверх, rotation в градусах
+fn star_path(star: &Star, matrix: Affine2, frame: f32) -> Option<Path> {
+    let center = vec2(&star.position, frame);
+    let points = scalar(&star.points, frame, 5.0).round() as usize;
+    let outer = scalar(&star.outer_radius, frame, 0.0);
+    let inner = scalar(&star.inner_radius, frame, 0.0);
+    let rotation = scalar(&star.rotation, frame, 0.0).to_radians() - std::f32::consts::FRAC_PI_2;
+
+    if points < 2 {
+        return None;
+    }
+
+    let vertices = if star.polygon { points } else { points * 2 };
+    let step = std::f32::consts::TAU / vertices as f32;
+
+    let mut builder = Path::builder();
+
+    for i in 0..vertices {
+        let radius = if star.polygon || i % 2 == 0 { outer } else { inner };
+        let angle = rotation + step * i as f32;
+        let p = to_point(matrix.transform_point2(center + Vec2::new(angle.cos(), angle.sin()) * radius));
+
+        match i {
+            0 => { builder.begin(p); }
+            _ => { builder.line_to(p); }
+        }
+    }
+
+    builder.end(true);
+    Some(builder.build())
+}
+
+// Отрезки обрезки в долях длины, с учётом сдвига по кругу. None если
+// обрезки нет (весь путь)
+fn trim_ranges(trim: TrimValue) -> Option<Vec<(f32, f32)>> {
+    let (start, end) = if trim.start <= trim.end { (trim.start, trim.end) } else { (trim.end, trim.start) };
+    let (start, end) = (start.clamp(0.0, 1.0), end.clamp(0.0, 1.0));
+
+    if end - start >= 1.0 {
+        return None;
+    }
+
+    let from = (start + trim.offset).rem_euclid(1.0);
+    let to = from + (end - start);
+
+    Some(match to > 1.0 {
+        true => vec![(from, 1.0), (0.0, to - 1.0)],
+        false => vec![(from, to)],
+    })
+}
+
+fn apply_trim(paths: &mut [&mut Path], trim: TrimValue) {
+    let Some(ranges) = trim_ranges(trim) else {
+        return;
+    };
+
+    let lengths: Vec<f32> = paths.iter()
+        .map(|p| PathMeasurements::from_path(p, TRIM_TOLERANCE).length())
+        .collect();
+
+    // В режиме individually все пути считаются одним общим путём
+    let total: f32 = lengths.iter().sum();
+    let mut offset = 0.0;
+
+    for (path, length) in paths.iter_mut().zip(lengths) {
+        let (scale, shift) = match trim.individually {
+            true => (total, offset),
+            false => (length, 0.0),
+        };
+
+        let absolute: Vec<(f32, f32)> = ranges.iter()
+            .map(|(a, b)| ((a * scale - shift).clamp(0.0, length), (b * scale - shift).clamp(0.0, length)))
+            .collect();
+
+        **path = cut_path(path, length, &absolute);
+        offset += length;
+    }
+}
+
+fn cut_path(path: &Path, length: f32, ranges: &[(f32, f32)]) -> Path {
+    let mut builder = Path::builder();
+
+    if length > 0.0 {
+        let measurements = PathMeasurements::from_path(path, TRIM_TOLERANCE);
+        let mut sampler = measurements.create_sampler(path, SampleType::Distance);
+
+        for (from, to) in ranges {
+            if to > from {
+                sampler.split_range(*from..*to, &mut builder);
+            }
+        }
+    }
+
+    builder.build()
+}
+
+// Заливка или обводка путей группы
+fn paint_item(painter: &Painter, geometry: &[Path], matrix: Affine2, opacity: f32, frame: f32) -> Option<Item> {
+    let opacity = opacity * scalar(&painter.opacity, frame, 100.0) / 100.0;
+
+    if opacity <= 0.0 || geometry.iter().all(|p| p.iter().next().is_none()) {
+        return None;
+    }
+
+    let paint = match &painter.source {
+        PaintSource::Color(color) => Paint::Solid(color_value(&color.at(frame)) * Vec4::new(1.0, 1.0, 1.0, opacity)),
+        PaintSource::Gradient(gradient) => gradient_paint(gradient, matrix, opacity, frame),
+    };
+
+    let Some(stroke) = &painter.stroke else {
+        return Some(Item { builder: fill_builder(geometry, paint, painter.even_odd), stroke: false });
+    };
+
+    // Толщина обводки масштабируется вместе с путём
+    let scale = matrix.matrix2.determinant().abs().sqrt();
+    let width = scalar(&stroke.width, frame, 1.0) * scale;
+
+    if width <= 0.0 {
+        return None;
+    }
+
+    let mut builder = path_builder(geometry);
+    builder.set_stroke(width);
+    builder.set_stroke_paint(paint);
+
+    builder.set_line_cap(match stroke.cap {
+        1 => LineCap::Butt,
+        3 => LineCap::Square,
+        _ => LineCap::Round,
+    });
+
+    builder.set_line_join(match stroke.join {
+        1 => LineJoin::Miter,
+        3 => LineJoin::Bevel,
+        _ => LineJoin::Round,
+    });
+
+    if !stroke.dashes.is_empty() {
+        let pattern: Vec<f32> = stroke.dashes.iter().map(|d| scalar(d, frame, 0.0) * scale).collect();
+        let offset = stroke.dash_offset.as_ref().map_or(0.0, |o| scalar(o, frame, 0.0) * scale);
+
+        builder.set_dash(&pattern, offset);
+    }
+
+    Some(Item { builder, stroke: true })
+}
+
+fn fill_builder(geometry: &[Path], paint: Paint, even_odd: bool) -> PathBuilder {
+    let mut builder = path_builder(geometry);
+    builder.set_fill_paint(paint);
+
+    if even_odd {
+        builder.set_fill_rule(FillRule::EvenOdd);
+    }
+
+    builder
+}
+
+fn path_builder(geometry: &[Path]) -> PathBuilder {
+    let mut builder = PathBuilder::new();
+
+    for event in geometry.iter().flat_map(|path| path.iter()) {
+        match event {
+            Event::Begin { at } => builder.move_to(at.x, at.y),
+            Event::Line { to, .. } => builder.line_to(to.x, to.y),
+            Event::Quadratic { ctrl, to, .. } => builder.quadratic_bezier_to(ctrl.x, ctrl.y, to.x, to.y),
+            Event::Cubic { ctrl1, ctrl2, to, .. } => builder.cubic_bezier_to(ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y),
+            Event::End { close: true, .. } => builder.close(),
+            Event::End { close: false, .. } => {}
+        }
+    }
+
+    builder
+}
+
+fn gradient_paint(gradient: &Gradient, matrix: Affine2, opacity: f32, frame: f32) -> Paint {
+    let start = matrix.transform_point2(vec2(&gradient.start, frame));
+    let end = matrix.transform_point2(vec2(&gradient.end, frame));
+    let values = gradient.stops.at(frame);
+
+    let count = gradient.count.min(values.len() / 4);
+    let (colors, alphas) = values.split_at(count * 4);
+
+    // Прозрачность задаётся своими точками, она берётся в точках цвета
+    let alpha_at = |offset: f32| -> f32 {
+        let pairs: Vec<(f32, f32)> = alphas.chunks_exact(2).map(|c| (c[0], c[1])).collect();
+
+        let Some(first) = pairs.first() else {
+            return 1.0;
+        };
+
+        if offset <= first.0 {
+            return first.1;
+        }
+
+        for w in pairs.windows(2) {
+            let ((a, va), (b, vb)) = (w[0], w[1]);
+
+            if offset <= b {
+                let t = if b > a { (offset - a) / (b - a) } else { 1.0 };
+                return va + (vb - va) * t;
+            }
+        }
+
+        pairs[pairs.len() - 1].1
+    };
+
+    let stops = colors.chunks_exact(4)
+        .map(|c| GradientStop::new(c[0], Vec4::new(c[1], c[2], c[3], alpha_at(c[0]) * opacity)))
+        .collect();
+
+    match gradient.radial {
+        true => Paint::Radial { center: start, radius: start.distance(end), stops },
+        false => Paint::Linear { start, end, stops },
+    }
+}
+
+// Общая маска слоя в координатах цели
+fn layer_mask(layer: &Layer, matrix: Affine2, frame: f32) -> Option<PathBuilder> {
+    let everything = || {
+        let mut b = PathBuilder::new();
+        b.move_to(-HUGE, -HUGE);
+        b.line_to(HUGE, -HUGE);
+        b.line_to(HUGE, HUGE);
+        b.line_to(-HUGE, HUGE);
+        b.close();
+        b
+    };
+
+    let mut result: Option<PathBuilder> = None;
+
+    for mask in &layer.masks {
+        if mask.mode == MaskMode::None {
+            continue;
+        }
+
+        let mut shape = match bezier_path(&mask.path.at(frame), matrix) {
+            Some(path) => path_builder(&[path]),
+            None => PathBuilder::new(),
+        };
+
+        if mask.inverted {
+            shape = everything().difference(&shape);
+        }
+
+        result = Some(match (mask.mode, result) {
+            (MaskMode::Add, Some(current)) => current.union(&shape),
+            (MaskMode::Add, None) => shape,
+            (MaskMode::Subtract, current) => current.unwrap_or_else(everything).difference(&shape),
+            (_, current) => current.unwrap_or_else(everything).intersection(&shape),
+        });
+    }
+
+    result
+}
+
+// Цвет Lottie в долях единицы. Старые экспортёры писали 0..255
+fn color_value(c: &[f32]) -> Vec4 {
+    let get = |i: usize, default: f32| c.get(i).copied().unwrap_or(default);
+    let mut color = Vec4::new(get(0, 0.0), get(1, 0.0), get(2, 0.0), get(3, 1.0));
+
+    if color.truncate().max_element() > 1.0 {
+        color = Vec4::new(color.x / 255.0, color.y / 255.0, color.z / 255.0, color.w);
+    }
+
+    color
+}
+
+fn scalar(property: &super::animated::Animated<Vec<f32>>, frame: f32, default: f32) -> f32 {
+    property.at(frame).first().copied().unwrap_or(default)
+}
+
+fn vec2(property: &super::animated::Animated<Vec<f32>>, frame: f32) -> Vec2 {
+    let v = property.at(frame);
+    Vec2::new(v.first().copied().unwrap_or(0.0), v.get(1).copied().unwrap_or(0.0))
+}
+
+fn to_point(v: Vec2) -> lyon::math::Point {
+    lyon::math::point(v.x, v.y)
+}
@@ -17,12 +17,14 @@ pub enum ObjectType {
     Unknown = 0,
     Rect = 1,
     Text = 2,
+    Path = 3,
 }
 
 impl ObjectType {
     pub fn from_u8(v: u8) -> Option<Self> {
         match v {
             1 => Some(Self::Rect),
+            3 => Some(Self::Path),
             _ => None,
         }
     }
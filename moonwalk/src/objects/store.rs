@@ -83,8 +83,37 @@ pub struct ObjectStore {
     pub font_sizes: Vec<f32>,
     pub text_bounds: Vec<Vec2>,
 
+    // Режим рендеринга текста: 0 - как у шрифта, 1 - растровый атлас, 2 - SDF
+    pub text_render_modes: Vec<u8>,
+
+    // Эффекты текста. None - эффект выключен
+    // - outline: (ширина, цвет)
+    // - shadow: (смещение, размытие, цвет)
+    // - glow: (радиус, цвет)
+    pub text_outlines: Vec<Option<(f32, Vec4)>>,
+    pub text_shadows: Vec<Option<(Vec2, f32, Vec4)>>,
+    pub text_glows: Vec<Option<(f32, Vec4)>>,
+
+    // Переполнение текста: 0 - видимо, 1 - обрезка, 2 - многоточие.
+    // Лимит строк 0 означает что лимита нет
+    pub text_overflows: Vec<u8>,
+    pub text_max_lines: Vec<u32>,
+    pub text_directions: Vec<u8>,
+
+    // Типографика: высота строки, интервал, начертание, OpenType фичи
+    pub text_styles: Vec<crate::textware::TextStyle>,
+
+    // Путь вдоль которого рисуется текст, None для обычного текста
+    pub text_paths: Vec<Option<Box<crate::textware::TextPath>>>,
+
     // Hit группы для коллизий
     pub hit_groups: Vec<u16>,
+
+    // Векторные пути. Геометрия хранится тесселированной, None для остальных
+    // объектов. path_revision растёт при каждой смене геометрии
+    pub path_ids: Vec<ObjectId>,
+    pub path_shapes: Vec<Option<Box<crate::path::PathShape>>>,
+    pub path_revision: u64,
 }
 
 impl ObjectStore {
@@ -122,11 +151,24 @@ impl ObjectStore {
             font_ids: Vec::with_capacity(128),
             font_sizes: Vec::with_capacity(128),
             text_bounds: Vec::with_capacity(128),
+            text_render_modes: Vec::with_capacity(128),
+            text_outlines: Vec::with_capacity(128),
+            text_shadows: Vec::with_capacity(128),
+            text_glows: Vec::with_capacity(128),
+            text_overflows: Vec::with_capacity(128),
+            text_max_lines: Vec::with_capacity(128),
+            text_directions: Vec::with_capacity(128),
+            text_paths: Vec::with_capacity(128),
+            text_styles: Vec::with_capacity(128),
 
             // Объекты изначально не грязные потому-что их нет
             dirty: false,
 
             hit_groups: Vec::with_capacity(1024),
+
+            path_ids: Vec::with_capacity(32),
+            path_shapes: Vec::with_capacity(1024),
+            path_revision: 0,
         }
     }
 
@@ -165,10 +207,21 @@ impl ObjectStore {
             self.font_ids[idx] = crate::textware::FontId(0);
             self.font_sizes[idx] = 0.0;
             self.text_bounds[idx] = Vec2::new(9999.0, 9999.0);
+            self.text_render_modes[idx] = 0;
+            self.text_outlines[idx] = None;
+            self.text_shadows[idx] = None;
+            self.text_glows[idx] = None;
+            self.text_overflows[idx] = 0;
+            self.text_max_lines[idx] = 0;
+            self.text_directions[idx] = 0;
+            self.text_styles[idx] = crate::textware::TextStyle::default();
+            self.text_paths[idx] = None;
 
             // Hit группа по умолчанию
             self.hit_groups[idx] = 0;
 
+            self.path_shapes[idx] = None;
+
             return idx;
         }
         
@@ -205,9 +258,20 @@ impl ObjectStore {
         self.font_ids.push(crate::textware::FontId(0));
         self.font_sizes.push(0.0);
         self.text_bounds.push(Vec2::new(9999.0, 9999.0));
+        self.text_render_modes.push(0);
+        self.text_outlines.push(None);
+        self.text_shadows.push(None);
+        self.text_glows.push(None);
+        self.text_overflows.push(0);
+        self.text_max_lines.push(0);
+        self.text_directions.push(0);
+        self.text_styles.push(crate::textware::TextStyle::default());
+        self.text_paths.push(None);
 
         self.hit_groups.push(0);
 
+        self.path_shapes.push(None);
+
         index
     }
 
@@ -242,6 +306,46 @@ impl ObjectStore {
         id
     }
 
+    /// Создаёт объект из тесселированного пути. Позиция и размер объекта
+    /// равны рамке пути, так что без изменений путь рисуется там же где был
+    /// построен
+    pub fn new_path(&mut self, shape: crate::path::PathShape) -> ObjectId {
+        let index = self.alloc_common();
+        let id = objects::ObjectId::new(objects::ObjectType::Path, index);
+
+        if self.object_types[index] != ObjectType::Path {
+            self.path_ids.push(id);
+            self.object_types[index] = ObjectType::Path;
+        }
+
+        self.positions[index] = shape.origin;
+        self.sizes[index] = shape.base_size;
+        self.set_path_shape(id, shape);
+
+        id
+    }
+
+    /// Заменяет геометрию пути. Рамка остаётся от первой геометрии объекта,
+    /// поэтому позиция, размер и вращение объекта сохраняют смысл
+    pub fn set_path_shape(&mut self, id: ObjectId, mut shape: crate::path::PathShape) {
+        let idx = id.index();
+
+        if let Some(old) = &self.path_shapes[idx] {
+            shape.origin = old.origin;
+            shape.base_size = old.base_size;
+        }
+
+        self.path_revision += 1;
+        shape.revision = self.path_revision;
+
+        self.path_shapes[idx] = Some(Box::new(shape));
+        self.dirty = true;
+    }
+
+    pub fn get_path_shape(&self, id: ObjectId) -> Option<&crate::path::PathShape> {
+        self.path_shapes.get(id.index())?.as_deref()
+    }
+
     #[inline(always)]
     pub fn set_text(&mut self, id: ObjectId, text: String) {
         let idx = id.index();
@@ -264,6 +368,114 @@ impl ObjectStore {
         self.dirty = true;
     }
 
+    #[inline(always)]
+    pub fn set_text_render_mode(&mut self, id: ObjectId, mode: u8) {
+        let idx = id.index();
+        if self.text_render_modes[idx] != mode {
+            self.text_render_modes[idx] = mode;
+            self.dirty = true;
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_text_outline(&mut self, id: ObjectId, outline: Option<(f32, Vec4)>) {
+        self.text_outlines[id.index()] = outline;
+        self.dirty = true;
+    }
+
+    #[inline(always)]
+    pub fn set_text_shadow(&mut self, id: ObjectId, shadow: Option<(Vec2, f32, Vec4)>) {
+        self.text_shadows[id.index()] = shadow;
+        self.dirty = true;
+    }
+
+    #[inline(always)]
+    pub fn set_text_glow(&mut self, id: ObjectId, glow: Option<(f32, Vec4)>) {
+        self.text_glows[id.index()] = glow;
+        self.dirty = true;
+    }
+
+    #[inline(always)]
+    pub fn set_text_overflow(&mut self, id: ObjectId, overflow: u8) {
+        let idx = id.index();
+        if self.text_overflows[idx] != overflow {
+            self.text_overflows[idx] = overflow;
+            self.dirty = true;
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_text_max_lines(&mut self, id: ObjectId, max_lines: u32) {
+        let idx = id.index();
+        if self.text_max_lines[idx] != max_lines {
+            self.text_max_lines[idx] = max_lines;
+            self.dirty = true;
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_text_direction(&mut self, id: ObjectId, direction: u8) {
+        let idx = id.index();
+        if self.text_directions[idx] != direction {
+            self.text_directions[idx] = direction;
+            self.dirty = true;
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_text_path(&mut self, id: ObjectId, path: Option<crate::textware::TextPath>) {
+        self.text_paths[id.index()] = path.map(Box::new);
+        self.dirty = true;
+    }
+
+    #[inline(always)]
+    pub fn set_text_path_offsets(&mut self, id: ObjectId, start_offset: f32, baseline_offset: f32) {
+        if let Some(path) = self.text_paths[id.index()].as_mut() {
+            path.start_offset = start_offset;
+            path.baseline_offset = baseline_offset;
+            self.dirty = true;
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_text_style(&mut self, id: ObjectId, style: crate::textware::TextStyle) {
+        let idx = id.index();
+        if self.text_styles[idx] != style {
+            self.text_styles[idx] = style;
+            self.dirty = true;
+        }
+    }
+
+    /// Параметры раскладки текста для TextWare
+    /// Параметры раскладки текста. У текста вдоль пути ширина строки это
+    /// длина пути, иначе перенос съел бы пробелы между строками на пути
+    pub fn text_params(&self, idx: usize) -> crate::textware::TextParams {
+        let max_width = match &self.text_paths[idx] {
+            Some(path) => path.length().max(1.0),
+            None => self.text_bounds[idx].x,
+        };
+
+        crate::textware::TextParams {
+            font_id: self.font_ids[idx],
+            font_size: self.font_sizes[idx],
+            max_width,
+            max_height: self.text_bounds[idx].y,
+            align: self.text_aligns[idx],
+            overflow: self.text_overflows[idx],
+            max_lines: self.text_max_lines[idx],
+            direction: self.text_directions[idx],
+            style: self.text_styles[idx],
+        }
+    }
+
+    /// Есть ли у текста хотя бы один эффект
+    #[inline(always)]
+    pub fn has_text_effects(&self, idx: usize) -> bool {
+        self.text_outlines[idx].is_some() ||
+        self.text_shadows[idx].is_some() ||
+        self.text_glows[idx].is_some()
+    }
+
     pub fn remove(&mut self, id: ObjectId) {
         let idx = id.index();
         
@@ -510,6 +722,51 @@ impl ObjectStore {
         }
     }
     
+    #[inline(always)]
+    pub fn get_text_render_mode(&self, id: ObjectId) -> u8 {
+        if id.index() < self.text_render_modes.len() {
+            self.text_render_modes[id.index()]
+        } else {
+            0
+        }
+    }
+
+    pub fn get_text_overflow(&self, id: ObjectId) -> u8 {
+        if id.index() < self.text_overflows.len() {
+            self.text_overflows[id.index()]
+        } else {
+            0
+        }
+    }
+
+    pub fn get_text_max_lines(&self, id: ObjectId) -> u32 {
+        if id.index() < self.text_max_lines.len() {
+            self.text_max_lines[id.index()]
+        } else {
+            0
+        }
+    }
+
+    pub fn get_text_direction(&self, id: ObjectId) -> u8 {
+        if id.index() < self.text_directions.len() {
+            self.text_directions[id.index()]
+        } else {
+            0
+        }
+    }
+
+    pub fn get_text_path(&self, id: ObjectId) -> Option<&crate::textware::TextPath> {
+        self.text_paths.get(id.index())?.as_deref()
+    }
+
+    pub fn get_text_style(&self, id: ObjectId) -> crate::textware::TextStyle {
+        if id.index() < self.text_styles.len() {
+            self.text_styles[id.index()]
+        } else {
+            crate::textware::TextStyle::default()
+        }
+    }
+
     /// Метод проверяет жив ли сейчас объект по айди
     #[inline(always)]
     pub fn is_alive(&self, id: ObjectId) -> bool {
@@ -0,0 +1,146 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Измерение пути, штрихи и обрезка. Всё построено на PathMeasurements из
+// lyon_algorithms: длина считается один раз, дальше выборка точки и
+// вырезание куска пути по расстоянию. Подпути идут друг за другом, их длины
+// складываются
+
+use glam::Vec2;
+use lyon::algorithms::measure::{PathMeasurements, SampleType};
+use lyon::path::Path;
+
+use super::PathBuilder;
+
+/// Измеренный путь: длина, точка и касательная на расстоянии, разбиение.
+/// Создаётся через PathBuilder::measure и не меняется при изменении билдера
+pub struct PathMeasure {
+    path: Path,
+    measurements: PathMeasurements,
+
+    // Пустой билдер с настройками исходного (цвет, обводка, заливки) для
+    // кусков из split и segment
+    style: PathBuilder,
+}
+
+impl PathMeasure {
+    pub(crate) fn new(path: Path, tolerance: f32, style: PathBuilder) -> Self {
+        let measurements = PathMeasurements::from_path(&path, tolerance.max(0.01));
+
+        Self { path, measurements, style }
+    }
+
+    /// Полная длина пути в пикселях
+    pub fn length(&self) -> f32 {
+        self.measurements.length()
+    }
+
+    /// Точка и единичная касательная на расстоянии distance от начала.
+    /// Расстояние ограничивается длиной пути, для пустого пути None
+    pub fn sample(&self, distance: f32) -> Option<(Vec2, Vec2)> {
+        let length = self.length();
+
+        if length <= 0.0 {
+            return None;
+        }
+
+        let mut sampler = self.measurements.create_sampler(&self.path, SampleType::Distance);
+        let sample = sampler.sample(distance.clamp(0.0, length));
+
+        let point = Vec2::new(sample.position().x, sample.position().y);
+        let tangent = Vec2::new(sample.tangent().x, sample.tangent().y).normalize_or_zero();
+
+        Some((point, tangent))
+    }
+
+    /// Угол касательной в радианах на расстоянии distance
+    pub fn angle_at(&self, distance: f32) -> Option<f32> {
+        self.sample(distance).map(|(_, t)| t.y.atan2(t.x))
+    }
+
+    /// Кусок пути между расстояниями start и end с настройками исходного
+    /// билдера. Значения ограничиваются длиной пути
+    pub fn segment(&self, start: f32, end: f32) -> PathBuilder {
+        let mut builder = self.style.clone();
+        self.split_into(start..end, &mut builder.builder);
+
+        builder
+    }
+
+    /// Делит путь на два по расстоянию distance
+    pub fn split(&self, distance: f32) -> (PathBuilder, PathBuilder) {
+        (self.segment(0.0, distance), self.segment(distance, self.length()))
+    }
+
+    fn split_into(&self, range: std::ops::Range<f32>, output: &mut lyon::path::Builder) {
+        if self.length() <= 0.0 {
+            return;
+        }
+
+        let mut sampler = self.measurements.create_sampler(&self.path, SampleType::Distance);
+        sampler.split_range(range, output);
+    }
+}
+
+/// Кусок пути от start до end в долях длины (0.0..1.0). Если start больше
+/// end то они меняются местами
+pub(crate) fn trim_path(path: &Path, start: f32, end: f32, tolerance: f32) -> Path {
+    let (start, end) = if start <= end { (start, end) } else { (end, start) };
+
+    let measurements = PathMeasurements::from_path(path, tolerance.max(0.01));
+    let length = measurements.length();
+
+    let mut builder = Path::builder();
+
+    if length > 0.0 {
+        let mut sampler = measurements.create_sampler(path, SampleType::Distance);
+        sampler.split_range(start.clamp(0.0, 1.0) * length..end.clamp(0.0, 1.0) * length, &mut builder);
+    }
+
+    builder.build()
+}
+
+/// Разбивает путь на штрихи. pattern чередует длины штриха и пробела, при
+/// нечётном количестве он повторяется дважды (как в SVG). offset сдвигает
+/// начало узора вдоль пути
+pub(crate) fn dash_path(path: &Path, pattern: &[f32], offset: f32, tolerance: f32) -> Path {
+    let measurements = PathMeasurements::from_path(path, tolerance.max(0.01));
+    let length = measurements.length();
+
+    let mut pattern = pattern.to_vec();
+
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_within(..);
+    }
+
+    let period: f32 = pattern.iter().sum();
+    let mut builder = Path::builder();
+
+    if length <= 0.0 || period <= 0.0 {
+        return builder.build();
+    }
+
+    let mut sampler = measurements.create_sampler(path, SampleType::Distance);
+
+    // Начинаем с начала периода до начала пути, чтобы offset работал в обе стороны
+    let mut distance = -offset.rem_euclid(period);
+    let mut index = 0;
+
+    while distance < length {
+        let dash = pattern[index];
+
+        if index % 2 == 0 && dash > 0.0 {
+            let from = distance.max(0.0);
+            let to = (distance + dash).min(length);
+
+            if to > from {
+                sampler.split_range(from..to, &mut builder);
+            }
+        }
+
+        distance += dash;
+        index = (index + 1) % pattern.len();
+    }
+
+    builder.build()
+}
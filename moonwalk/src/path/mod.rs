@@ -2,6 +2,13 @@
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2025 MoonWalk
 
 pub mod svg;
+pub mod paint;
+pub mod measure;
+pub mod ops;
+
+pub use paint::{Paint, GradientStop, MAX_GRADIENT_STOPS};
+pub use measure::PathMeasure;
+pub use ops::BooleanOp;
 
 use wgpu::util::DeviceExt;
 use lyon::math::point;
@@ -48,11 +55,27 @@ pub enum FillRule {
     EvenOdd,
 }
 
+// Юниформ слоя пути (группа 2 в path.wgsl). Проекция передаётся отдельно
+// в группе 0, так же как у остальных объектов сцены
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
-struct VectorUniform {
-    view_proj: [[f32; 4]; 4],
-    color: [f32; 4],
+pub(crate) struct PathUniform {
+    pub transform: [f32; 4],
+    pub translate: [f32; 4],
+    pub tint: [f32; 4],
+    pub paint: paint::PaintUniform,
+}
+
+impl PathUniform {
+    /// Слой без преобразования и без изменения цвета
+    pub fn identity(paint: paint::PaintUniform) -> Self {
+        Self {
+            transform: [1.0, 0.0, 0.0, 1.0],
+            translate: [0.0; 4],
+            tint: [1.0; 4],
+            paint,
+        }
+    }
 }
 
 #[repr(C)]
@@ -61,108 +84,255 @@ pub struct VectorVertex {
     pub position: [f32; 2],
 }
 
+/// Режим сглаживания векторного пути
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AaMode {
+    /// Без сглаживания, жёсткие края треугольников
+    None,
+    /// MSAA 4x: путь рисуется в мультисэмпл текстуру и усредняется в целевую.
+    /// 4 сэмпла это максимум который wgpu гарантирует без дополнительных фич (стандарт)
+    #[default]
+    Msaa4,
+}
+
+impl AaMode {
+    fn sample_count(self) -> u32 {
+        match self {
+            AaMode::None => 1,
+            AaMode::Msaa4 => 4,
+        }
+    }
+}
+
+// Все текстуры в которые рисуются пути (render target и загруженные
+// текстуры) имеют этот формат, а не формат поверхности окна
+const VECTOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Тесселированный путь для объекта сцены (new_path). Хранит слои и рамку
+/// пути на момент создания объекта: позиция и размер объекта отображают эту
+/// рамку, поэтому новая геометрия из set_path рисуется в той же системе
+/// координат и не растягивается
+#[derive(Clone)]
+pub struct PathShape {
+    pub(crate) layers: Vec<(VertexBuffers<VectorVertex, u16>, Paint)>,
+    pub(crate) origin: glam::Vec2,
+    pub(crate) base_size: glam::Vec2,
+
+    // Номер версии геометрии. Батч перезаливает буферы только при его смене
+    pub(crate) revision: u64,
+}
+
+impl PathShape {
+    /// Рамка пути (левый верхний угол и размер) в координатах пути
+    pub fn bounds(&self) -> (glam::Vec2, glam::Vec2) {
+        (self.origin, self.base_size)
+    }
+}
+
+/// Один слой пути (заливка или обводка): геометрия и её заливка. Для
+/// текстурной заливки texture это сама текстура, иначе любая (не читается)
+pub struct VectorLayer<'a> {
+    pub geometry: VertexBuffers<VectorVertex, u16>,
+    pub paint: Paint,
+    pub texture: &'a Texture,
+}
+
 /// Система для рендеринга векторной графики (векторных путей) в текстуры
 pub struct VectorSystem {
     pipeline: wgpu::RenderPipeline,
-    bind_group: Option<wgpu::BindGroup>,
+    msaa_pipeline: wgpu::RenderPipeline,
+    globals_layout: wgpu::BindGroupLayout,
+    uniform_layout: wgpu::BindGroupLayout,
+    texture_layout: wgpu::BindGroupLayout,
+
+    // Мультисэмпл текстура переиспользуется пока размер пути не меняется
+    msaa_target: Option<(wgpu::Texture, wgpu::TextureView)>,
 }
 
 impl VectorSystem {
     pub fn new(ctx: &Context) -> Result<Self, MoonWalkError> {
-        let shader_source = include_str!("path.wgsl");
-        let actual_format = ctx.config.format;
-        
-        let pipeline = MoonPipeline::new(shader_source)
-            .vertex_shader("vs_main")
-            .fragment_shader("fs_main")
-            .add_vertex_layout(
-                VertexLayout::new()
-                    .stride(8)
-                    .step_mode(StepMode::Vertex)
-                    .add_attr(
-                        VertexAttr::new()
-                            .format(Format::Float32x2)
-                            .location(0)
-                            .offset(0)
-                    )
-            )
-            .add_bind_group(
-                BindGroup::new()
-                    .add_uniform(0, ShaderStage::Both)
-            )
-            .blend(BlendMode::Alpha)
-            .cull(CullMode::None)
-            .topology(Topology::TriangleList)
-            .depth_test(false)
-            .depth_write(false)
-            .label("vector_path")
-            .build(ctx, actual_format, &[])?;
-        
+        let globals_layout = BindGroup::new()
+            .add_uniform(0, ShaderStage::Vertex)
+            .build(ctx)?;
+
+        let (texture_layout, uniform_layout) = create_path_layouts(ctx)?;
+        let layouts = [&globals_layout, &texture_layout, &uniform_layout];
+
         Ok(Self {
-            pipeline: pipeline.pipeline.raw,
-            bind_group: None,
+            pipeline: create_path_pipeline(ctx, VECTOR_FORMAT, AaMode::None, &layouts)?.raw,
+            msaa_pipeline: create_path_pipeline(ctx, VECTOR_FORMAT, AaMode::Msaa4, &layouts)?.raw,
+            globals_layout,
+            uniform_layout,
+            texture_layout,
+            msaa_target: None,
         })
     }
 
+    // Создаёт мультисэмпл цель размером с целевую текстуру если её ещё нет
+    fn prepare_msaa_target(&mut self, ctx: &Context, width: u32, height: u32) {
+        let reuse = self.msaa_target.as_ref()
+            .is_some_and(|(t, _)| t.width() == width && t.height() == height);
+
+        if !reuse {
+            let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Vector MSAA Target"),
+                size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: AaMode::Msaa4.sample_count(),
+                dimension: wgpu::TextureDimension::D2,
+                format: VECTOR_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.msaa_target = Some((texture, view));
+        }
+    }
+
+    /// Рисует слои пути в target по порядку (сначала заливка, потом обводка).
+    /// width и height задают систему координат пути. Пайплайны собраны под
+    /// RGBA8 в sRGB, в текстуру другого формата рисовать нельзя
     pub fn render(
         &mut self,
         ctx: &Context,
-        vertices: &[VectorVertex],
-        indices: &[u16],
+        layers: &[VectorLayer],
         width: u32,
         height: u32,
-        color: [f32; 4],
+        aa: AaMode,
         target: &Texture,
-    ) {
-        if vertices.is_empty() || indices.is_empty() {
-            return;
+    ) -> Result<(), MoonWalkError> {
+        if target.texture.format() != VECTOR_FORMAT {
+            return Err(MoonWalkError::TextureLoading(format!(
+                "Can't render path to {:?} texture, expected {:?}", target.texture.format(), VECTOR_FORMAT
+            )));
         }
 
-        let vertex_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vector VBO"),
-            contents: bytemuck::cast_slice(vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
+        self.draw(ctx, layers, width, height, aa, target);
 
-        let index_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vector IBO"),
-            contents: bytemuck::cast_slice(indices),
-            usage: wgpu::BufferUsages::INDEX,
-        });
+        Ok(())
+    }
 
+    // То же что и render, но без проверки формата. Для текстур которые
+    // создаются в VECTOR_FORMAT самой системой
+    pub(crate) fn draw(
+        &mut self,
+        ctx: &Context,
+        layers: &[VectorLayer],
+        width: u32,
+        height: u32,
+        aa: AaMode,
+        target: &Texture,
+    ) {
         let mut matrix_stack = crate::gpu::MatrixStack::new();
         matrix_stack.set_ortho(width as f32, height as f32);
-        
-        let uniform_data = VectorUniform {
+
+        let globals = crate::rendering::state::GlobalUniform {
             view_proj: matrix_stack.projection.to_cols_array_2d(),
-            color,
         };
 
-        let uniform_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vector Uniforms"),
-            contents: bytemuck::bytes_of(&uniform_data),
+        let globals_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Vector Globals"),
+            contents: bytemuck::bytes_of(&globals),
             usage: wgpu::BufferUsages::UNIFORM,
         });
 
-        self.bind_group = Some(ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &self.get_bind_group_layout(ctx),
+        let globals_bg = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.globals_layout,
             entries: &[wgpu::BindGroupEntry {
                 binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
+                resource: globals_buffer.as_entire_binding(),
             }],
             label: None,
-        }));
+        });
+
+        // Буферы и бинд группы для каждого непустого слоя
+        let mut draws = Vec::with_capacity(layers.len());
+
+        for layer in layers {
+            let geometry = &layer.geometry;
+
+            if geometry.vertices.is_empty() || geometry.indices.is_empty() {
+                continue;
+            }
+
+            let vertex_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Vector VBO"),
+                contents: bytemuck::cast_slice(&geometry.vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+
+            let index_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Vector IBO"),
+                contents: bytemuck::cast_slice(&geometry.indices),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+
+            let texture_size = glam::Vec2::new(
+                layer.texture.texture.width() as f32,
+                layer.texture.texture.height() as f32
+            );
+
+            let uniform_data = PathUniform::identity(layer.paint.to_uniform(texture_size));
+
+            let uniform_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Vector Uniforms"),
+                contents: bytemuck::bytes_of(&uniform_data),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+            let uniform_bg = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.uniform_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }],
+                label: None,
+            });
+
+            let texture_bg = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.texture_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&layer.texture.view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&layer.texture.sampler) },
+                ],
+                label: None,
+            });
+
+            draws.push((vertex_buffer, index_buffer, geometry.indices.len() as u32, uniform_bg, texture_bg));
+        }
+
+        if draws.is_empty() {
+            return;
+        }
+
+        if aa == AaMode::Msaa4 {
+            self.prepare_msaa_target(ctx, target.texture.width(), target.texture.height());
+        }
+
+        // При MSAA рисуем в мультисэмпл текстуру, а в целевую попадает
+        // усреднённый результат (resolve). Сами сэмплы после прохода не нужны
+        let (pipeline, view, resolve_target, store) = match (aa, &self.msaa_target) {
+            (AaMode::Msaa4, Some((_, msaa_view))) => (
+                &self.msaa_pipeline,
+                msaa_view,
+                Some(&target.view),
+                wgpu::StoreOp::Discard,
+            ),
+
+            _ => (&self.pipeline, &target.view, None, wgpu::StoreOp::Store),
+        };
 
         let mut encoder = ctx.create_encoder();
         {
             let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Vector Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &target.view,
-                    resolve_target: None,
+                    view,
+                    resolve_target,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-                        store: wgpu::StoreOp::Store,
+                        store,
                     },
                 })],
                 depth_stencil_attachment: None,
@@ -170,13 +340,16 @@ impl VectorSystem {
                 occlusion_query_set: None,
             });
 
-            pass.set_pipeline(&self.pipeline);
-            if let Some(bind_group) = &self.bind_group {
-                pass.set_bind_group(0, bind_group, &[]);
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &globals_bg, &[]);
+
+            for (vertex_buffer, index_buffer, index_count, uniform_bg, texture_bg) in &draws {
+                pass.set_bind_group(1, texture_bg, &[]);
+                pass.set_bind_group(2, uniform_bg, &[]);
+                pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                pass.draw_indexed(0..*index_count, 0, 0..1);
             }
-            pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-            pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            pass.draw_indexed(0..indices.len() as u32, 0, 0..1);
         }
 
         ctx.submit(encoder);
@@ -185,39 +358,109 @@ impl VectorSystem {
     pub fn render_to_texture(
         &mut self,
         ctx: &Context,
-        vertices: &[VectorVertex],
-        indices: &[u16],
+        layers: &[VectorLayer],
         width: u32,
         height: u32,
-        color: [f32; 4],
+        aa: AaMode,
     ) -> Texture {
         let texture = Texture::create_render_target(
             ctx, 
             width, 
             height, 
-            wgpu::TextureFormat::Rgba8UnormSrgb
+            VECTOR_FORMAT
         );
 
-        self.render(ctx, vertices, indices, width, height, color, &texture);
+        self.draw(ctx, layers, width, height, aa, &texture);
 
         texture
     }
+}
 
-    fn get_bind_group_layout(&self, ctx: &Context) -> wgpu::BindGroupLayout {
-        BindGroup::new()
-            .add_uniform(0, ShaderStage::Both)
-            .build(ctx)
-            .expect("Failed to create vector bind group layout")
+// Выгруженные бюджетом текстуры заливок загружаются обратно, иначе слой
+// нарисовался бы белой текстурой
+fn restore_paint_textures(mw: &mut crate::MoonWalk, layers: &[(VertexBuffers<VectorVertex, u16>, Paint)]) {
+    for (_, paint) in layers {
+        if let Some(id) = paint.texture_id() {
+            mw.restore_texture(id);
+        }
     }
 }
 
+// Раскладки групп 1 (текстура заливки) и 2 (юниформ слоя) шейдера path.wgsl
+pub(crate) fn create_path_layouts(
+    ctx: &Context
+) -> Result<(wgpu::BindGroupLayout, wgpu::BindGroupLayout), MoonWalkError> {
+    let texture_layout = BindGroup::new()
+        .add_texture(0, TextureType::Float)
+        .add_sampler(1, SamplerType::Linear)
+        .build(ctx)?;
+
+    let uniform_layout = BindGroup::new()
+        .add_uniform(0, ShaderStage::Both)
+        .build(ctx)?;
+
+    Ok((texture_layout, uniform_layout))
+}
+
+// Конвейер для путей. layouts это группы 0 (проекция), 1 (текстура) и 2
+// (юниформ слоя). Используется и для рендера в текстуру, и в основном проходе
+pub(crate) fn create_path_pipeline(
+    ctx: &Context,
+    format: wgpu::TextureFormat,
+    aa: AaMode,
+    layouts: &[&wgpu::BindGroupLayout]
+) -> Result<crate::gpu::Pipeline, MoonWalkError> {
+    let shader_source = include_str!("path.wgsl");
+
+    let pipeline = MoonPipeline::new(shader_source)
+        .vertex_shader("vs_main")
+        .fragment_shader("fs_main")
+        .add_vertex_layout(
+            VertexLayout::new()
+                .stride(8)
+                .step_mode(StepMode::Vertex)
+                .add_attr(
+                    VertexAttr::new()
+                        .format(Format::Float32x2)
+                        .location(0)
+                        .offset(0)
+                )
+        )
+        .blend(BlendMode::Alpha)
+        .cull(CullMode::None)
+        .topology(Topology::TriangleList)
+        .depth_test(false)
+        .depth_write(false)
+        .sample_count(aa.sample_count())
+        .label("vector_path")
+        .build(ctx, format, layouts)?;
+
+    Ok(pipeline.pipeline)
+}
+
 /// Обертка над билдером из lyon для удобного апи
+#[derive(Clone)]
 pub struct PathBuilder {
     builder: lyon::path::Builder,
     color: [f32; 4],
     is_stroke: bool,
     stroke_options: StrokeOptions,
     fill_options: FillOptions,
+    antialias: AaMode,
+
+    // Явно заданные заливки. Пока обе пустые работает старый режим: путь
+    // либо заливается, либо обводится (set_stroke) сплошным color
+    fill_paint: Option<Paint>,
+    stroke_paint: Option<Paint>,
+
+    // Штрихи обводки (пустой узор это сплошная линия) и обрезка пути
+    dash: Vec<f32>,
+    dash_offset: f32,
+    trim: Option<(f32, f32)>,
+
+    // Есть ли незавершённый подпуть. lyon требует закрыть (end) каждый
+    // подпуть перед новым move_to и перед build
+    open: bool,
 }
 
 impl PathBuilder {
@@ -232,6 +475,13 @@ impl PathBuilder {
                 .with_tolerance(0.1),
             fill_options: FillOptions::default()
                 .with_tolerance(0.1),
+            antialias: AaMode::default(),
+            fill_paint: None,
+            stroke_paint: None,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+            trim: None,
+            open: false,
         }
     }
 
@@ -269,17 +519,68 @@ impl PathBuilder {
         self.fill_options = self.fill_options.with_tolerance(tolerance);
     }
 
+    /// Режим сглаживания краёв при рендере в текстуру (по умолчанию MSAA 4x)
+    pub fn set_antialias(&mut self, mode: AaMode) {
+        self.antialias = mode;
+    }
+
+    /// Сплошной цвет пути. Используется только пока не заданы
+    /// set_fill_paint и set_stroke_paint
     pub fn set_color(&mut self, color: glam::Vec4) {
         self.color = color.to_array();
     }
 
+    /// Заливка внутренней области пути (градиент, текстура или цвет).
+    /// Вместе с set_stroke_paint путь рисуется и с заливкой, и с обводкой
+    pub fn set_fill_paint(&mut self, paint: Paint) {
+        self.fill_paint = Some(paint);
+    }
+
+    /// Заливка обводки пути. Толщина берётся из set_stroke (по умолчанию 1)
+    pub fn set_stroke_paint(&mut self, paint: Paint) {
+        self.stroke_paint = Some(paint);
+    }
+
     pub fn set_stroke(&mut self, width: f32) {
         self.is_stroke = true;
         self.stroke_options = self.stroke_options.with_line_width(width);
     }
 
+    /// Штрихи для обводки: длины штрихов и пробелов по очереди в пикселях,
+    /// например [10.0, 5.0]. Нечётный узор повторяется дважды как в SVG.
+    /// offset сдвигает узор вдоль пути, его удобно анимировать. Пустой узор
+    /// или узор с отрицательными значениями выключает штрихи. На заливку
+    /// штрихи не влияют
+    pub fn set_dash(&mut self, pattern: &[f32], offset: f32) {
+        let valid = pattern.iter().all(|v| *v >= 0.0) && pattern.iter().sum::<f32>() > 0.0;
+
+        self.dash = if valid { pattern.to_vec() } else { Vec::new() };
+        self.dash_offset = offset;
+    }
+
+    /// Оставляет от пути только кусок от start до end в долях длины
+    /// (0.0..1.0), например trim(0.0, t) для анимации прорисовки линии.
+    /// Действует и на заливку, и на обводку. trim(0.0, 1.0) выключает обрезку
+    pub fn trim(&mut self, start: f32, end: f32) {
+        self.trim = if start <= 0.0 && end >= 1.0 { None } else { Some((start, end)) };
+    }
+
+    /// Измеряет текущий путь (длина, точка на расстоянии, разбиение). Билдер
+    /// не расходуется, обрезка и штрихи не учитываются
+    pub fn measure(&self) -> PathMeasure {
+        let mut copy = self.clone();
+        let path = copy.build();
+
+        PathMeasure::new(path, self.fill_options.tolerance, copy)
+    }
+
     pub fn move_to(&mut self, x: f32, y: f32) {
+        if self.open {
+            self.builder.end(false);
+        }
+
         self.builder.begin(point(x, y));
+        self.open = true;
     }
 
     pub fn line_to(&mut self, x: f32, y: f32) {
@@ -295,93 +596,236 @@ impl PathBuilder {
     }
     
     pub fn close(&mut self) {
-        self.builder.close();
+        if self.open {
+            self.builder.close();
+            self.open = false;
+        }
     }
 
-    /// Завершает построение, тесселирует и рендерит в текстуру. Возвращает айди
-    /// новой текстуры
-    pub fn tessellate(self, mw: &mut crate::MoonWalk, width: u32, height: u32) -> u32 {
-        let path = self.builder.build();
-        
-        let mut geometry: VertexBuffers<VectorVertex, u16> = VertexBuffers::new();
-        
-        if self.is_stroke {
-            let mut tessellator = StrokeTessellator::new();
+    /// Завершает открытый подпуть и строит путь. Билдер после этого пустой
+    fn build(&mut self) -> Path {
+        if self.open {
+            self.builder.end(false);
+            self.open = false;
+        }
 
-            let _ = tessellator.tessellate_path(
-                &path,
-                &self.stroke_options,
-                &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| {
-                    VectorVertex { position: [vertex.position().x, vertex.position().y] }
-                }),
-            );
-        } else {
+        std::mem::replace(&mut self.builder, Path::builder()).build()
+    }
+
+    // Заливка и обводка с учётом старого режима с одним цветом
+    fn paints(&self) -> (Option<Paint>, Option<Paint>) {
+        if self.fill_paint.is_none() && self.stroke_paint.is_none() {
+            let solid = Paint::Solid(glam::Vec4::from_array(self.color));
+
+            return match self.is_stroke {
+                true => (None, Some(solid)),
+                false => (Some(solid), None),
+            };
+        }
+
+        (self.fill_paint.clone(), self.stroke_paint.clone())
+    }
+
+    /// Завершает построение и тесселирует путь. Возвращает слои в порядке
+    /// отрисовки: заливка, затем обводка
+    pub(crate) fn tessellate_layers(&mut self) -> Vec<(VertexBuffers<VectorVertex, u16>, Paint)> {
+        let tolerance = self.fill_options.tolerance;
+        let mut path = self.build();
+
+        if let Some((start, end)) = self.trim {
+            path = measure::trim_path(&path, start, end, tolerance);
+        }
+
+        let (fill, stroke) = self.paints();
+
+        let mut layers = Vec::new();
+
+        if let Some(paint) = fill {
+            let mut geometry: VertexBuffers<VectorVertex, u16> = VertexBuffers::new();
             let mut tessellator = FillTessellator::new();
 
             let _ = tessellator.tessellate_path(
                 &path,
                 &self.fill_options,
                 &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
-                    VectorVertex {
-                        position: [vertex.position().x, vertex.position().y]
-                    }
+                    VectorVertex { position: [vertex.position().x, vertex.position().y] }
                 }),
             );
+
+            layers.push((geometry, paint));
         }
 
-        let texture = mw.renderer.vector_system.render_to_texture(
-            &mw.renderer.context, 
-            &geometry.vertices, 
-            &geometry.indices, 
-            width, 
-            height, 
-            self.color
-        );
-        
-        mw.renderer.register_texture(texture)
-    }
+        if let Some(paint) = stroke {
+            let stroke_path = match self.dash.is_empty() {
+                true => path,
+                false => measure::dash_path(&path, &self.dash, self.dash_offset, tolerance),
+            };
 
-    pub fn tessellate_to(self, mw: &mut crate::MoonWalk, texture_id: u32, width: u32, height: u32) {
-        let path = self.builder.build();
-        
-        let mut geometry: VertexBuffers<VectorVertex, u16> = VertexBuffers::new();
-        
-        if self.is_stroke {
+            let mut geometry: VertexBuffers<VectorVertex, u16> = VertexBuffers::new();
             let mut tessellator = StrokeTessellator::new();
 
             let _ = tessellator.tessellate_path(
-                &path,
+                &stroke_path,
                 &self.stroke_options,
                 &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| {
                     VectorVertex { position: [vertex.position().x, vertex.position().y] }
                 }),
             );
-        } else {
-            let mut tessellator = FillTessellator::new();
 
-            let _ = tessellator.tessellate_path(
-                &path,
-                &self.fill_options,
-                &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
-                    VectorVertex { position: [vertex.position().x, vertex.position().y] }
-                }),
-            );
+            layers.push((geometry, paint));
         }
 
-        if let Some(texture) = mw.renderer.state.textures.get(&texture_id) {
-            mw.renderer.vector_system.render(
-                &mw.renderer.context, 
-                &geometry.vertices, 
-                &geometry.indices, 
-                width, 
-                height, 
-                self.color,
-                texture
-            );
+        layers
+    }
+
+    /// Завершает построение и тесселирует путь для объекта сцены. Рамка
+    /// считается по вершинам всех слоёв, вместе с толщиной обводки
+    pub fn into_shape(mut self) -> PathShape {
+        let layers = self.tessellate_layers();
+
+        let mut min = glam::Vec2::splat(f32::MAX);
+        let mut max = glam::Vec2::splat(f32::MIN);
+
+        for vertex in layers.iter().flat_map(|(geometry, _)| geometry.vertices.iter()) {
+            let p = glam::Vec2::from_array(vertex.position);
+            min = min.min(p);
+            max = max.max(p);
         }
+
+        let (origin, base_size) = match min.x <= max.x {
+            true => (min, max - min),
+            false => (glam::Vec2::ZERO, glam::Vec2::ZERO),
+        };
+
+        PathShape { layers, origin, base_size, revision: 0 }
+    }
+
+    /// Завершает построение, тесселирует и рендерит в текстуру. Возвращает айди
+    /// новой текстуры
+    pub fn tessellate(mut self, mw: &mut crate::MoonWalk, width: u32, height: u32) -> u32 {
+        let layers = self.tessellate_layers();
+        restore_paint_textures(mw, &layers);
+        let state = &mw.renderer.state;
+
+        let layers: Vec<VectorLayer> = layers.into_iter()
+            .map(|(geometry, paint)| {
+                let texture = paint.texture_id()
+                    .and_then(|id| state.textures.get(&id))
+                    .unwrap_or(&state.white_texture);
+
+                VectorLayer { geometry, paint, texture }
+            })
+            .collect();
+
+        let texture = mw.renderer.vector_system.render_to_texture(
+            &mw.renderer.context, 
+            &layers,
+            width, 
+            height, 
+            self.antialias
+        );
+        
+        mw.renderer.register_texture(texture)
+    }
+
+    /// То же что и tessellate, но рисует в уже существующую текстуру. Текстура
+    /// должна быть RGBA8 в sRGB (как загруженные по умолчанию), иначе
+    /// вернётся ошибка и текстура не изменится
+    pub fn tessellate_to(mut self, mw: &mut crate::MoonWalk, texture_id: u32, width: u32, height: u32) -> Result<(), MoonWalkError> {
+        let layers = self.tessellate_layers();
+        mw.keep_texture(texture_id);
+        restore_paint_textures(mw, &layers);
+        let state = &mw.renderer.state;
+
+        let Some(target) = state.textures.get(&texture_id) else {
+            return Err(MoonWalkError::TextureLoading(format!("Texture {} not found", texture_id)));
+        };
+
+        // Текстура не может быть одновременно целью и заливкой
+        let layers: Vec<VectorLayer> = layers.into_iter()
+            .map(|(geometry, paint)| {
+                let texture = paint.texture_id()
+                    .filter(|id| *id != texture_id)
+                    .and_then(|id| state.textures.get(&id))
+                    .unwrap_or(&state.white_texture);
+
+                VectorLayer { geometry, paint, texture }
+            })
+            .collect();
+
+        mw.renderer.vector_system.render(
+            &mw.renderer.context, 
+            &layers,
+            width, 
+            height, 
+            self.antialias,
+            target
+        )
+    }
+
+    /// Завершает построение и переводит путь в ломаную для текста вдоль пути
+    /// с точностью билдера (set_tolerance). Цвет и параметры обводки не
+    /// используются
+    pub fn into_text_path(mut self) -> crate::textware::TextPath {
+        let tolerance = self.fill_options.tolerance;
+        crate::textware::TextPath::new(&self.build(), tolerance)
     }
 
     pub fn get_internal_builder(&mut self) -> &mut lyon::path::Builder {
         &mut self.builder
     }
 }
+
+#[test]
+fn vector_target_format_test() {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).unwrap();
+    let (device, queue) = pollster::block_on(adapter.request_device(&Default::default(), None)).unwrap();
+
+    let ctx = Context {
+        config: wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: VECTOR_FORMAT,
+            width: 1,
+            height: 1,
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: Vec::new(),
+        },
+        adapter_info: adapter.get_info(),
+        device,
+        queue,
+        surface: None,
+        adapter,
+        instance,
+    };
+
+    let mut system = VectorSystem::new(&ctx).unwrap();
+    let white = Texture::from_raw(&ctx, &[255; 4], 1, 1, "White").unwrap();
+
+    // Белый квадрат на всю текстуру 8x8
+    let mut builder = PathBuilder::new();
+    builder.move_to(0.0, 0.0);
+    builder.line_to(8.0, 0.0);
+    builder.line_to(8.0, 8.0);
+    builder.line_to(0.0, 8.0);
+    builder.close();
+
+    let layers: Vec<VectorLayer> = builder.tessellate_layers().into_iter()
+        .map(|(geometry, paint)| VectorLayer { geometry, paint, texture: &white })
+        .collect();
+
+    let srgb = Texture::from_raw(&ctx, &[0; 8 * 8 * 4], 8, 8, "Srgb").unwrap();
+    system.render(&ctx, &layers, 8, 8, AaMode::None, &srgb).unwrap();
+    assert_eq!(srgb.read_pixel(&ctx, 4, 4).unwrap(), [255; 4]);
+
+    // В линейную текстуру путь не рисуется, она остаётся как была
+    let options = crate::rendering::texture::TextureOptions::default().srgb(false);
+    let linear = Texture::from_raw_with_options(&ctx, &[0; 8 * 8 * 4], 8, 8, "Linear", &options).unwrap();
+    assert!(matches!(system.render(&ctx, &layers, 8, 8, AaMode::Msaa4, &linear), Err(MoonWalkError::TextureLoading(_))));
+    assert_eq!(linear.read_pixel(&ctx, 4, 4).unwrap(), [0; 4]);
+
+    let mask = Texture::create_dynamic(&ctx, 8, 8, crate::rendering::texture::TextureFormat::R8Unorm, "Mask").unwrap();
+    assert!(system.render(&ctx, &layers, 8, 8, AaMode::None, &mask).is_err());
+}
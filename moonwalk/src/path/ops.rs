@@ -0,0 +1,350 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Булевы операции, смещение контура и упрощение пути. Кривые переводятся в
+// ломаные с точностью билдера (set_tolerance), дальше с ломаными работает
+// i_overlay. Результат это новый билдер из замкнутых контуров с настройками
+// исходного (цвет, заливка, обводка), его можно тесселировать как обычно
+
+use std::fmt::Write;
+
+use i_overlay::core::fill_rule::FillRule as OverlayFillRule;
+use i_overlay::core::overlay_rule::OverlayRule;
+use i_overlay::float::simplify::SimplifyShape;
+use i_overlay::float::single::SingleFloatOverlay;
+use i_overlay::mesh::outline::offset::OutlineOffset;
+use i_overlay::mesh::stroke::offset::StrokeOffset;
+use i_overlay::mesh::style::{LineCap as OverlayLineCap, LineJoin as OverlayLineJoin, OutlineStyle, StrokeStyle};
+use lyon::path::iterator::PathIterator;
+use lyon::path::{Event, Path};
+
+use super::{measure, FillRule, LineJoin, Paint, PathBuilder};
+
+// Ломаная подпути и замкнут ли он
+type Contour = (Vec<[f32; 2]>, bool);
+
+/// Булева операция над двумя путями
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Объединение
+    Union,
+    /// Пересечение
+    Intersection,
+    /// Разность: первый путь без второго
+    Difference,
+    /// Исключающее или: области которые покрыты ровно одним путём
+    Xor,
+}
+
+impl PathBuilder {
+    /// Булева операция между этим путём и other. Оба пути считаются
+    /// замкнутыми фигурами с правилом заливки этого билдера. Результат
+    /// получает настройки этого билдера
+    pub fn boolean(&self, other: &PathBuilder, op: BooleanOp) -> PathBuilder {
+        let rule = match op {
+            BooleanOp::Union => OverlayRule::Union,
+            BooleanOp::Intersection => OverlayRule::Intersect,
+            BooleanOp::Difference => OverlayRule::Difference,
+            BooleanOp::Xor => OverlayRule::Xor,
+        };
+
+        let subject = polygons(&self.flatten());
+        let clip = polygons(&other.flatten());
+
+        let shapes = subject.overlay(&clip, rule, self.overlay_fill_rule());
+        self.with_contours(shapes.into_iter().flatten().map(|c| (c, true)))
+    }
+
+    pub fn union(&self, other: &PathBuilder) -> PathBuilder {
+        self.boolean(other, BooleanOp::Union)
+    }
+
+    pub fn intersection(&self, other: &PathBuilder) -> PathBuilder {
+        self.boolean(other, BooleanOp::Intersection)
+    }
+
+    pub fn difference(&self, other: &PathBuilder) -> PathBuilder {
+        self.boolean(other, BooleanOp::Difference)
+    }
+
+    pub fn xor(&self, other: &PathBuilder) -> PathBuilder {
+        self.boolean(other, BooleanOp::Xor)
+    }
+
+    /// Смещает контур фигуры на distance пикселей: положительное значение
+    /// раздувает фигуру, отрицательное сжимает (дырки ведут себя наоборот).
+    /// join задаёт форму внешних углов. Незамкнутые подпути замыкаются
+    pub fn offset(&self, distance: f32, join: LineJoin) -> PathBuilder {
+        let tolerance = self.fill_options.tolerance.max(0.01);
+
+        // Контуры сначала приводятся к правильному обходу (внешние и дырки),
+        // иначе смещение не знает где у фигуры внешняя сторона
+        let shapes = polygons(&self.flatten()).simplify_shape(self.overlay_fill_rule());
+
+        if distance == 0.0 {
+            return self.with_contours(shapes.into_iter().flatten().map(|c| (c, true)));
+        }
+
+        let join = overlay_line_join(join, distance.abs(), tolerance);
+        let outline = shapes.outline(&OutlineStyle::new(distance).line_join(join));
+        self.with_contours(outline.into_iter().flatten().map(|c| (c, true)))
+    }
+
+    /// Переводит обводку пути в замкнутую фигуру с учётом толщины, концов,
+    /// соединений, штрихов и обрезки. Результат заливается цветом обводки,
+    /// его можно пересекать с другими путями как обычную заливку
+    pub fn stroke_outline(&self) -> PathBuilder {
+        let tolerance = self.fill_options.tolerance.max(0.01);
+        let width = self.stroke_options.line_width;
+
+        let mut path = self.clone().build();
+
+        if let Some((start, end)) = self.trim {
+            path = measure::trim_path(&path, start, end, tolerance);
+        }
+
+        if !self.dash.is_empty() {
+            path = measure::dash_path(&path, &self.dash, self.dash_offset, tolerance);
+        }
+
+        let join = match self.stroke_options.line_join {
+            lyon::tessellation::LineJoin::Round => LineJoin::Round,
+            lyon::tessellation::LineJoin::Bevel => LineJoin::Bevel,
+            _ => LineJoin::Miter,
+        };
+
+        let join = overlay_line_join(join, width * 0.5, tolerance);
+
+        let cap = || match self.stroke_options.start_cap {
+            lyon::tessellation::LineCap::Round => match overlay_line_join(LineJoin::Round, width * 0.5, tolerance) {
+                OverlayLineJoin::Round(angle) => OverlayLineCap::Round(angle),
+                _ => OverlayLineCap::Butt,
+            },
+
+            lyon::tessellation::LineCap::Square => OverlayLineCap::Square,
+            lyon::tessellation::LineCap::Butt => OverlayLineCap::Butt,
+        };
+
+        // Замкнутые и открытые подпути обводятся отдельно, обход контуров у
+        // обоих результатов согласован, так что хватает правила NonZero
+        let contours = flatten_path(&path, tolerance);
+        let mut shapes = Vec::new();
+
+        for closed in [true, false] {
+            let lines: Vec<Vec<[f32; 2]>> = contours.iter()
+                .filter(|(points, c)| *c == closed && points.len() >= 2)
+                .map(|(points, _)| points.clone())
+                .collect();
+
+            if lines.is_empty() || width <= 0.0 {
+                continue;
+            }
+
+            let style = StrokeStyle::new(width)
+                .line_join(join.clone())
+                .start_cap(cap())
+                .end_cap(cap());
+
+            shapes.extend(lines.stroke(style, closed));
+        }
+
+        let (_, stroke) = self.paints();
+
+        let mut builder = self.with_contours(shapes.into_iter().flatten().map(|c| (c, true)));
+        builder.is_stroke = false;
+        builder.fill_paint = Some(stroke.unwrap_or(Paint::Solid(glam::Vec4::from_array(self.color))));
+        builder.stroke_paint = None;
+        builder.dash.clear();
+        builder.trim = None;
+        builder.set_fill_rule(FillRule::NonZero);
+
+        builder
+    }
+
+    /// Упрощает путь: кривые переводятся в ломаные, а из ломаных убираются
+    /// точки которые отходят от прямой меньше чем на tolerance пикселей
+    /// (алгоритм Рамера-Дугласа-Пекера). Замкнутость подпутей сохраняется
+    pub fn simplify(&self, tolerance: f32) -> PathBuilder {
+        let tolerance = tolerance.max(0.0);
+
+        let contours: Vec<Contour> = self.flatten().into_iter()
+            .map(|(points, closed)| (simplify_polyline(&points, tolerance, closed), closed))
+            .filter(|(points, closed)| points.len() >= if *closed { 3 } else { 2 })
+            .collect();
+
+        self.with_contours(contours)
+    }
+
+    /// Переводит путь в строку для атрибута d в SVG. Кривые сохраняются как
+    /// есть (Q и C), обрезка и штрихи не учитываются
+    pub fn to_svg_path_string(&self) -> String {
+        let path = self.clone().build();
+        let mut out = String::new();
+
+        for event in path.iter() {
+            let _ = match event {
+                Event::Begin { at } => write!(out, "M{} {} ", at.x, at.y),
+                Event::Line { to, .. } => write!(out, "L{} {} ", to.x, to.y),
+
+                Event::Quadratic { ctrl, to, .. } => {
+                    write!(out, "Q{} {} {} {} ", ctrl.x, ctrl.y, to.x, to.y)
+                }
+
+                Event::Cubic { ctrl1, ctrl2, to, .. } => write!(
+                    out,
+                    "C{} {} {} {} {} {} ",
+                    ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y
+                ),
+
+                Event::End { close: true, .. } => write!(out, "Z "),
+                Event::End { close: false, .. } => Ok(()),
+            };
+        }
+
+        out.truncate(out.trim_end().len());
+        out
+    }
+
+    // Подпути в виде ломаных с допуском билдера
+    fn flatten(&self) -> Vec<Contour> {
+        let path: Path = self.clone().build();
+        flatten_path(&path, self.fill_options.tolerance.max(0.01))
+    }
+
+    fn overlay_fill_rule(&self) -> OverlayFillRule {
+        match self.fill_options.fill_rule {
+            lyon::tessellation::FillRule::EvenOdd => OverlayFillRule::EvenOdd,
+            lyon::tessellation::FillRule::NonZero => OverlayFillRule::NonZero,
+        }
+    }
+
+    // Новый билдер с настройками этого и заданными подпутями
+    fn with_contours(&self, contours: impl IntoIterator<Item = Contour>) -> PathBuilder {
+        let mut builder = self.clone();
+        builder.builder = Path::builder();
+        builder.open = false;
+
+        for (points, closed) in contours {
+            let Some((first, rest)) = points.split_first() else {
+                continue;
+            };
+
+            builder.move_to(first[0], first[1]);
+
+            for p in rest {
+                builder.line_to(p[0], p[1]);
+            }
+
+            if closed {
+                builder.close();
+            }
+        }
+
+        builder
+    }
+}
+
+fn flatten_path(path: &Path, tolerance: f32) -> Vec<Contour> {
+    let mut contours = Vec::new();
+    let mut current = Vec::new();
+
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            Event::Begin { at } => current.push([at.x, at.y]),
+            Event::Line { to, .. } => current.push([to.x, to.y]),
+
+            Event::End { close, .. } => {
+                contours.push((std::mem::take(&mut current), close));
+            }
+
+            _ => {}
+        }
+    }
+
+    contours
+}
+
+// Форма углов для i_overlay. radius это половина толщины или расстояние
+// смещения, от него зависит шаг дуги скругления
+fn overlay_line_join(join: LineJoin, radius: f32, tolerance: f32) -> OverlayLineJoin<f32> {
+    match join {
+        // Минимальный острый угол как у SVG с miterlimit 4
+        LineJoin::Miter => OverlayLineJoin::Miter(2.0 * 0.25f32.asin()),
+
+        // Шаг дуги по допуску: хорда отходит от окружности не больше чем на tolerance
+        LineJoin::Round => {
+            let radius = radius.max(tolerance);
+            OverlayLineJoin::Round(2.0 * (1.0 - tolerance / radius).max(0.0).acos())
+        }
+
+        LineJoin::Bevel => OverlayLineJoin::Bevel,
+    }
+}
+
+// Фигуры для i_overlay: все подпути как замкнутые контуры
+fn polygons(contours: &[Contour]) -> Vec<Vec<[f32; 2]>> {
+    contours.iter()
+        .filter(|(points, _)| points.len() >= 3)
+        .map(|(points, _)| points.clone())
+        .collect()
+}
+
+// Рамер-Дуглас-Пекер. У замкнутой ломаной первая точка считается и
+// последней, чтобы упрощался и замыкающий отрезок
+fn simplify_polyline(points: &[[f32; 2]], tolerance: f32, closed: bool) -> Vec<[f32; 2]> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let mut line = points.to_vec();
+
+    if closed {
+        line.push(points[0]);
+    }
+
+    let mut keep = vec![false; line.len()];
+    keep[0] = true;
+    keep[line.len() - 1] = true;
+
+    let mut stack = vec![(0, line.len() - 1)];
+
+    while let Some((start, end)) = stack.pop() {
+        let mut farthest = 0.0;
+        let mut index = start;
+
+        for i in start + 1..end {
+            let d = segment_distance(line[i], line[start], line[end]);
+
+            if d > farthest {
+                farthest = d;
+                index = i;
+            }
+        }
+
+        if farthest > tolerance {
+            keep[index] = true;
+            stack.push((start, index));
+            stack.push((index, end));
+        }
+    }
+
+    let mut result: Vec<[f32; 2]> = line.into_iter()
+        .zip(keep)
+        .filter_map(|(p, k)| k.then_some(p))
+        .collect();
+
+    if closed {
+        result.pop();
+    }
+
+    result
+}
+
+// Расстояние от точки до отрезка a-b
+fn segment_distance(p: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let (p, a, b) = (glam::Vec2::from(p), glam::Vec2::from(a), glam::Vec2::from(b));
+    let ab = b - a;
+    let t = if ab.length_squared() > 0.0 { ((p - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0) } else { 0.0 };
+
+    p.distance(a + ab * t)
+}
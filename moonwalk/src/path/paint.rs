@@ -0,0 +1,154 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use glam::{Vec2, Vec4};
+use bytemuck::{Pod, Zeroable};
+
+/// Максимальное количество точек градиента, лишние точки отбрасываются
+pub const MAX_GRADIENT_STOPS: usize = 8;
+
+/// Точка градиента: позиция от 0.0 до 1.0 и цвет в ней
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Vec4,
+}
+
+impl GradientStop {
+    pub fn new(offset: f32, color: Vec4) -> Self {
+        Self { offset, color }
+    }
+}
+
+/// Заливка для векторного пути. Используется и для заливки, и для обводки.
+/// Координаты градиентов и текстуры задаются в пространстве пути (в тех же
+/// пикселях что и move_to/line_to). За пределами градиента берётся цвет
+/// крайней точки
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    /// Сплошной цвет
+    Solid(Vec4),
+
+    /// Линейный градиент от start к end
+    Linear {
+        start: Vec2,
+        end: Vec2,
+        stops: Vec<GradientStop>,
+    },
+
+    /// Радиальный градиент от центра (offset 0.0) до окружности радиуса radius
+    Radial {
+        center: Vec2,
+        radius: f32,
+        stops: Vec<GradientStop>,
+    },
+
+    /// Конический градиент вокруг центра. Начинается с угла angle (радианы,
+    /// 0 это направление вправо) и идёт по часовой стрелке полный оборот
+    Conic {
+        center: Vec2,
+        angle: f32,
+        stops: Vec<GradientStop>,
+    },
+
+    /// Текстура (паттерн) по айди из load_texture. Текстура размером size
+    /// начинается в точке origin и повторяется по обеим осям. Нулевой size
+    /// означает размер самой текстуры в пикселях
+    Texture {
+        texture_id: u32,
+        origin: Vec2,
+        size: Vec2,
+    },
+}
+
+impl Default for Paint {
+    fn default() -> Self {
+        Paint::Solid(Vec4::ONE)
+    }
+}
+
+impl Paint {
+    /// Линейный градиент из двух цветов
+    pub fn linear(start: Vec2, end: Vec2, from: Vec4, to: Vec4) -> Self {
+        Paint::Linear {
+            start,
+            end,
+            stops: vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+        }
+    }
+
+    /// Радиальный градиент из двух цветов, from в центре
+    pub fn radial(center: Vec2, radius: f32, from: Vec4, to: Vec4) -> Self {
+        Paint::Radial {
+            center,
+            radius,
+            stops: vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+        }
+    }
+
+    /// Айди текстуры если это текстурная заливка
+    pub fn texture_id(&self) -> Option<u32> {
+        match self {
+            Paint::Texture { texture_id, .. } => Some(*texture_id),
+            _ => None,
+        }
+    }
+
+    // Упаковка в юниформ шейдера path.wgsl. texture_size нужен для текстуры
+    // с нулевым размером
+    pub(crate) fn to_uniform(&self, texture_size: Vec2) -> PaintUniform {
+        let mut uniform = PaintUniform::zeroed();
+
+        let (kind, geometry, stops) = match self {
+            Paint::Solid(color) => {
+                uniform.color = color.to_array();
+                (0, [0.0; 4], None)
+            }
+
+            Paint::Linear { start, end, stops } => (1, [start.x, start.y, end.x, end.y], Some(stops)),
+            Paint::Radial { center, radius, stops } => (2, [center.x, center.y, *radius, 0.0], Some(stops)),
+            Paint::Conic { center, angle, stops } => (3, [center.x, center.y, *angle, 0.0], Some(stops)),
+
+            Paint::Texture { origin, size, .. } => {
+                let size = if size.x > 0.0 && size.y > 0.0 { *size } else { texture_size };
+
+                uniform.color = [1.0; 4];
+                (4, [origin.x, origin.y, size.x.max(1.0), size.y.max(1.0)], None)
+            }
+        };
+
+        let mut count = 0;
+
+        if let Some(stops) = stops {
+            let mut sorted: Vec<GradientStop> = stops.iter().take(MAX_GRADIENT_STOPS).copied().collect();
+            sorted.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+
+            for (i, stop) in sorted.iter().enumerate() {
+                uniform.stop_colors[i] = stop.color.to_array();
+                uniform.stop_offsets[i / 4][i % 4] = stop.offset.clamp(0.0, 1.0);
+            }
+
+            count = sorted.len() as u32;
+        }
+
+        uniform.kind = [kind, count, 0, 0];
+        uniform.geometry = geometry;
+
+        uniform
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub(crate) struct PaintUniform {
+    pub color: [f32; 4],
+
+    // Тип заливки и количество точек градиента
+    pub kind: [u32; 4],
+
+    // Параметры: линейный (start, end), радиальный (center, radius),
+    // конический (center, angle), текстура (origin, size)
+    pub geometry: [f32; 4],
+    pub stop_colors: [[f32; 4]; MAX_GRADIENT_STOPS],
+    pub stop_offsets: [[f32; 4]; MAX_GRADIENT_STOPS / 4],
+}
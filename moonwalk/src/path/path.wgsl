@@ -1,23 +1,126 @@
 // Часть проекта MoonWalk с открытым исходным кодом.
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2025 MoonWalk
 
-struct Uniforms {
+const PAINT_SOLID: u32 = 0u;
+const PAINT_LINEAR: u32 = 1u;
+const PAINT_RADIAL: u32 = 2u;
+const PAINT_CONIC: u32 = 3u;
+const PAINT_TEXTURE: u32 = 4u;
+
+const TAU: f32 = 6.28318530718;
+
+struct Globals {
     view_proj: mat4x4<f32>,
+};
+
+struct Uniforms {
+    // Аффинное преобразование пути: матрица 2x2 по столбцам и смещение.
+    // Для рендера в текстуру это единичное преобразование
+    transform: vec4<f32>,
+    translate: vec4<f32>,
+
+    // Множитель цвета, для объектов сцены это цвет объекта
+    tint: vec4<f32>,
+
     color: vec4<f32>,
+    // x - тип заливки, y - количество точек градиента
+    kind: vec4<u32>,
+    geometry: vec4<f32>,
+    stop_colors: array<vec4<f32>, 8>,
+    stop_offsets: array<vec4<f32>, 2>,
 };
 
-@group(0) @binding(0) var<uniform> params: Uniforms;
+@group(0) @binding(0) var<uniform> globals: Globals;
+@group(1) @binding(0) var paint_texture: texture_2d<f32>;
+@group(1) @binding(1) var paint_sampler: sampler;
+@group(2) @binding(0) var<uniform> params: Uniforms;
 
 struct VertexInput {
     @location(0) position: vec2<f32>,
 };
 
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) local: vec2<f32>,
+};
+
 @vertex
-fn vs_main(in: VertexInput) -> @builtin(position) vec4<f32> {
-    return params.view_proj * vec4<f32>(in.position, 0.0, 1.0);
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let transform = mat2x2<f32>(params.transform.xy, params.transform.zw);
+    let world = transform * in.position + params.translate.xy;
+
+    out.clip_position = globals.view_proj * vec4<f32>(world, 0.0, 1.0);
+    out.local = in.position;
+    return out;
+}
+
+fn stop_offset(i: u32) -> f32 {
+    return params.stop_offsets[i / 4u][i % 4u];
+}
+
+// Цвет градиента в точке t между отсортированными точками
+fn gradient(t: f32) -> vec4<f32> {
+    let count = params.kind.y;
+
+    if (count == 0u) {
+        return params.color;
+    }
+
+    var color = params.stop_colors[0];
+
+    for (var i = 1u; i < count; i = i + 1u) {
+        let a = stop_offset(i - 1u);
+        let b = stop_offset(i);
+
+        if (t >= b) {
+            color = params.stop_colors[i];
+        } else if (t > a) {
+            let k = (t - a) / max(b - a, 0.00001);
+            color = mix(params.stop_colors[i - 1u], params.stop_colors[i], k);
+        }
+    }
+
+    return color;
 }
 
 @fragment
-fn fs_main() -> @location(0) vec4<f32> {
-    return params.color;
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return paint(in) * params.tint;
+}
+
+// Заливка в точке пути. Координаты заливки задаются до преобразования
+fn paint(in: VertexOutput) -> vec4<f32> {
+    let g = params.geometry;
+
+    // Текстура сэмплируется всегда, чтобы не нарушать uniform control flow
+    let uv = fract((in.local - g.xy) / max(g.zw, vec2<f32>(1.0)));
+    let texel = textureSample(paint_texture, paint_sampler, uv);
+
+    switch params.kind.x {
+        case PAINT_LINEAR: {
+            let d = g.zw - g.xy;
+            let t = dot(in.local - g.xy, d) / max(dot(d, d), 0.00001);
+            return gradient(clamp(t, 0.0, 1.0));
+        }
+
+        case PAINT_RADIAL: {
+            let t = length(in.local - g.xy) / max(g.z, 0.00001);
+            return gradient(clamp(t, 0.0, 1.0));
+        }
+
+        case PAINT_CONIC: {
+            let d = in.local - g.xy;
+            let t = fract((atan2(d.y, d.x) - g.z) / TAU);
+            return gradient(t);
+        }
+
+        case PAINT_TEXTURE: {
+            return texel * params.color;
+        }
+
+        default: {
+            return params.color;
+        }
+    }
 }
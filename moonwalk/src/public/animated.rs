@@ -0,0 +1,61 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use std::path::Path;
+
+use crate::error::MoonWalkError;
+use crate::rendering::animated::{decode_animation, AnimatedFrames, AnimatedTexture};
+use crate::MoonWalk;
+
+impl MoonWalk {
+    /// Загружает анимированный GIF, APNG или WebP по пути как и load_texture.
+    /// Все кадры сразу попадают в видеопамять, объектам ставится
+    /// anim.texture_id() и дальше они анимируются сами. Время двигается через
+    /// update_animated_textures. Обычная картинка загружается как анимация
+    /// из одного кадра. Пример:
+    ///  let anim = mw.load_animated_texture("loader.gif")?;
+    ///  mw.set_texture(rect, anim.texture_id());
+    ///  // каждый кадр
+    ///  mw.update_animated_textures(dt);
+    pub fn load_animated_texture(&mut self, path: &str) -> Result<AnimatedTexture, MoonWalkError> {
+        let bytes = self.resources.read_bytes(path)?;
+
+        let label = Path::new(path).file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Animated Texture");
+
+        self.load_animated_texture_from_bytes(&bytes, label)
+    }
+
+    /// То же что load_animated_texture, но из байт файла
+    pub fn load_animated_texture_from_bytes(&mut self, bytes: &[u8], label: &str) -> Result<AnimatedTexture, MoonWalkError> {
+        let frames = decode_animation(bytes)?;
+        AnimatedFrames::upload(self, frames, label)
+    }
+
+    /// Двигает время всех анимированных текстур на dt секунд. Кадр
+    /// копируется только когда он сменился
+    pub fn update_animated_textures(&mut self, dt: f32) {
+        let mut animations = std::mem::take(&mut self.resources.animations);
+
+        for animation in &mut animations {
+            animation.update(self, dt);
+        }
+
+        self.resources.animations = animations;
+    }
+
+    // Если texture_id это текстура показа анимации, удаляет и страницы с
+    // её кадрами
+    pub(crate) fn remove_animation(&mut self, texture_id: u32) {
+        let Some(index) = self.resources.animations.iter().position(|a| a.texture_id() == texture_id) else {
+            return;
+        };
+
+        let animation = self.resources.animations.swap_remove(index);
+
+        for page in animation.pages() {
+            self.renderer.remove_texture(*page);
+        }
+    }
+}
@@ -123,6 +123,9 @@ impl MoonWalk {
 
     /// [WAIT DOC]
     pub fn draw_stroke(&mut self, target_id: u32, brush: &Brush, from: Vec2, to: Vec2) {
+        self.keep_texture(target_id);
+        self.restore_texture(brush.texture_id);
+
         let texture_res = self.renderer.state.textures.get(&target_id);
         if texture_res.is_none() {
             return;
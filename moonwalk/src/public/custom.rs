@@ -1,6 +1,9 @@
 // Часть проекта MoonWalk с открытым исходным кодом.
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
 
+use std::sync::Arc;
+
+use parking_lot::RwLock;
 use wgpu::util::DeviceExt;
 
 use crate::MoonWalk;
@@ -107,15 +110,47 @@ impl MoonWalk {
         )?;
         
         Ok(CustomPipeline {
-            raw: result.pipeline.raw
+            raw: Arc::new(RwLock::new(result.pipeline.raw))
         })
     }
 
+    /// Как compile_pipeline, но исходный код шейдера читается из файла path
+    /// через ResourceManager (с учётом точек монтирования). С фичей hot-reload
+    /// пайплайн пересобирается при изменении файла, все клоны CustomPipeline
+    /// сразу рисуют новым шейдером
+    pub fn load_pipeline(
+        &mut self,
+        path: &str,
+        pipeline_desc: crate::r#abstract::MoonPipeline,
+        layouts: &[&MoonBindGroupLayout]
+    ) -> Result<CustomPipeline, MoonWalkError> {
+        let source = read_shader(self, path)?;
+        let pipeline_desc = pipeline_desc.shader_source(&source);
+        let pipeline = self.compile_pipeline(pipeline_desc.clone(), layouts)?;
+
+        #[cfg(feature = "hot-reload")]
+        self.watch_asset(path, crate::hot_reload::ReloadTarget::Pipeline {
+            pipeline: Arc::downgrade(&pipeline.raw),
+            desc: pipeline_desc,
+            layouts: layouts.iter().map(|layout| layout.raw.clone()).collect(),
+        });
+
+        Ok(pipeline)
+    }
+
     pub fn create_bind_group(
-        &self, 
+        &mut self, 
         layout: &MoonBindGroupLayout, 
         resources: &[BindResource]
     ) -> Result<MoonBindGroup, MoonWalkError> {
+        // Выгруженные бюджетом текстуры загружаются обратно до того как на
+        // них сошлётся группа
+        for res in resources {
+            if let BindResource::Texture(id) | BindResource::Sampler(id) = res {
+                self.restore_texture(*id);
+            }
+        }
+
         let mut entries = Vec::with_capacity(resources.len());
 
         for (i, res) in resources.iter().enumerate() {
@@ -152,3 +187,11 @@ impl MoonWalk {
         Ok(MoonBindGroup { raw: bg })
     }
 }
+
+/// Читает исходный код шейдера через ResourceManager
+pub(crate) fn read_shader(mw: &MoonWalk, path: &str) -> Result<String, MoonWalkError> {
+    let bytes = mw.resources.read_bytes(path)?;
+
+    String::from_utf8(bytes)
+        .map_err(|e| MoonWalkError::ShaderError(format!("Shader {} is not valid UTF-8: {}", path, e)))
+}
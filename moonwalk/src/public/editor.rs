@@ -0,0 +1,768 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use glam::{Vec2, Vec4};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::objects::ObjectId;
+use crate::public::text::{Affinity, Rect, TextCursor, TextMotion};
+use crate::{MoonWalk, FontAsset};
+
+/// Максимальное количество шагов отмены
+const UNDO_LIMIT: usize = 128;
+
+/// Вид последней правки, подряд идущие однотипные правки (набор символов,
+/// удаление по одному) склеиваются в один шаг отмены
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditKind {
+    None,
+    Typing,
+    Deleting,
+    Other,
+}
+
+#[derive(Clone, Debug)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+    anchor: Option<usize>,
+}
+
+/// Текст предредактирования IME (композиция). Рисуется в позиции каретки с
+/// подчёркиванием и не попадает в текст пока не будет подтверждён
+#[derive(Clone, Debug)]
+struct Preedit {
+    text: String,
+    cursor: Option<usize>,
+}
+
+/// Объекты сцены которые рисуют редактор
+struct EditorObjects {
+    text: ObjectId,
+    caret: ObjectId,
+    selection: Vec<ObjectId>,
+    underline: Vec<ObjectId>,
+}
+
+/// Хук копирования, получает копируемый текст
+pub type CopyHook = Box<dyn FnMut(&str)>;
+
+/// Хук вставки, возвращает текст из буфера обмена
+pub type PasteHook = Box<dyn FnMut() -> Option<String>>;
+
+/// Редактируемое текстовое поле. Состояние правки (вставка, удаление,
+/// выделение, отмена, маска пароля, лимит длины) не зависит от рендеринга и
+/// меняется обычными методами, а методы принимающие MoonWalk отвечают за
+/// раскладку: движение каретки по строкам, клики и синхронизацию объектов
+/// сцены через update. Буфер обмена не используется напрямую, вместо этого
+/// есть хуки on_copy/on_paste либо значения возвращаемые из copy/cut.
+///
+/// Пример:
+/// let mut editor = TextEditor::new(font, 18.0);
+/// editor.insert("Hello");
+/// editor.move_cursor(&mut mw, TextMotion::Left, true);
+/// editor.update(&mut mw);
+pub struct TextEditor {
+    objects: Option<EditorObjects>,
+    font: FontAsset,
+    font_size: f32,
+
+    text: String,
+    cursor: TextCursor,
+
+    // Второй конец выделения, выделение это диапазон между anchor и cursor
+    anchor: Option<usize>,
+
+    // Сохранённый x для движения вверх/вниз
+    preferred_x: Option<f32>,
+
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    last_edit: EditKind,
+
+    password_mask: Option<char>,
+    max_length: Option<usize>,
+    multiline: bool,
+    preedit: Option<Preedit>,
+
+    position: Vec2,
+    bounds: Vec2,
+    z_index: f32,
+    focused: bool,
+    caret_visible: bool,
+    text_color: Vec4,
+    caret_color: Vec4,
+    selection_color: Vec4,
+
+    dirty: bool,
+
+    /// Вызывается при copy и cut с копируемым текстом
+    pub on_copy: Option<CopyHook>,
+
+    /// Вызывается при paste, должен вернуть текст из буфера обмена
+    pub on_paste: Option<PasteHook>,
+}
+
+impl TextEditor {
+    /// Создаёт пустой однострочный редактор. Объекты сцены создаются при
+    /// первом вызове update
+    pub fn new(font: FontAsset, font_size: f32) -> Self {
+        Self {
+            objects: None,
+            font,
+            font_size,
+            text: String::new(),
+            cursor: TextCursor::default(),
+            anchor: None,
+            preferred_x: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: EditKind::None,
+            password_mask: None,
+            max_length: None,
+            multiline: false,
+            preedit: None,
+            position: Vec2::ZERO,
+            bounds: Vec2::new(9999.0, 9999.0),
+            z_index: 0.0,
+            focused: true,
+            caret_visible: true,
+            text_color: Vec4::ONE,
+            caret_color: Vec4::ONE,
+            selection_color: Vec4::new(0.25, 0.45, 0.95, 0.45),
+            dirty: true,
+            on_copy: None,
+            on_paste: None,
+        }
+    }
+
+    /// Текущий текст (без маски и без текста IME)
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Заменяет текст целиком и очищает историю отмены
+    pub fn set_text(&mut self, text: &str) {
+        self.text = self.filter(text);
+        self.truncate_to_max_length();
+
+        self.cursor = TextCursor { index: self.text.len(), affinity: Affinity::Before };
+        self.anchor = None;
+        self.preedit = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_edit = EditKind::None;
+        self.dirty = true;
+    }
+
+    pub fn cursor(&self) -> TextCursor {
+        self.cursor
+    }
+
+    /// Ставит каретку на байтовый индекс (выравнивается на границу графемы)
+    /// и снимает выделение
+    pub fn set_cursor(&mut self, index: usize) {
+        self.cursor = TextCursor::new(self.snap_to_grapheme(index));
+        self.anchor = None;
+        self.preferred_x = None;
+        self.last_edit = EditKind::None;
+        self.dirty = true;
+    }
+
+    /// Выделенный байтовый диапазон, None если выделения нет
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+
+        if anchor == self.cursor.index {
+            return None;
+        }
+
+        Some(anchor.min(self.cursor.index)..anchor.max(self.cursor.index))
+    }
+
+    /// Выделяет диапазон, каретка ставится в его конец
+    pub fn set_selection(&mut self, range: Range<usize>) {
+        let start = self.snap_to_grapheme(range.start);
+        let end = self.snap_to_grapheme(range.end);
+
+        self.anchor = Some(start);
+        self.cursor = TextCursor::new(end);
+        self.last_edit = EditKind::None;
+        self.dirty = true;
+    }
+
+    pub fn select_all(&mut self) {
+        self.set_selection(0..self.text.len());
+    }
+
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|range| &self.text[range])
+    }
+
+    /// Вставляет текст в позицию каретки заменяя выделение. В однострочном
+    /// режиме переносы строк заменяются пробелами, лишнее сверх max_length
+    /// отбрасывается по графемам
+    pub fn insert(&mut self, text: &str) {
+        let mut text = self.filter(text);
+
+        let selection = self.selection().unwrap_or(self.cursor.index..self.cursor.index);
+
+        if let Some(max) = self.max_length {
+            let kept = self.text.graphemes(true).count() - self.text[selection.clone()].graphemes(true).count();
+            let available = max.saturating_sub(kept);
+
+            if let Some((cut, _)) = text.grapheme_indices(true).nth(available) {
+                text.truncate(cut);
+            }
+        }
+
+        if text.is_empty() && selection.is_empty() {
+            return;
+        }
+
+        // Одиночные символы без выделения это набор текста
+        let is_typing = selection.is_empty() && text.graphemes(true).count() == 1;
+        self.push_undo(if is_typing { EditKind::Typing } else { EditKind::Other });
+
+        self.text.replace_range(selection.clone(), &text);
+        self.cursor = TextCursor { index: selection.start + text.len(), affinity: Affinity::Before };
+        self.anchor = None;
+        self.preferred_x = None;
+        self.dirty = true;
+    }
+
+    /// Удаляет выделение либо графему перед кареткой
+    pub fn backspace(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+
+        let end = self.cursor.index;
+        let start = self.text[..end].grapheme_indices(true).next_back().map_or(0, |(i, _)| i);
+
+        if start == end {
+            return;
+        }
+
+        self.push_undo(EditKind::Deleting);
+        self.text.replace_range(start..end, "");
+        self.cursor = TextCursor { index: start, affinity: Affinity::After };
+        self.dirty = true;
+    }
+
+    /// Удаляет выделение либо графему после каретки
+    pub fn delete(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+
+        let start = self.cursor.index;
+        let end = self.text[start..].graphemes(true).next().map_or(start, |g| start + g.len());
+
+        if start == end {
+            return;
+        }
+
+        self.push_undo(EditKind::Deleting);
+        self.text.replace_range(start..end, "");
+        self.dirty = true;
+    }
+
+    /// Копирует выделение. В режиме пароля ничего не копируется
+    pub fn copy(&mut self) -> Option<String> {
+        if self.password_mask.is_some() {
+            return None;
+        }
+
+        let selected = self.selected_text()?.to_string();
+
+        if let Some(hook) = self.on_copy.as_mut() {
+            hook(&selected);
+        }
+
+        Some(selected)
+    }
+
+    /// Вырезает выделение. В режиме пароля ничего не копируется, но
+    /// выделение всё равно удаляется
+    pub fn cut(&mut self) -> Option<String> {
+        let copied = self.copy();
+        self.delete_selection();
+        copied
+    }
+
+    /// Вставляет текст из хука on_paste
+    pub fn paste(&mut self) {
+        let text = self.on_paste.as_mut().and_then(|hook| hook());
+
+        if let Some(text) = text {
+            self.paste_text(&text);
+        }
+    }
+
+    /// Вставка готового текста как одна правка (отменяется целиком)
+    pub fn paste_text(&mut self, text: &str) {
+        self.last_edit = EditKind::None;
+        self.insert(text);
+        self.last_edit = EditKind::Other;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(snapshot) = self.undo_stack.pop() {
+            let current = self.snapshot();
+            self.redo_stack.push(current);
+            self.restore(snapshot);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            let current = self.snapshot();
+            self.undo_stack.push(current);
+            self.restore(snapshot);
+        }
+    }
+
+    /// Режим пароля: каждая графема показывается символом mask. None
+    /// выключает маску
+    pub fn set_password_mask(&mut self, mask: Option<char>) {
+        self.password_mask = mask;
+        self.dirty = true;
+    }
+
+    /// Лимит длины в графемах. Текущий текст обрезается если он длиннее
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+        self.truncate_to_max_length();
+        self.dirty = true;
+    }
+
+    /// Многострочный режим разрешает переносы строк и движение вверх/вниз
+    pub fn set_multiline(&mut self, multiline: bool) {
+        self.multiline = multiline;
+
+        if !multiline {
+            let filtered = self.filter(&self.text);
+            if filtered != self.text {
+                self.set_text(&filtered);
+            }
+        }
+
+        self.dirty = true;
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    /// Текст композиции IME. cursor это позиция каретки внутри композиции
+    /// (байты), None ставит каретку в конец. Пустая строка убирает композицию
+    pub fn set_preedit(&mut self, text: &str, cursor: Option<usize>) {
+        self.preedit = if text.is_empty() {
+            None
+        } else {
+            Some(Preedit { text: text.to_string(), cursor })
+        };
+
+        self.dirty = true;
+    }
+
+    /// Подтверждение композиции IME: композиция убирается, текст вставляется
+    pub fn commit(&mut self, text: &str) {
+        self.preedit = None;
+        self.last_edit = EditKind::None;
+        self.insert(text);
+        self.dirty = true;
+    }
+
+    pub fn preedit(&self) -> Option<&str> {
+        self.preedit.as_ref().map(|p| p.text.as_str())
+    }
+
+    /// Текст который реально рисуется: с маской пароля и композицией IME
+    pub fn display_text(&self) -> String {
+        let mut display = match self.password_mask {
+            Some(mask) => self.text.graphemes(true).map(|_| mask).collect(),
+            None => self.text.clone(),
+        };
+
+        if let Some(preedit) = &self.preedit {
+            let at = self.to_display(self.cursor.index, false);
+            display.insert_str(at, &preedit.text);
+        }
+
+        display
+    }
+
+    /// Переводит индекс текста в индекс отображаемого текста. with_preedit
+    /// учитывает сдвиг от композиции IME после каретки
+    fn to_display(&self, index: usize, with_preedit: bool) -> usize {
+        let mut display = match self.password_mask {
+            Some(mask) => self.text[..index].graphemes(true).count() * mask.len_utf8(),
+            None => index,
+        };
+
+        if with_preedit && index > self.cursor.index {
+            if let Some(preedit) = &self.preedit {
+                display += preedit.text.len();
+            }
+        }
+
+        display
+    }
+
+    /// Обратный перевод. Индексы внутри композиции прижимаются к каретке
+    fn index_from_display(&self, display: usize) -> usize {
+        let mut display = display;
+
+        if let Some(preedit) = &self.preedit {
+            let at = self.to_display(self.cursor.index, false);
+
+            if display > at {
+                display = at.max(display.saturating_sub(preedit.text.len()));
+            }
+        }
+
+        match self.password_mask {
+            Some(mask) => {
+                let count = display / mask.len_utf8();
+                self.text.grapheme_indices(true).nth(count).map_or(self.text.len(), |(i, _)| i)
+            }
+            None => self.snap_to_grapheme(display),
+        }
+    }
+
+    fn display_cursor(&self) -> TextCursor {
+        let mut index = self.to_display(self.cursor.index, false);
+
+        if let Some(preedit) = &self.preedit {
+            index += preedit.cursor.unwrap_or(preedit.text.len()).min(preedit.text.len());
+        }
+
+        TextCursor { index, affinity: self.cursor.affinity }
+    }
+
+    fn filter(&self, text: &str) -> String {
+        if self.multiline {
+            text.replace("\r\n", "\n")
+        } else {
+            text.replace("\r\n", " ").replace(['\n', '\r'], " ")
+        }
+    }
+
+    fn truncate_to_max_length(&mut self) {
+        if let Some(max) = self.max_length {
+            if let Some((cut, _)) = self.text.grapheme_indices(true).nth(max) {
+                self.text.truncate(cut);
+                self.cursor.index = self.cursor.index.min(cut);
+                self.anchor = self.anchor.map(|a| a.min(cut));
+            }
+        }
+    }
+
+    fn snap_to_grapheme(&self, index: usize) -> usize {
+        if index >= self.text.len() {
+            return self.text.len();
+        }
+
+        self.text.grapheme_indices(true)
+            .map(|(i, _)| i)
+            .take_while(|&i| i <= index)
+            .last()
+            .unwrap_or(0)
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        let Some(range) = self.selection() else {
+            return false;
+        };
+
+        self.push_undo(EditKind::Other);
+        self.text.replace_range(range.clone(), "");
+        self.cursor = TextCursor { index: range.start, affinity: Affinity::After };
+        self.anchor = None;
+        self.dirty = true;
+
+        true
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.text.clone(),
+            cursor: self.cursor.index,
+            anchor: self.anchor,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.text = snapshot.text;
+        self.cursor = TextCursor::new(snapshot.cursor.min(self.text.len()));
+        self.anchor = snapshot.anchor;
+        self.preedit = None;
+        self.preferred_x = None;
+        self.last_edit = EditKind::None;
+        self.dirty = true;
+    }
+
+    fn push_undo(&mut self, kind: EditKind) {
+        // Продолжение набора или удаления не создаёт новый шаг
+        let coalesce = kind != EditKind::Other && kind == self.last_edit;
+
+        if !coalesce {
+            let snapshot = self.snapshot();
+            self.undo_stack.push(snapshot);
+
+            if self.undo_stack.len() > UNDO_LIMIT {
+                self.undo_stack.remove(0);
+            }
+        }
+
+        self.redo_stack.clear();
+        self.last_edit = kind;
+    }
+
+    /// Двигает каретку. select расширяет выделение, иначе выделение
+    /// снимается (Left/Right/Previous/Next при выделении прыгают на его край).
+    /// Пока идёт композиция IME движения игнорируются
+    pub fn move_cursor(&mut self, mw: &mut MoonWalk, motion: TextMotion, select: bool) {
+        if self.preedit.is_some() {
+            return;
+        }
+
+        if !self.multiline && matches!(motion, TextMotion::Up | TextMotion::Down) {
+            return;
+        }
+
+        let vertical = matches!(motion, TextMotion::Up | TextMotion::Down);
+
+        if !select {
+            if let Some(range) = self.selection() {
+                let collapse = match motion {
+                    TextMotion::Left | TextMotion::Previous => Some(range.start),
+                    TextMotion::Right | TextMotion::Next => Some(range.end),
+                    _ => None,
+                };
+
+                if let Some(index) = collapse {
+                    self.cursor = TextCursor::new(index);
+                    self.anchor = None;
+                    self.preferred_x = None;
+                    self.dirty = true;
+                    return;
+                }
+            }
+        }
+
+        self.update(mw);
+
+        let Some(text_id) = self.objects.as_ref().map(|o| o.text) else {
+            return;
+        };
+
+        let preferred_x = if vertical { self.preferred_x } else { None };
+        let (moved, x) = mw.text_motion(text_id, self.display_cursor(), motion, preferred_x);
+
+        if select {
+            self.anchor.get_or_insert(self.cursor.index);
+        } else {
+            self.anchor = None;
+        }
+
+        self.cursor = TextCursor {
+            index: self.index_from_display(moved.index),
+            affinity: moved.affinity,
+        };
+
+        self.preferred_x = x;
+        self.last_edit = EditKind::None;
+        self.dirty = true;
+    }
+
+    /// Ставит каретку в точку (мировые координаты), select расширяет
+    /// выделение от текущей позиции
+    pub fn click(&mut self, mw: &mut MoonWalk, point: Vec2, select: bool) {
+        self.update(mw);
+
+        let Some(text_id) = self.objects.as_ref().map(|o| o.text) else {
+            return;
+        };
+
+        let hit = mw.text_hit(text_id, point);
+
+        if select {
+            self.anchor.get_or_insert(self.cursor.index);
+        } else {
+            self.anchor = None;
+        }
+
+        self.cursor = TextCursor {
+            index: self.index_from_display(hit.index),
+            affinity: hit.affinity,
+        };
+
+        self.preferred_x = None;
+        self.last_edit = EditKind::None;
+        self.dirty = true;
+    }
+
+    pub fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+        self.dirty = true;
+    }
+
+    /// Границы текста (как set_text_size у текстового объекта)
+    pub fn set_size(&mut self, width: f32, height: f32) {
+        self.bounds = Vec2::new(width, height);
+        self.dirty = true;
+    }
+
+    pub fn set_z_index(&mut self, z: f32) {
+        self.z_index = z;
+        self.dirty = true;
+    }
+
+    /// Без фокуса каретка и выделение не рисуются
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.dirty = true;
+    }
+
+    /// Для мигания каретки
+    pub fn set_caret_visible(&mut self, visible: bool) {
+        self.caret_visible = visible;
+        self.dirty = true;
+    }
+
+    pub fn set_colors(&mut self, text: Vec4, caret: Vec4, selection: Vec4) {
+        self.text_color = text;
+        self.caret_color = caret;
+        self.selection_color = selection;
+        self.dirty = true;
+    }
+
+    /// Айди текстового объекта редактора (после первого update)
+    pub fn text_id(&self) -> Option<ObjectId> {
+        self.objects.as_ref().map(|o| o.text)
+    }
+
+    /// Синхронизирует объекты сцены с состоянием редактора. Вызывается после
+    /// изменений, до render_frame
+    pub fn update(&mut self, mw: &mut MoonWalk) {
+        if self.objects.is_none() {
+            let text = mw.new_text("", self.font, self.font_size);
+            let caret = mw.new_rect();
+
+            self.objects = Some(EditorObjects {
+                text,
+                caret,
+                selection: Vec::new(),
+                underline: Vec::new(),
+            });
+        }
+
+        if !self.dirty {
+            return;
+        }
+
+        let display = self.display_text();
+        let display_cursor = self.display_cursor();
+
+        let selection = self.selection()
+            .filter(|_| self.focused)
+            .map(|range| self.to_display(range.start, true)..self.to_display(range.end, true));
+
+        let preedit = self.preedit.as_ref().map(|preedit| {
+            let at = self.to_display(self.cursor.index, false);
+            at..at + preedit.text.len()
+        });
+
+        let Some(objects) = self.objects.as_mut() else {
+            return;
+        };
+
+        mw.set_text(objects.text, &display);
+        mw.set_position(objects.text, self.position);
+        mw.set_text_size(objects.text, self.bounds.x, self.bounds.y);
+        mw.set_color(objects.text, self.text_color);
+        mw.set_z_index(objects.text, self.z_index);
+
+        // Каретка
+        let caret = mw.caret_rect(objects.text, display_cursor);
+        let caret_alpha = if self.focused && self.caret_visible { self.caret_color.w } else { 0.0 };
+
+        mw.set_position(objects.caret, caret.position);
+        mw.set_size(objects.caret, caret.size);
+        mw.set_color(objects.caret, self.caret_color.truncate().extend(caret_alpha));
+        mw.set_z_index(objects.caret, self.z_index + 0.01);
+
+        // Выделение под текстом
+        let selection_rects = match selection {
+            Some(range) => mw.selection_rects(objects.text, range),
+            None => Vec::new(),
+        };
+
+        sync_rects(mw, &mut objects.selection, &selection_rects, self.selection_color, self.z_index - 0.01);
+
+        // Подчёркивание композиции IME по нижнему краю строки
+        let thickness = (self.font_size * 0.07).max(1.0);
+
+        let underline_rects: Vec<Rect> = match preedit {
+            Some(range) => mw.selection_rects(objects.text, range)
+                .into_iter()
+                .map(|rect| Rect {
+                    position: Vec2::new(rect.position.x, rect.position.y + rect.size.y - thickness),
+                    size: Vec2::new(rect.size.x, thickness),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        sync_rects(mw, &mut objects.underline, &underline_rects, self.text_color, self.z_index + 0.01);
+
+        self.dirty = false;
+    }
+
+    /// Удаляет объекты редактора со сцены
+    pub fn remove(&mut self, mw: &mut MoonWalk) {
+        if let Some(objects) = self.objects.take() {
+            mw.remove(objects.text);
+            mw.remove(objects.caret);
+
+            for id in objects.selection.into_iter().chain(objects.underline) {
+                mw.remove(id);
+            }
+        }
+
+        self.dirty = true;
+    }
+}
+
+/// Приводит пул прямоугольников к списку rects: лишние удаляются, недостающие
+/// создаются
+fn sync_rects(mw: &mut MoonWalk, pool: &mut Vec<ObjectId>, rects: &[Rect], color: Vec4, z: f32) {
+    while pool.len() > rects.len() {
+        if let Some(id) = pool.pop() {
+            mw.remove(id);
+        }
+    }
+
+    while pool.len() < rects.len() {
+        pool.push(mw.new_rect());
+    }
+
+    for (id, rect) in pool.iter().zip(rects) {
+        mw.set_position(*id, rect.position);
+        mw.set_size(*id, rect.size);
+        mw.set_color(*id, color);
+        mw.set_z_index(*id, z);
+    }
+}
@@ -19,9 +19,17 @@ impl MoonWalk {
 
     /// Scale Factor нужно взять у winit либо другой библиотеки
     /// он необходим чтобы преобразовать логические размеры окна
-    /// в физические (Иначе полноэкранного режима не будет на телефонах)
+    /// в физические (Иначе полноэкранного режима не будет на телефонах).
+    /// При смене значения svg текстуры перерисовываются в новом масштабе
     pub fn set_scale_factor(&mut self, scale: f32) {
+        let changed = self.renderer.scale_factor != scale;
         self.renderer.set_scale_factor(scale);
+
+        if changed {
+            if let Err(e) = self.rasterize_all_svgs() {
+                eprintln!("MoonWalk Error rasterizing SVG: {}", e);
+            }
+        }
     }
 
     /// Функция для рендеринга всех элементов которые накопил движок.
@@ -32,6 +40,16 @@ impl MoonWalk {
     /// Первый аргумент это структура Vec4 из крейта GLAM, сюда нужно
     /// передать цвет которым будет заливаться экран.
     pub fn render_frame(&mut self, clear_color: Vec4) -> Result<(), MoonWalkError> {
+        // С фичей hot-reload изменённые файлы ресурсов подхватываются здесь
+        #[cfg(feature = "hot-reload")]
+        self.poll_hot_reload(false);
+
+        // Текстуры декодированные в фоне загружаются в видеопамять здесь
+        self.poll_texture_loads();
+
+        // С бюджетом видеопамяти давно не рисовавшиеся текстуры выгружаются
+        self.apply_texture_budget();
+
         self.renderer.render(clear_color)
     }
 
@@ -66,6 +84,7 @@ impl MoonWalk {
     /// чтобы указать координаты области и ширину/высоту области для снапшота.
     /// Ничего не возвращает, только обновляет
     pub fn update_snapshot(&mut self, pos: Vec2, size: Vec2, id: u32) {
+        self.keep_texture(id);
         self.renderer.update_snapshot(
             pos.x as u32, 
             pos.y as u32, 
@@ -112,6 +131,8 @@ impl MoonWalk {
     ///  только последнее слово после точки
     /// cute_elephant.jpg -> Кодировщик jpeg
     pub fn save_texture(&mut self, texture_id: u32, path: &str) -> Result<(), crate::MoonWalkError> {
+        self.restore_texture(texture_id);
+
         let texture = self.renderer.state.textures.get(&texture_id)
             .ok_or_else(|| crate::MoonWalkError::IOError("Texture not found".to_string()))?;
 
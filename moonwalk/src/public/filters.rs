@@ -1,16 +1,22 @@
 // Часть проекта MoonWalk с открытым исходным кодом.
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2025 MoonWalk
 
-use glam::{Vec3, Vec4, Mat4};
+use glam::{Vec2, Vec3, Vec4, Mat4};
 
 use crate::MoonWalk;
+use crate::error::MoonWalkError;
+use crate::filters::chain::{FilterChain, FilterOp};
+use crate::rendering::texture::Texture;
 
 impl MoonWalk {
     /// Эта функция перезаписывает текстуру применив к ней блюр по гаусу. Для
     /// правильного блюра обязательно применить блюр дважды, первый раз
     /// с horizontal true (горизонтальный проход), второй раз с horizontal false
-    /// (вертикальный проход). Принимает айди текстуры, радиус блюра и направление
+    /// (вертикальный проход). Принимает айди текстуры, радиус блюра и направление.
+    /// [!] Как и остальные фильтры без Result, работает только с RGBA8 в sRGB,
+    /// другие текстуры не меняются (ошибка пишется в лог)
     pub fn blur_texture(&mut self, texture_id: u32, radius: f32, horizontal: bool) {
+        self.keep_texture(texture_id);
         self.renderer.apply_blur(texture_id, radius, horizontal);
     }
 
@@ -18,6 +24,7 @@ impl MoonWalk {
     /// и значение яркости. 0.0 - нулевая яркость, 1.0 - стандартная (как в оригинале),
     /// 2.0 - в два раза ярче
     pub fn brightness(&mut self, texture_id: u32, factor: f32) {
+        self.keep_texture(texture_id);
         let (matrix, offset) = crate::filters::color_matrix::matrix_brightness(factor);
         self.renderer.apply_color_matrix(texture_id, matrix, offset);
     }
@@ -25,6 +32,7 @@ impl MoonWalk {
     /// Эта функция перезаписывает текстуру обновив её контраст. Принимает айди текстуры
     /// и новое значение контраста
     pub fn contrast(&mut self, texture_id: u32, contrast: f32) {
+        self.keep_texture(texture_id);
         let (matrix, offset) = crate::filters::color_matrix::matrix_contrast(contrast);
         self.renderer.apply_color_matrix(texture_id, matrix, offset);
     }
@@ -32,6 +40,7 @@ impl MoonWalk {
     /// Эта функция перезаписывает текстуру обновив её насыщеность. Принимает айди текстуры
     /// и новое значение насыщености
     pub fn saturation(&mut self, texture_id: u32, sat: f32) {
+        self.keep_texture(texture_id);
         let (matrix, offset) = crate::filters::color_matrix::matrix_saturation(sat);
         self.renderer.apply_color_matrix(texture_id, matrix, offset);
     }
@@ -39,6 +48,7 @@ impl MoonWalk {
     /// Эта функция перезаписывает текстуру обновив её тон. Принимает айди текстуры
     /// и градусы для угла поворота цветового колеса (от 0 до 360)
     pub fn hue_shift(&mut self, texture_id: u32, degrees: f32) {
+        self.keep_texture(texture_id);
         let (matrix, offset) = crate::filters::color_matrix::matrix_hue(degrees);
         self.renderer.apply_color_matrix(texture_id, matrix, offset);
     }
@@ -49,6 +59,7 @@ impl MoonWalk {
     /// в этом контексте не учитывается. Если ваш цвет Vec4 то просто передайте
     /// xyz, а w не передавайте) и силу применения фильтра (f32)
     pub fn chromakey(&mut self, texture_id: u32, key_color: Vec3, tolerance: f32) {
+        self.keep_texture(texture_id);
         self.renderer.apply_chromakey(texture_id, key_color.to_array(), tolerance);
     }
 
@@ -60,6 +71,8 @@ impl MoonWalk {
     /// а непрозрачные скрывает. Если true то прозрачные маски скрывают пиксель, а
     /// непрозрачные делают видимой (классическая маска)
     pub fn apply_mask(&mut self, target_id: u32, mask_id: u32, invert: bool) {
+        self.keep_texture(target_id);
+        self.restore_texture(mask_id);
         self.renderer.apply_stencil(target_id, mask_id, invert);
     }
 
@@ -70,10 +83,113 @@ impl MoonWalk {
     ///  - Столбцы матрицы отвечают за входные каналы (R, G, B, A)
     ///  - Строки отвечают за выходные каналы.
     /// и offset (4д вектор Vec4 из glam, который прибавляется к результату (смещение цвета)
+    ///
+    /// [!] Текстура должна быть RGBA8 в sRGB, иначе она не изменится
     pub fn color_matrix(&mut self, texture_id: u32, matrix: Mat4, offset: Vec4) {
+        self.keep_texture(texture_id);
         let mat_arr = matrix.to_cols_array_2d();
         let off_arr = offset.to_array();
 
         self.renderer.apply_color_matrix(texture_id, mat_arr, off_arr);
     }
+
+    /// Применяет одну операцию цепочки к текстуре на месте. Текстура должна
+    /// быть RGBA8 в sRGB (как загруженные текстуры по умолчанию), иначе
+    /// вернётся FilterError. Для нескольких операций подряд FilterChain
+    /// быстрее, все проходы идут одной отправкой
+    pub fn apply_filter(&mut self, texture_id: u32, op: FilterOp) -> Result<(), MoonWalkError> {
+        self.apply_filter_chain(&FilterChain::new().then(op), texture_id, texture_id)
+    }
+
+    /// Свечение ярких участков. threshold это яркость от 0 до 1 с которой
+    /// пиксель начинает светиться, intensity сила свечения (1.0 обычная)
+    pub fn bloom(&mut self, texture_id: u32, threshold: f32, intensity: f32) -> Result<(), MoonWalkError> {
+        self.apply_filter(texture_id, FilterOp::bloom(threshold, intensity))
+    }
+
+    /// Тень по альфа каналу текстуры, смещённая на offset пикселей и
+    /// размытая с радиусом radius. Альфа color это непрозрачность тени.
+    /// Тень не выходит за размер текстуры, поэтому вокруг картинки нужны
+    /// прозрачные поля
+    pub fn drop_shadow(&mut self, texture_id: u32, offset: Vec2, radius: f32, color: Vec4) -> Result<(), MoonWalkError> {
+        self.apply_filter(texture_id, FilterOp::drop_shadow(offset, radius, color))
+    }
+
+    /// Повышает резкость нерезкой маской. amount сила (0.5 - 2.0 обычно),
+    /// radius размытие с которым сравнивается картинка
+    pub fn sharpen(&mut self, texture_id: u32, amount: f32, radius: f32) -> Result<(), MoonWalkError> {
+        self.apply_filter(texture_id, FilterOp::sharpen(amount, radius))
+    }
+
+    /// Затемняет края. strength от 0 до 1, radius доля расстояния от центра
+    /// до угла с которой начинается затемнение
+    pub fn vignette(&mut self, texture_id: u32, strength: f32, radius: f32) -> Result<(), MoonWalkError> {
+        self.apply_filter(texture_id, FilterOp::vignette(strength, radius))
+    }
+
+    /// Мозаика из квадратов со стороной size пикселей
+    pub fn pixelate(&mut self, texture_id: u32, size: f32) -> Result<(), MoonWalkError> {
+        self.apply_filter(texture_id, FilterOp::pixelate(size))
+    }
+
+    /// Зерно плёнки силой amount (0.05 - 0.2 обычно). С одним seed шум
+    /// всегда одинаковый
+    pub fn film_grain(&mut self, texture_id: u32, amount: f32, seed: f32) -> Result<(), MoonWalkError> {
+        self.apply_filter(texture_id, FilterOp::film_grain(amount, seed))
+    }
+
+    /// Заменяет картинку её краями, белыми на чёрном фоне
+    pub fn edge_detect(&mut self, texture_id: u32, strength: f32) -> Result<(), MoonWalkError> {
+        self.apply_filter(texture_id, FilterOp::edge_detect(strength))
+    }
+
+    /// Применяет цепочку фильтров к source_id и записывает результат в
+    /// target_id, исходная текстура не меняется (если это не одна и та же
+    /// текстура). Размеры текстур должны совпадать, target должна быть RGBA8
+    /// в sRGB, в линейную текстуру цвета записались бы со сдвигом.
+    /// Промежуточные текстуры берутся из пула и переиспользуются, поэтому
+    /// одну цепочку можно применять каждый кадр, меняя её параметры
+    pub fn apply_filter_chain(&mut self, chain: &FilterChain, source_id: u32, target_id: u32) -> Result<(), MoonWalkError> {
+        self.restore_filter_inputs(chain, source_id);
+        self.keep_texture(target_id);
+
+        self.renderer.apply_filter_chain(chain, source_id, target_id)
+    }
+
+    /// Создаёт новую текстуру с результатом цепочки фильтров и возвращает
+    /// её айди. Исходная текстура не меняется
+    pub fn create_filtered_texture(&mut self, chain: &FilterChain, source_id: u32) -> Result<u32, MoonWalkError> {
+        self.restore_filter_inputs(chain, source_id);
+
+        let size = self.get_texture_size(source_id);
+
+        if size.x < 1.0 || size.y < 1.0 {
+            return Err(MoonWalkError::FilterError(format!("Texture {} not found", source_id)));
+        }
+
+        let texture = Texture::create_empty(&self.renderer.context, size.x as u32, size.y as u32, crate::filters::FILTER_FORMAT, "Filtered Texture");
+        let target_id = self.renderer.register_texture(texture);
+
+        if let Err(e) = self.renderer.apply_filter_chain(chain, source_id, target_id) {
+            self.renderer.remove_texture(target_id);
+            return Err(e);
+        }
+
+        Ok(target_id)
+    }
+
+    /// Освобождает промежуточные текстуры фильтров и цепочек. Полезно после
+    /// разовой обработки больших картинок
+    pub fn release_filter_textures(&mut self) {
+        self.renderer.filters.release_textures();
+    }
+
+    // Выгруженные бюджетом текстуры которые читает цепочка загружаются обратно
+    fn restore_filter_inputs(&mut self, chain: &FilterChain, source_id: u32) {
+        self.restore_texture(source_id);
+
+        for input in chain.texture_inputs() {
+            self.restore_texture(input);
+        }
+    }
 }
@@ -69,6 +69,8 @@ impl MoonWalk {
             1 => TextAlign::Center,
             2 => TextAlign::Right,
             3 => TextAlign::Justified,
+            4 => TextAlign::Start,
+            5 => TextAlign::End,
             _ => TextAlign::Left,
         }
     }
@@ -0,0 +1,114 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use std::time::Duration;
+
+use crate::error::MoonWalkError;
+use crate::hot_reload::{ReloadTarget, Watch};
+use crate::public::custom::read_shader;
+use crate::MoonWalk;
+
+impl MoonWalk {
+    /// Как часто render_frame проверяет файлы ресурсов (по умолчанию раз в
+    /// полсекунды)
+    pub fn set_hot_reload_interval(&mut self, interval: Duration) {
+        self.resources.watcher.set_interval(interval);
+    }
+
+    /// Сразу проверяет файлы загруженных текстур, шрифтов и шейдеров и
+    /// перезагружает изменившиеся. Айди и пайплайны остаются прежними. Если
+    /// новый файл не загрузился, ошибка пишется в лог и остаётся старая версия.
+    /// Возвращает сколько ресурсов перезагружено. render_frame делает то же
+    /// самое сам, не чаще чем раз в интервал
+    pub fn reload_changed_assets(&mut self) -> usize {
+        self.poll_hot_reload(true)
+    }
+
+    pub(crate) fn poll_hot_reload(&mut self, force: bool) -> usize {
+        // Вотчер на время перезагрузки вынимается, ресурсы грузятся через self
+        let mut watcher = std::mem::take(&mut self.resources.watcher);
+        let mut reloaded = 0;
+
+        for watch in watcher.poll(force) {
+            match self.reload_asset(watch) {
+                Ok(true) => {
+                    log::info!("Hot reload: {}", watch.path);
+                    reloaded += 1;
+                }
+
+                Ok(false) => watch.alive = false,
+
+                Err(e) => log::error!("Hot reload of {} failed, keeping previous version: {}", watch.path, e),
+            }
+        }
+
+        self.resources.watcher = watcher;
+
+        reloaded
+    }
+
+    /// Начинает следить за файлом ресурса. Ресурсы без настоящего пути
+    /// (архивы, память, assets на android) пропускаются
+    pub(crate) fn watch_asset(&mut self, path: &str, target: ReloadTarget) {
+        if let Some(real) = self.resources.real_path(path) {
+            self.resources.watcher.watch(path, real, target);
+        }
+    }
+
+    // Ok(false) если ресурса уже нет и следить за файлом больше не нужно
+    fn reload_asset(&mut self, watch: &mut Watch) -> Result<bool, MoonWalkError> {
+        match &mut watch.target {
+            ReloadTarget::Texture(id) => {
+                // Выгруженная бюджетом текстура и так прочитается из
+                // нового файла когда понадобится
+                if self.resources.budget.is_evicted(*id) {
+                    return Ok(true);
+                }
+
+                let Some(options) = self.renderer.state.textures.get(id).map(|texture| texture.options) else {
+                    return Ok(false);
+                };
+
+                let texture = self.resources.load_texture_with_options(&self.renderer.context, &watch.path, &options)?;
+                self.renderer.state.textures.insert(*id, texture);
+            }
+
+            ReloadTarget::Font(id) => {
+                let bytes = self.resources.read_bytes(&watch.path)?;
+                self.renderer.text_engine.reload_font_bytes(*id, &bytes)?;
+                self.renderer.state.store.dirty = true;
+            }
+
+            ReloadTarget::Pipeline { pipeline, desc, layouts } => {
+                let Some(pipeline) = pipeline.upgrade() else {
+                    return Ok(false);
+                };
+
+                let source = read_shader(self, &watch.path)?;
+                let ctx = &self.renderer.context;
+                let format = ctx.config.format;
+
+                let new_desc = desc.clone().shader_source(&source);
+                let raw_layouts: Vec<&wgpu::BindGroupLayout> = layouts.iter().collect();
+
+                // Ошибки в шейдере wgpu по умолчанию превращает в панику,
+                // здесь они ловятся и сборка считается неудачной
+                ctx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+                let result = new_desc.build(ctx, format, &raw_layouts);
+
+                if let Some(error) = pollster::block_on(ctx.device.pop_error_scope()) {
+                    new_desc.forget_cached(ctx, format);
+                    return Err(MoonWalkError::ShaderError(error.to_string()));
+                }
+
+                let result = result?;
+
+                desc.forget_cached(ctx, format);
+                *desc = new_desc;
+                *pipeline.write() = result.pipeline.raw;
+            }
+        }
+
+        Ok(true)
+    }
+}
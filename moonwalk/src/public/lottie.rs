@@ -0,0 +1,23 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use crate::error::MoonWalkError;
+use crate::lottie::LottiePlayer;
+use crate::MoonWalk;
+
+impl MoonWalk {
+    /// Загружает анимацию Lottie (.json) по пути как и load_texture и
+    /// возвращает плеер. Плеер не привязан к движку: время двигается через
+    /// player.update(dt), кадр рисуется через player.render_to_texture или
+    /// player.render_to_objects. Пример:
+    ///  let mut player = mw.load_lottie("loader.json")?;
+    ///  player.play();
+    ///  // каждый кадр
+    ///  if player.update(dt) { let tex = player.render_to_texture(&mut mw, 256, 256); }
+    pub fn load_lottie(&self, path: &str) -> Result<LottiePlayer, MoonWalkError> {
+        let bytes = self.resources.read_bytes(path)?;
+        let data = String::from_utf8(bytes).map_err(|e| MoonWalkError::AnimationLoading(e.to_string()))?;
+
+        LottiePlayer::from_json(&data)
+    }
+}
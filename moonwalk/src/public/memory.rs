@@ -0,0 +1,142 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use std::collections::HashSet;
+
+use crate::objects::store::ObjectStore;
+use crate::rendering::memory::{used_textures, MemoryStats, TrackedBytes};
+use crate::MoonWalk;
+
+impl MoonWalk {
+    /// Сколько видеопамяти занимают текстуры, цели рендера, фильтры, атласы
+    /// глифов и промежуточные буферы. Размеры считаются по формату и
+    /// размеру текстур, драйвер может занимать немного больше
+    pub fn memory_stats(&self) -> MemoryStats {
+        let state = &self.renderer.state;
+        let budget = &self.resources.budget;
+
+        MemoryStats {
+            textures: state.white_texture.memory_bytes()
+                + state.textures.values().map(|texture| texture.memory_bytes()).sum::<u64>(),
+            texture_count: state.textures.len(),
+            evicted_textures: budget.evicted_count(),
+            render_targets: self.renderer.offscreen_bytes() + TrackedBytes::total(),
+            filters: self.renderer.filters.memory_bytes(),
+            glyph_atlases: self.renderer.text_engine.atlas_bytes(),
+            staging: state.staging.allocated(),
+            budget: budget.limit,
+            evictions: budget.evictions,
+            reloads: budget.reloads,
+        }
+    }
+
+    /// Бюджет видеопамяти в байтах, None выключает его. С бюджетом
+    /// render_frame после каждого кадра выгружает текстуры которые давно не
+    /// рисовались, пока MemoryStats::total не станет меньше бюджета.
+    /// Выгружаются только текстуры из load_texture и load_texture_background,
+    /// их айди не меняется и при следующем рисовании они загружаются
+    /// из файла снова. Остальное (снапшоты, svg, create_texture, цели
+    /// рендера) не выгружается, но учитывается. Пример для слабых android:
+    ///  mw.set_texture_budget(Some(256 * 1024 * 1024));
+    pub fn set_texture_budget(&mut self, bytes: Option<u64>) {
+        self.resources.budget.limit = bytes;
+    }
+
+    pub fn texture_budget(&self) -> Option<u64> {
+        self.resources.budget.limit
+    }
+
+    /// Загружает выгруженную текстуру обратно. Рисование делает это само,
+    /// вручную нужно перед чтением пикселей (get_texture_pixel, download).
+    /// false если текстуры нет или загрузить её не удалось
+    pub fn restore_texture(&mut self, texture_id: u32) -> bool {
+        if !self.resources.budget.is_evicted(texture_id) {
+            return self.renderer.state.textures.contains_key(&texture_id);
+        }
+
+        let Some(source) = self.resources.budget.source(texture_id) else {
+            return false;
+        };
+
+        match self.resources.load_texture_with_options(&self.renderer.context, &source.path, &source.options) {
+            Ok(texture) => {
+                self.renderer.state.textures.insert(texture_id, texture);
+                self.resources.budget.mark_reloaded(texture_id);
+                true
+            }
+
+            Err(e) => {
+                // Файл пропал, объект дальше рисуется белым и попытки не повторяются
+                log::error!("Reloading of evicted texture {} failed: {}", texture_id, e);
+                self.resources.budget.forget(texture_id);
+                false
+            }
+        }
+    }
+
+    // Текстуру изменили на видеокарте (фильтры, кисть, update_texture) и она
+    // больше не совпадает с файлом, поэтому бюджет её не выгружает
+    pub(crate) fn keep_texture(&mut self, texture_id: u32) {
+        if self.resources.budget.source(texture_id).is_some() {
+            self.restore_texture(texture_id);
+            self.resources.budget.forget(texture_id);
+        }
+    }
+
+    // Загружает выгруженные текстуры объектов хранилища и отмечает что они
+    // рисуются в этом кадре
+    pub(crate) fn restore_textures(&mut self, store: &ObjectStore) {
+        let budget = &self.resources.budget;
+
+        if budget.limit.is_none() && budget.evicted_count() == 0 {
+            return;
+        }
+
+        self.use_textures(used_textures(store));
+    }
+
+    fn use_textures(&mut self, used: HashSet<u32>) {
+        for id in used {
+            if self.resources.budget.is_evicted(id) {
+                self.restore_texture(id);
+            }
+
+            self.resources.budget.touch(id);
+        }
+    }
+
+    // Вызывается из render_frame: загружает текстуры главной сцены и
+    // выгружает самые давние пока расход больше бюджета
+    pub(crate) fn apply_texture_budget(&mut self) {
+        let budget = &self.resources.budget;
+
+        if budget.limit.is_none() && budget.evicted_count() == 0 {
+            return;
+        }
+
+        self.use_textures(used_textures(&self.renderer.state.store));
+        self.evict_textures();
+        self.resources.budget.next_frame();
+    }
+
+    fn evict_textures(&mut self) {
+        let Some(limit) = self.resources.budget.limit else {
+            return;
+        };
+
+        let mut total = self.memory_stats().total();
+
+        for id in self.resources.budget.candidates() {
+            if total <= limit {
+                break;
+            }
+
+            let Some(texture) = self.renderer.state.textures.remove(&id) else {
+                continue;
+            };
+
+            total = total.saturating_sub(texture.memory_bytes());
+            self.resources.budget.mark_evicted(id, [texture.texture.width(), texture.texture.height()]);
+        }
+    }
+}
@@ -12,12 +12,24 @@ mod filters;
 mod objects;
 mod resources;
 mod getter;
+mod text;
+mod editor;
+mod svg;
+mod lottie;
+mod animated;
+mod memory;
+
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
 
 pub use export::*;
 pub use filters::*;
 pub use objects::*;
 pub use resources::*;
 pub use getter::*;
+pub use text::*;
+pub use editor::*;
+pub use svg::*;
 pub use brush::*;
 pub use custom::*;
 pub use video::*;
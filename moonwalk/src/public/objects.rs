@@ -33,6 +33,28 @@ impl MoonWalk {
         PathBuilder::new()
     }
 
+    /// Создаёт векторный путь как объект сцены. В отличие от tessellate путь
+    /// не запекается в текстуру: его геометрия хранится на видеокарте и
+    /// рисуется в основном проходе вместе с остальными объектами по z индексу.
+    /// Позиция и размер объекта изначально равны рамке пути (вместе с
+    /// обводкой), set_position, set_size и set_rotation двигают, растягивают
+    /// и вращают путь как прямоугольник. set_color умножается на заливку пути.
+    /// Геометрия пересчитывается только в set_path
+    ///  [!] Сглаживание (set_antialias) работает только при рендере в
+    ///      текстуру, живые пути рисуются без MSAA
+    pub fn new_path(&mut self, builder: PathBuilder) -> ObjectId {
+        self.renderer.state.store.new_path(builder.into_shape())
+    }
+
+    /// Заменяет геометрию пути созданного через new_path. Новый путь рисуется в
+    /// той же системе координат что и первый, поэтому позиция и размер объекта
+    /// не сбрасываются. Для других объектов ничего не делает
+    pub fn set_path(&mut self, id: ObjectId, builder: PathBuilder) {
+        if self.renderer.state.store.get_path_shape(id).is_some() {
+            self.renderer.state.store.set_path_shape(id, builder.into_shape());
+        }
+    }
+
     /// [WAIT DOC]
     pub fn parse_svg_path(&self, pb: &mut crate::path::PathBuilder, data: &str) -> Result<(), String> {
         crate::path::svg::parse_svg_path(pb, data)
@@ -155,11 +177,20 @@ impl MoonWalk {
     }
 
     /// Эта функция устанавливает UV координаты для текстуры на объекте.
-    /// Принимает айди объекта и массив из 4 флоатом (x, y, ширина, высота)
+    /// Принимает айди объекта и массив из 4 флоатом (x, y, ширина, высота).
+    /// Значения больше 1 (или меньше 0) повторяют текстуру если она загружена
+    /// с TextureWrap::Repeat или MirrorRepeat, иначе растягивается край
     pub fn set_uv(&mut self, id: ObjectId, uv: [f32; 4]) {
         self.renderer.state.store.config_uv(id, uv);
     }
 
+    /// Эта функция ставит объекту спрайт из атласа (TextureAtlas): текстуру
+    /// страницы и uv координаты спрайта на ней
+    pub fn set_sprite(&mut self, id: ObjectId, sprite: &crate::AtlasSprite) {
+        self.renderer.state.store.config_texture(id, sprite.texture_id);
+        self.renderer.state.store.config_uv(id, sprite.uv);
+    }
+
     /// Эта функция устаналивает эффекты для объекта. Работает идеально только
     /// с прямоугольниками. Принимает айди объекта, ширину обводки и настройку
     /// для размытия углов (box_shadow Так как используется технология как в CSS)
@@ -199,12 +230,16 @@ impl MoonWalk {
     /// 2. Center, выравнивание по центру
     /// 3. Right, выравнивание по правой стороне
     /// 4. Justified, текст прижат к сторонам своих границ (они меняются через set_text_size) 
+    /// 5. Start, к началу абзаца (слева для LTR, справа для RTL)
+    /// 6. End, к концу абзаца (справа для LTR, слева для RTL)
     pub fn set_text_align(&mut self, id: ObjectId, align: TextAlign) {
         let val = match align {
             TextAlign::Left => 0,
             TextAlign::Center => 1,
             TextAlign::Right => 2,
             TextAlign::Justified => 3,
+            TextAlign::Start => 4,
+            TextAlign::End => 5,
         };
         self.renderer.state.store.set_text_align(id, val);
     }
@@ -213,6 +248,9 @@ impl MoonWalk {
     /// (FontAsset, его можно получить через функцию load_font и load_font_from_bytes)
     /// размер шрифта и максимальную ширину. На выходе идёт Vec2 из крейта glam который
     /// содержит ширину и высоту текста по указанным параметрам
+    ///  [!] Лимит строк и многоточие здесь не учитываются, текст измеряется
+    ///      целиком. Размер после обрезки даёт measure_text_truncated, а для
+    ///      уже созданного объекта measure_text_object
     pub fn measure_text(&mut self, text: &str, font: FontAsset, size: f32, max_width: f32) -> Vec2 {
         let (w, h) = self.renderer.text_engine.measure_text(
             text, 
@@ -223,6 +261,35 @@ impl MoonWalk {
         Vec2::new(w, h)
     }
 
+    /// То же что measure_text, но текст обрезается как у объекта с
+    /// set_max_lines(max_lines) и set_text_overflow(overflow): лишние строки
+    /// отбрасываются, с Overflow::Ellipsis последняя строка заканчивается
+    /// многоточием. max_lines 0 означает без лимита
+    pub fn measure_text_truncated(
+        &mut self,
+        text: &str,
+        font: FontAsset,
+        size: f32,
+        max_width: f32,
+        max_lines: usize,
+        overflow: crate::Overflow
+    ) -> Vec2 {
+        let params = crate::textware::TextParams {
+            font_id: crate::textware::FontId(font.0),
+            font_size: size,
+            max_width,
+            max_height: f32::MAX,
+            align: 0,
+            overflow: overflow.to_u8(),
+            max_lines: max_lines as u32,
+            direction: 0,
+            style: crate::textware::TextStyle::default(),
+        };
+
+        let (w, h) = self.renderer.text_engine.measure_text_params(text, params);
+        Vec2::new(w, h)
+    }
+
     /// Эта функция делает объект с переданным ID мёртвым. Он сохраняет в ObjectStore,
     /// но перестаёт отрисовываться. Потом при создании другого объекта он занимает
     /// айди любого мёртвого объекта, если мёртвого объекта нет - создаёт новый id
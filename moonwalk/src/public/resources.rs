@@ -3,6 +3,8 @@
 
 use crate::error;
 use crate::{MoonWalk, FontAsset};
+use crate::rendering::loader::{FinishedLoad, TextureCallback, TextureHandle};
+use crate::rendering::texture::{Texture, TextureFormat, TextureOptions};
 
 impl MoonWalk {
     /// Эта функция агружает текстуру из файла через его путь
@@ -17,16 +19,165 @@ impl MoonWalk {
     ///  "test.png" - файл test.png из assets приложения
     ///  "data/data/com.example.package/file/test.png" - файл test.png из файловой системы
     pub fn load_texture(&mut self, path: &str) -> Result<u32, error::MoonWalkError> {
-        let texture = self.resources.load_texture(&self.renderer.context, path)?;
+        self.load_texture_with_options(path, TextureOptions::default())
+    }
+
+    /// То же что load_texture, но с параметрами сэмплирования: фильтрация
+    /// (Nearest для пиксель-арта), мипмапы, повтор по осям и sRGB. Чтобы
+    /// текстура замостила объект, включите TextureWrap::Repeat и задайте
+    /// через set_uv размер больше 1, например [0.0, 0.0, 4.0, 4.0]
+    pub fn load_texture_with_options(&mut self, path: &str, options: TextureOptions) -> Result<u32, error::MoonWalkError> {
+        let texture = self.resources.load_texture_with_options(&self.renderer.context, path, &options)?;
         let id = self.renderer.register_texture(texture);
+        self.resources.budget.set_source(id, path, options);
+
+        #[cfg(feature = "hot-reload")]
+        self.watch_asset(path, crate::hot_reload::ReloadTarget::Texture(id));
         
         Ok(id)
     }
 
+    /// Загружает текстуру не останавливая кадр: файл читается и декодируется
+    /// в пуле потоков, а в видеопамять попадает при одном из следующих
+    /// render_frame. Айди текстуры известен сразу, до окончания загрузки под
+    /// ним белая заглушка 1x1. Ошибки пишутся в лог и в ручку
+    pub fn load_texture_background(&mut self, path: &str) -> TextureHandle {
+        self.start_background_load(path, TextureOptions::default(), None)
+    }
+
+    /// То же что load_texture_background, но с параметрами сэмплирования как
+    /// в load_texture_with_options. Заглушка рисуется с параметрами по
+    /// умолчанию, параметры применяются к готовой текстуре
+    pub fn load_texture_background_with_options(&mut self, path: &str, options: TextureOptions) -> TextureHandle {
+        self.start_background_load(path, options, None)
+    }
+
+    /// То же что load_texture_background, но по окончании загрузки в потоке
+    /// рендера вызывается callback с айди текстуры или ошибкой
+    pub fn load_texture_background_with(
+        &mut self,
+        path: &str,
+        callback: impl FnOnce(Result<u32, error::MoonWalkError>) + Send + 'static
+    ) -> TextureHandle {
+        self.start_background_load(path, TextureOptions::default(), Some(Box::new(callback)))
+    }
+
+    fn start_background_load(
+        &mut self,
+        path: &str,
+        options: TextureOptions,
+        callback: Option<TextureCallback>
+    ) -> TextureHandle {
+        // Файл заглушки в бюджете не запоминается, иначе её выгрузили бы
+        // первой и загрузили бы файл заново уже в потоке рендера
+        let placeholder = Texture::from_raw(&self.renderer.context, &[255; 4], 1, 1, "Loading placeholder")
+            .expect("1x1 placeholder texture is always valid");
+
+        let id = self.renderer.register_texture(placeholder);
+        let read = self.resources.read_bytes_detached(path);
+
+        #[cfg(feature = "hot-reload")]
+        self.watch_asset(path, crate::hot_reload::ReloadTarget::Texture(id));
+
+        self.resources.loader.start(id, path, options, read, callback)
+    }
+
+    /// Загружает в видеопамять текстуры которые закончили декодирование и
+    /// вызывает их колбэки. render_frame вызывает это сам, вручную нужно
+    /// только если кадры не рисуются. Возвращает сколько загрузок закончилось
+    pub fn poll_texture_loads(&mut self) -> usize {
+        let finished = self.resources.loader.take_finished();
+        let count = finished.len();
+
+        for FinishedLoad { decoded, path, options, notifier } in finished {
+            let id = decoded.id;
+
+            let result = decoded.result.and_then(|image| {
+                // Текстуру могли удалить пока она грузилась
+                if !self.renderer.state.textures.contains_key(&id) {
+                    return Err(error::MoonWalkError::TextureLoading("Texture was removed before loading finished".into()));
+                }
+
+                Texture::from_raw_with_options(
+                    &self.renderer.context, &image, image.width(), image.height(), &decoded.label, &options
+                )
+            });
+
+            let result = match result {
+                Ok(texture) => {
+                    // Только готовую текстуру можно выгрузить и прочитать
+                    // из файла снова
+                    self.renderer.state.textures.insert(id, texture);
+                    self.resources.budget.set_source(id, &path, options);
+                    Ok(id)
+                }
+
+                Err(e) => {
+                    log::error!("Background loading of texture {} failed: {}", decoded.label, e);
+                    Err(e)
+                }
+            };
+
+            notifier.notify(result);
+        }
+
+        count
+    }
+
+    /// Сколько текстур ещё грузится в фоне
+    pub fn pending_texture_loads(&self) -> usize {
+        self.resources.loader.pending()
+    }
+
     /// Эта функция очищает текстуру из памяти. Текстура после очищения просто
-    /// перестанет отобразиться на объекте
+    /// перестанет отобразиться на объекте. Для svg текстур удаляется и
+    /// разобранный документ, для анимированных текстур все их кадры
     pub fn remove_texture(&mut self, texture_id: u32) {
         self.renderer.remove_texture(texture_id);
+        self.resources.svgs.remove(&texture_id);
+        self.resources.budget.forget(texture_id);
+        self.remove_animation(texture_id);
+    }
+
+    /// Создаёт прозрачную текстуру которую приложение заполняет само через
+    /// update_texture_region, например кадры с камеры, процедурный шум или
+    /// миникарта. Стандартный шейдер объектов берёт из R8Unorm только
+    /// красный канал, а из Rg8Unorm красный и зелёный
+    pub fn create_texture(&mut self, width: u32, height: u32, format: TextureFormat) -> Result<u32, error::MoonWalkError> {
+        let texture = Texture::create_dynamic(&self.renderer.context, width, height, format, "Dynamic Texture")?;
+        Ok(self.renderer.register_texture(texture))
+    }
+
+    /// Заменяет прямоугольник x, y, w, h текстуры пикселями из data. Строки
+    /// идут подряд без выравнивания, размер пикселя задаётся форматом
+    /// текстуры (4 байта для RGBA8, 1 для R8, 2 для RG8 и 8 для RGBA16F).
+    /// Промежуточные буферы переиспользуются, так что обновлять текстуру
+    /// можно каждый кадр. Работает и с загруженными текстурами, мипмапы при
+    /// этом пересчитываются, а бюджет видеопамяти такую текстуру больше не
+    /// выгружает
+    pub fn update_texture_region(&mut self, texture_id: u32, x: u32, y: u32, w: u32, h: u32, data: &[u8]) -> Result<(), error::MoonWalkError> {
+        self.keep_texture(texture_id);
+
+        let state = &mut self.renderer.state;
+        let ctx = &self.renderer.context;
+
+        let texture = state.textures.get(&texture_id)
+            .ok_or_else(|| error::MoonWalkError::TextureLoading(format!("Texture {} not found", texture_id)))?;
+
+        state.staging.upload(ctx, &texture.texture, [x, y], [w, h], data)?;
+
+        if texture.texture.mip_level_count() > 1 {
+            crate::rendering::mipmap::generate_mipmaps(ctx, &texture.texture)?;
+        }
+
+        Ok(())
+    }
+
+    /// Заменяет все пиксели текстуры, см. update_texture_region
+    pub fn update_texture(&mut self, texture_id: u32, data: &[u8]) -> Result<(), error::MoonWalkError> {
+        self.restore_texture(texture_id);
+        let size = self.get_texture_size(texture_id);
+        self.update_texture_region(texture_id, 0, 0, size.x as u32, size.y as u32, data)
     }
 
     /// Эта функция загружает шрифт во время выполнения программы (Этот шрифт обязательно
@@ -38,6 +189,9 @@ impl MoonWalk {
         
         let internal_id = self.renderer.text_engine.load_font_bytes(&bytes, name)?;
 
+        #[cfg(feature = "hot-reload")]
+        self.watch_asset(path, crate::hot_reload::ReloadTarget::Font(internal_id));
+
         Ok(FontAsset(internal_id.0))
     }
 
@@ -54,10 +208,13 @@ impl MoonWalk {
     }
 
     /// Возвращает размер текстуры в физических пикселях (ширина и высота) а если текстура
-    /// не найдена то возвращает нули Vec2 [0.0, 0.0]
+    /// не найдена то возвращает нули Vec2 [0.0, 0.0]. Для выгруженной бюджетом
+    /// текстуры возвращается её размер до выгрузки
     pub fn get_texture_size(&self, texture_id: u32) -> glam::Vec2 {
         if let Some(tex) = self.renderer.state.textures.get(&texture_id) {
             glam::Vec2::new(tex.texture.width() as f32, tex.texture.height() as f32)
+        } else if let Some([width, height]) = self.resources.budget.evicted_size(texture_id) {
+            glam::Vec2::new(width as f32, height as f32)
         } else {
             glam::Vec2::ZERO
         }
@@ -92,11 +249,16 @@ impl MoonWalk {
 
     /// Асинхронная загрузка текстуру. Полезен для подгрузки контента в рантайме
     /// Сначала асинхронно читает файл, потом регистрирует текстуру в рендерере
+    /// Декодирование при этом идёт в вызывающем потоке, для больших картинок
+    /// лучше подходит load_texture_background
     #[cfg(feature = "async")]
     pub async fn load_texture_async(&mut self, path: &str) -> Result<u32, error::MoonWalkError> {
         let texture = self.resources.load_texture_async(&self.renderer.context, path).await?;
 
         let id = self.renderer.register_texture(texture);
+
+        #[cfg(feature = "hot-reload")]
+        self.watch_asset(path, crate::hot_reload::ReloadTarget::Texture(id));
         
         Ok(id)
     }
@@ -108,6 +270,9 @@ impl MoonWalk {
         
         let internal_id = self.renderer.text_engine.load_font_bytes(&bytes, name)?;
 
+        #[cfg(feature = "hot-reload")]
+        self.watch_asset(path, crate::hot_reload::ReloadTarget::Font(internal_id));
+
         Ok(FontAsset(internal_id.0))
     }
 }
@@ -0,0 +1,288 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Загрузка целых svg документов. Документ разбирается через usvg и
+// растеризуется через resvg в обычную текстуру, разобранное дерево остаётся
+// в ResourceManager чтобы перерисовать текстуру в новом размере или при смене
+// scale factor без повторного чтения файла. Айди текстуры при этом не меняется
+
+use std::sync::Arc;
+
+use glam::Vec2;
+use resvg::{tiny_skia, usvg};
+
+use crate::error::MoonWalkError;
+use crate::objects::ObjectId;
+use crate::rendering::texture::Texture;
+use crate::MoonWalk;
+
+// Шаг z индекса между объектами из одного документа, чтобы порядок
+// отрисовки совпадал с порядком элементов в svg
+const SVG_Z_STEP: f32 = 0.0001;
+
+/// Объект сцены созданный из элемента верхнего уровня svg документа
+#[derive(Clone, Debug)]
+pub struct SvgObject {
+    /// Атрибут id элемента в svg, пустая строка если его нет
+    pub name: String,
+    pub object: ObjectId,
+    pub texture_id: u32,
+}
+
+// Источник текстуры: весь документ или один элемент верхнего уровня
+pub(crate) struct SvgSource {
+    tree: Arc<usvg::Tree>,
+    node: Option<usize>,
+
+    // Логических пикселей на единицу пространства svg
+    scale: Vec2,
+}
+
+impl SvgSource {
+    // Размер источника в единицах svg без масштаба
+    fn base_size(&self) -> Vec2 {
+        match self.node.and_then(|i| self.tree.root().children().get(i)) {
+            Some(node) => node.abs_layer_bounding_box()
+                .map(|b| Vec2::new(b.width(), b.height()))
+                .unwrap_or(Vec2::ZERO),
+
+            None => Vec2::new(self.tree.size().width(), self.tree.size().height()),
+        }
+    }
+
+    // Растеризует источник в rgba байты (без премультипликации) с учётом
+    // scale factor. Возвращает None для пустых элементов
+    fn rasterize(&self, scale_factor: f32) -> Option<(Vec<u8>, u32, u32)> {
+        let scale = self.scale * scale_factor;
+        let size = (self.base_size() * scale).ceil().max(Vec2::ONE);
+
+        let mut pixmap = tiny_skia::Pixmap::new(size.x as u32, size.y as u32)?;
+        let transform = tiny_skia::Transform::from_scale(scale.x, scale.y);
+
+        match self.node {
+            Some(i) => {
+                let node = self.tree.root().children().get(i)?;
+                resvg::render_node(node, transform, &mut pixmap.as_mut())?;
+            }
+
+            None => resvg::render(&self.tree, transform, &mut pixmap.as_mut()),
+        }
+
+        // tiny-skia хранит цвета премультиплицированными, а текстуры
+        // движка ожидают обычную альфу
+        let bytes = pixmap.pixels().iter()
+            .flat_map(|p| {
+                let c = p.demultiply();
+                [c.red(), c.green(), c.blue(), c.alpha()]
+            })
+            .collect();
+
+        Some((bytes, pixmap.width(), pixmap.height()))
+    }
+}
+
+// Масштаб под запрошенный размер. Нулевая сторона берётся с сохранением
+// пропорций, нулевой размер целиком означает собственный размер документа
+fn fit_scale(base: Vec2, size: Vec2) -> Vec2 {
+    let base = base.max(Vec2::splat(f32::EPSILON));
+
+    match (size.x > 0.0, size.y > 0.0) {
+        (true, true) => size / base,
+        (true, false) => Vec2::splat(size.x / base.x),
+        (false, true) => Vec2::splat(size.y / base.y),
+        (false, false) => Vec2::ONE,
+    }
+}
+
+fn parse_svg(bytes: &[u8]) -> Result<usvg::Tree, MoonWalkError> {
+    usvg::Tree::from_data(bytes, &usvg::Options::default())
+        .map_err(|e| MoonWalkError::TextureLoading(format!("SVG: {}", e)))
+}
+
+// Источники для элементов верхнего уровня вместе с их именем и рамкой в
+// пространстве svg. Пустые элементы пропускаются
+fn layer_sources(tree: &Arc<usvg::Tree>, scale: Vec2) -> Vec<(String, usvg::NonZeroRect, SvgSource)> {
+    tree.root().children().iter().enumerate()
+        .filter_map(|(i, node)| {
+            // usvg даёт пустой группе рамку 1x1, а не None
+            if let usvg::Node::Group(group) = node {
+                if !group.has_children() {
+                    return None;
+                }
+            }
+
+            let bbox = node.abs_layer_bounding_box()?;
+            let source = SvgSource { tree: tree.clone(), node: Some(i), scale };
+
+            Some((node.id().to_string(), bbox, source))
+        })
+        .collect()
+}
+
+impl MoonWalk {
+    /// Загружает svg документ из файла и растеризует его в текстуру размером size
+    /// (логические пиксели). Если одна из сторон равна нулю то она вычисляется
+    /// по пропорциям документа, если обе равны нулю то берётся размер из самого
+    /// svg. Текстура рисуется с учётом scale factor и перерисовывается при его
+    /// смене, так что на экранах с высокой плотностью пикселей картинка остаётся
+    /// чёткой. Путь работает так же как в load_texture
+    ///  [!] Текст внутри svg (<text>) не поддерживается, его нужно перевести в
+    ///      кривые в редакторе перед экспортом
+    pub fn load_svg(&mut self, path: &str, size: Vec2) -> Result<u32, MoonWalkError> {
+        let bytes = self.resources.read_bytes(path)?;
+        self.load_svg_from_bytes(&bytes, size)
+    }
+
+    /// То же что и load_svg, но svg документ передаётся набором байт
+    pub fn load_svg_from_bytes(&mut self, bytes: &[u8], size: Vec2) -> Result<u32, MoonWalkError> {
+        let tree = Arc::new(parse_svg(bytes)?);
+        let base = Vec2::new(tree.size().width(), tree.size().height());
+
+        let source = SvgSource { tree, node: None, scale: fit_scale(base, size) };
+
+        self.register_svg(source)?
+            .ok_or_else(|| MoonWalkError::TextureLoading("SVG: empty document".into()))
+    }
+
+    /// Загружает svg документ как набор объектов сцены: каждый элемент верхнего
+    /// уровня (обычно слой или группа) растеризуется в свою текстуру и ставится
+    /// прямоугольником на своё место в документе. Документ масштабируется под
+    /// size так же как в load_svg, левый верхний угол документа в (0, 0).
+    /// Объекты идут в порядке документа и получают растущий z индекс начиная
+    /// с нуля, пустые элементы пропускаются
+    pub fn load_svg_objects(&mut self, path: &str, size: Vec2) -> Result<Vec<SvgObject>, MoonWalkError> {
+        let bytes = self.resources.read_bytes(path)?;
+        self.load_svg_objects_from_bytes(&bytes, size)
+    }
+
+    /// То же что и load_svg_objects, но svg документ передаётся набором байт
+    pub fn load_svg_objects_from_bytes(
+        &mut self,
+        bytes: &[u8],
+        size: Vec2
+    ) -> Result<Vec<SvgObject>, MoonWalkError> {
+        let tree = Arc::new(parse_svg(bytes)?);
+        let base = Vec2::new(tree.size().width(), tree.size().height());
+        let scale = fit_scale(base, size);
+
+        let mut objects = Vec::new();
+
+        for (name, bbox, source) in layer_sources(&tree, scale) {
+            let Some(texture_id) = self.register_svg(source)? else {
+                continue;
+            };
+
+            let object = self.new_rect();
+            self.set_position(object, Vec2::new(bbox.x(), bbox.y()) * scale);
+            self.set_size(object, Vec2::new(bbox.width(), bbox.height()) * scale);
+            self.set_texture(object, texture_id);
+            self.set_z_index(object, objects.len() as f32 * SVG_Z_STEP);
+
+            objects.push(SvgObject { name, object, texture_id });
+        }
+
+        Ok(objects)
+    }
+
+    /// Перерисовывает svg текстуру (из load_svg или load_svg_objects) под новый
+    /// размер в логических пикселях, правила для нулевых сторон как в load_svg.
+    /// Айди текстуры остаётся прежним, объекты с ней обновятся сами. Нужно
+    /// вызывать когда объект с svg сильно увеличивается, иначе он будет мыльным.
+    /// Возвращает false если это не svg текстура
+    pub fn resize_svg(&mut self, texture_id: u32, size: Vec2) -> Result<bool, MoonWalkError> {
+        let Some(source) = self.resources.svgs.get_mut(&texture_id) else {
+            return Ok(false);
+        };
+
+        source.scale = fit_scale(source.base_size(), size);
+        self.rasterize_svg(texture_id)?;
+
+        Ok(true)
+    }
+
+    // Перерисовывает все svg текстуры, вызывается при смене scale factor
+    pub(crate) fn rasterize_all_svgs(&mut self) -> Result<(), MoonWalkError> {
+        let ids: Vec<u32> = self.resources.svgs.keys().copied().collect();
+
+        for id in ids {
+            self.rasterize_svg(id)?;
+        }
+
+        Ok(())
+    }
+
+    fn rasterize_svg(&mut self, texture_id: u32) -> Result<(), MoonWalkError> {
+        let Some(source) = self.resources.svgs.get(&texture_id) else {
+            return Ok(());
+        };
+
+        if let Some((bytes, w, h)) = source.rasterize(self.renderer.scale_factor) {
+            let texture = Texture::from_raw(&self.renderer.context, &bytes, w, h, "SVG Texture")?;
+            self.renderer.state.textures.insert(texture_id, texture);
+        }
+
+        Ok(())
+    }
+
+    // Растеризует источник и регистрирует текстуру. None если рисовать нечего
+    fn register_svg(&mut self, source: SvgSource) -> Result<Option<u32>, MoonWalkError> {
+        let Some((bytes, w, h)) = source.rasterize(self.renderer.scale_factor) else {
+            return Ok(None);
+        };
+
+        let texture = Texture::from_raw(&self.renderer.context, &bytes, w, h, "SVG Texture")?;
+        let id = self.renderer.register_texture(texture);
+        self.resources.svgs.insert(id, source);
+
+        Ok(Some(id))
+    }
+}
+
+#[test]
+fn svg_test() {
+    // Битые байты дают ошибку загрузки, а не панику
+    assert!(matches!(parse_svg(b"<svg><rect"), Err(MoonWalkError::TextureLoading(_))));
+    assert!(matches!(parse_svg(&[0xff, 0x00, 0x13]), Err(MoonWalkError::TextureLoading(_))));
+
+    let data = br##"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="50">
+        <rect id="back" x="0" y="0" width="100" height="50" fill="#203040"/>
+        <g id="empty"/>
+        <circle id="dot" cx="25" cy="25" r="10" fill="#ff0000"/>
+        <g id="group">
+            <rect x="60" y="10" width="20" height="30" fill="#00ff00"/>
+        </g>
+    </svg>"##;
+
+    let tree = Arc::new(parse_svg(data).unwrap());
+
+    // Пустая группа не становится объектом
+    let layers = layer_sources(&tree, Vec2::ONE);
+    let names: Vec<&str> = layers.iter().map(|(name, _, _)| name.as_str()).collect();
+    assert_eq!(names, ["back", "dot", "group"]);
+
+    let (_, bbox, _) = &layers[2];
+    assert_eq!((bbox.x(), bbox.y(), bbox.width(), bbox.height()), (60.0, 10.0, 20.0, 30.0));
+
+    // Нулевая сторона считается по пропорциям документа
+    let base = Vec2::new(100.0, 50.0);
+    assert_eq!(fit_scale(base, Vec2::ZERO), Vec2::ONE);
+    assert_eq!(fit_scale(base, Vec2::new(200.0, 0.0)), Vec2::splat(2.0));
+    assert_eq!(fit_scale(base, Vec2::new(0.0, 25.0)), Vec2::splat(0.5));
+    assert_eq!(fit_scale(base, Vec2::new(50.0, 100.0)), Vec2::new(0.5, 2.0));
+
+    // Перерисовка под новый размер и scale factor как в resize_svg
+    let mut source = SvgSource { tree: tree.clone(), node: None, scale: Vec2::ONE };
+    let (bytes, w, h) = source.rasterize(1.0).unwrap();
+    assert_eq!((w, h), (100, 50));
+    assert_eq!(bytes.len(), (w * h * 4) as usize);
+    assert_eq!(&bytes[..4], &[0x20, 0x30, 0x40, 0xff]);
+
+    source.scale = fit_scale(source.base_size(), Vec2::new(0.0, 100.0));
+    let (_, w, h) = source.rasterize(2.0).unwrap();
+    assert_eq!((w, h), (400, 200));
+
+    // Элемент рисуется в размер своей рамки
+    let (_, _, group) = &layers[2];
+    let (_, w, h) = group.rasterize(1.0).unwrap();
+    assert_eq!((w, h), (20, 30));
+}
@@ -0,0 +1,609 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use glam::{Vec2, Vec4};
+use std::ops::Range;
+
+use crate::objects::ObjectId;
+use crate::textware::{Motion, TextStyle, FontFeatures};
+use crate::{MoonWalk, FontAsset, PathBuilder};
+
+/// Способ рендеринга глифов текста
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TextRenderMode {
+    /// Режим берётся из настроек шрифта (set_font_render_mode). Для самого
+    /// шрифта Default означает Bitmap
+    #[default]
+    Default,
+
+    /// Обычный растровый атлас. Каждый размер шрифта растеризуется отдельно,
+    /// даёт самый точный хинтинг для статичного текста
+    Bitmap,
+
+    /// Поле расстояний (SDF). Глиф растеризуется один раз и рисуется в любом
+    /// масштабе без новых растеризаций, подходит для анимации размера и зума.
+    /// [?] Цветные глифы (эмодзи) всё равно рисуются через растровый атлас
+    Sdf,
+}
+
+impl TextRenderMode {
+    pub(crate) fn to_u8(self) -> u8 {
+        match self {
+            TextRenderMode::Default => 0,
+            TextRenderMode::Bitmap => 1,
+            TextRenderMode::Sdf => 2,
+        }
+    }
+
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TextRenderMode::Bitmap,
+            2 => TextRenderMode::Sdf,
+            _ => TextRenderMode::Default,
+        }
+    }
+}
+
+/// Что делать с текстом который не влезает в границы (set_text_size) или в
+/// лимит строк (set_max_lines)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Текст рисуется за границами по высоте. Лимит строк всё равно
+    /// применяется, лишние строки просто отбрасываются
+    #[default]
+    Visible,
+
+    /// Строки которые не влезают по высоте целиком отбрасываются
+    Clip,
+
+    /// Как Clip, но последняя видимая строка заканчивается многоточием
+    Ellipsis,
+}
+
+/// Настройки текста вдоль пути (set_text_path)
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct TextPathOptions {
+    /// Расстояние вдоль пути до точки привязки текста в пикселях. К этой
+    /// точке по set_text_align привязано начало, середина или конец текста
+    pub start_offset: f32,
+
+    /// Сдвиг базовой линии от пути, положительный поднимает текст над путём
+    pub baseline_offset: f32,
+}
+
+/// Базовое направление абзацев текста. По умолчанию направление каждого
+/// абзаца определяется по первому сильному символу (правила Unicode Bidi),
+/// Ltr и Rtl задают его явно. От направления зависят TextAlign::Start/End,
+/// сторона на которой оказываются нейтральные символы и порядок
+/// смешанного текста
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    #[default]
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    pub(crate) fn to_u8(self) -> u8 {
+        match self {
+            TextDirection::Auto => 0,
+            TextDirection::Ltr => 1,
+            TextDirection::Rtl => 2,
+        }
+    }
+
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TextDirection::Ltr,
+            2 => TextDirection::Rtl,
+            _ => TextDirection::Auto,
+        }
+    }
+}
+
+impl Overflow {
+    pub(crate) fn to_u8(self) -> u8 {
+        match self {
+            Overflow::Visible => 0,
+            Overflow::Clip => 1,
+            Overflow::Ellipsis => 2,
+        }
+    }
+
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Overflow::Clip,
+            2 => Overflow::Ellipsis,
+            _ => Overflow::Visible,
+        }
+    }
+}
+
+/// Начертание шрифта
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+
+    /// Курсив, берётся курсивное начертание из загруженных шрифтов семейства
+    Italic,
+
+    /// Наклонное начертание
+    Oblique,
+}
+
+impl FontStyle {
+    pub(crate) fn to_u8(self) -> u8 {
+        match self {
+            FontStyle::Normal => 0,
+            FontStyle::Italic => 1,
+            FontStyle::Oblique => 2,
+        }
+    }
+
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            1 => FontStyle::Italic,
+            2 => FontStyle::Oblique,
+            _ => FontStyle::Normal,
+        }
+    }
+}
+
+/// К какой стороне относится позиция в тексте. Нужно в местах где один
+/// индекс соответствует двум визуальным местам: на переносе строки и на
+/// границе разных направлений текста
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Affinity {
+    /// Каретка прижата к символу перед индексом
+    Before,
+
+    /// Каретка прижата к символу после индекса
+    #[default]
+    After,
+}
+
+impl Affinity {
+    fn to_cosmic(self) -> cosmic_text::Affinity {
+        match self {
+            Affinity::Before => cosmic_text::Affinity::Before,
+            Affinity::After => cosmic_text::Affinity::After,
+        }
+    }
+
+    fn from_cosmic(affinity: cosmic_text::Affinity) -> Self {
+        match affinity {
+            cosmic_text::Affinity::Before => Affinity::Before,
+            cosmic_text::Affinity::After => Affinity::After,
+        }
+    }
+}
+
+/// Позиция в тексте. index это байтовый индекс в строке объекта (всегда на
+/// границе символа)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct TextCursor {
+    pub index: usize,
+    pub affinity: Affinity,
+}
+
+impl TextCursor {
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            affinity: Affinity::After,
+        }
+    }
+}
+
+/// Прямоугольник в мировых координатах (левый верхний угол и размер)
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Rect {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+/// Движение каретки для text_motion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextMotion {
+    /// Предыдущая графема в логическом порядке
+    Previous,
+
+    /// Следующая графема в логическом порядке
+    Next,
+
+    /// Ближайшая позиция слева на экране. В смешанном тексте каретка
+    /// проходит фрагменты в том порядке, в каком они нарисованы
+    Left,
+
+    /// Ближайшая позиция справа на экране
+    Right,
+
+    /// Начало текущего или предыдущего слова
+    PreviousWord,
+
+    /// Конец текущего или следующего слова
+    NextWord,
+
+    /// Начало визуальной строки (с учётом переноса)
+    LineStart,
+
+    /// Конец визуальной строки (с учётом переноса)
+    LineEnd,
+
+    /// Строка выше с сохранением x
+    Up,
+
+    /// Строка ниже с сохранением x
+    Down,
+
+    /// Начало текста
+    TextStart,
+
+    /// Конец текста
+    TextEnd,
+}
+
+impl TextMotion {
+    fn to_internal(self) -> Motion {
+        match self {
+            TextMotion::Previous => Motion::Previous,
+            TextMotion::Next => Motion::Next,
+            TextMotion::Left => Motion::Left,
+            TextMotion::Right => Motion::Right,
+            TextMotion::PreviousWord => Motion::PreviousWord,
+            TextMotion::NextWord => Motion::NextWord,
+            TextMotion::LineStart => Motion::LineStart,
+            TextMotion::LineEnd => Motion::LineEnd,
+            TextMotion::Up => Motion::Up,
+            TextMotion::Down => Motion::Down,
+            TextMotion::TextStart => Motion::TextStart,
+            TextMotion::TextEnd => Motion::TextEnd,
+        }
+    }
+}
+
+impl MoonWalk {
+    /// Раскладывает текст объекта с его текущими параметрами (тот же буфер
+    /// что использует батчинг) и возвращает ключ буфера и позицию объекта
+    fn layout_text_object(&mut self, id: ObjectId) -> Option<(u64, Vec2)> {
+        let store = &self.renderer.state.store;
+        let idx = id.index();
+
+        if !store.is_alive(id) || idx >= store.text_contents.len() {
+            return None;
+        }
+
+        let key = idx as u64;
+
+        self.renderer.text_engine.process_text(key, &store.text_contents[idx], store.text_params(idx));
+
+        Some((key, store.positions[idx]))
+    }
+
+    /// Находит позицию в тексте под точкой point (мировые координаты). Если
+    /// точка вне текста возвращается ближайшая позиция, для пустого текста
+    /// это индекс 0. Поворот объекта не учитывается
+    pub fn text_hit(&mut self, id: ObjectId, point: Vec2) -> TextCursor {
+        let Some((key, position)) = self.layout_text_object(id) else {
+            return TextCursor::default();
+        };
+
+        let local = point - position;
+        let text = self.renderer.state.store.get_text(id);
+
+        match self.renderer.text_engine.hit(key, text, local.x, local.y) {
+            Some((index, affinity)) => TextCursor {
+                index,
+                affinity: Affinity::from_cosmic(affinity),
+            },
+            None => TextCursor::new(text.len()),
+        }
+    }
+
+    /// Прямоугольник каретки (ширина 1 пиксель, высота строки) для позиции
+    /// cursor в мировых координатах
+    pub fn caret_rect(&mut self, id: ObjectId, cursor: TextCursor) -> Rect {
+        let Some((key, position)) = self.layout_text_object(id) else {
+            return Rect::default();
+        };
+
+        let text = self.renderer.state.store.get_text(id);
+        let caret = self.renderer.text_engine.caret_position(
+            key,
+            text,
+            cursor.index,
+            cursor.affinity.to_cosmic(),
+        );
+
+        match caret {
+            Some((x, top, height)) => Rect {
+                position: position + Vec2::new(x, top),
+                size: Vec2::new(1.0, height),
+            },
+            None => Rect {
+                position,
+                size: Vec2::new(1.0, self.renderer.state.store.get_font_size(id) * 1.2),
+            },
+        }
+    }
+
+    /// Прямоугольники выделения для байтового диапазона range в мировых
+    /// координатах. По одному или несколько на каждую визуальную строку
+    pub fn selection_rects(&mut self, id: ObjectId, range: Range<usize>) -> Vec<Rect> {
+        let Some((key, position)) = self.layout_text_object(id) else {
+            return Vec::new();
+        };
+
+        let text = self.renderer.state.store.get_text(id);
+
+        self.renderer.text_engine
+            .selection_rects(key, text, range.start, range.end)
+            .into_iter()
+            .map(|[x, y, w, h]| Rect {
+                position: position + Vec2::new(x, y),
+                size: Vec2::new(w, h),
+            })
+            .collect()
+    }
+
+    /// Двигает каретку по тексту. Для Up/Down нужно передавать x который
+    /// вернул предыдущий вызов, тогда каретка сохраняет горизонтальную
+    /// позицию при проходе через короткие строки. Остальные движения
+    /// возвращают None и сбрасывают его
+    pub fn text_motion(
+        &mut self,
+        id: ObjectId,
+        cursor: TextCursor,
+        motion: TextMotion,
+        preferred_x: Option<f32>,
+    ) -> (TextCursor, Option<f32>) {
+        if self.layout_text_object(id).is_none() {
+            return (cursor, None);
+        }
+
+        let key = id.index() as u64;
+        let text = self.renderer.state.store.get_text(id);
+
+        let (index, affinity, x) = self.renderer.text_engine.motion(
+            key,
+            text,
+            cursor.index,
+            cursor.affinity.to_cosmic(),
+            motion.to_internal(),
+            preferred_x,
+        );
+
+        (TextCursor { index, affinity: Affinity::from_cosmic(affinity) }, x)
+    }
+
+    /// Устанавливает поведение текста при переполнении. Для однострочных
+    /// заголовков с многоточием достаточно set_max_lines(id, 1) и
+    /// Overflow::Ellipsis. Многоточие ставится по границе графемы и с учётом
+    /// направления текста
+    pub fn set_text_overflow(&mut self, id: ObjectId, overflow: Overflow) {
+        self.renderer.state.store.set_text_overflow(id, overflow.to_u8());
+    }
+
+    /// Получить поведение текста при переполнении
+    pub fn get_text_overflow(&self, id: ObjectId) -> Overflow {
+        Overflow::from_u8(self.renderer.state.store.get_text_overflow(id))
+    }
+
+    /// Устанавливает базовое направление абзацев текста. Для арабского и
+    /// иврита с TextDirection::Rtl строка вроде "123 شارع" встанет справа
+    /// даже если начинается с цифр
+    pub fn set_text_direction(&mut self, id: ObjectId, direction: TextDirection) {
+        self.renderer.state.store.set_text_direction(id, direction.to_u8());
+    }
+
+    /// Получить базовое направление текста
+    pub fn get_text_direction(&self, id: ObjectId) -> TextDirection {
+        TextDirection::from_u8(self.renderer.state.store.get_text_direction(id))
+    }
+
+    /// Ограничивает количество визуальных строк (с учётом переноса). 0
+    /// снимает ограничение
+    pub fn set_max_lines(&mut self, id: ObjectId, max_lines: usize) {
+        self.renderer.state.store.set_text_max_lines(id, max_lines as u32);
+    }
+
+    /// Получить лимит строк текста, 0 если лимита нет
+    pub fn get_max_lines(&self, id: ObjectId) -> usize {
+        self.renderer.state.store.get_text_max_lines(id) as usize
+    }
+
+    /// Размер текстового объекта в том виде как он будет нарисован: с его
+    /// границами, выравниванием, лимитом строк и многоточием. В отличии от
+    /// measure_text учитывает обрезку текста
+    pub fn measure_text_object(&mut self, id: ObjectId) -> Vec2 {
+        let Some((key, _)) = self.layout_text_object(id) else {
+            return Vec2::ZERO;
+        };
+
+        let (w, h) = self.renderer.text_engine.measure_buffer(key);
+        Vec2::new(w, h)
+    }
+
+    fn update_text_style(&mut self, id: ObjectId, update: impl FnOnce(&mut TextStyle)) {
+        let store = &mut self.renderer.state.store;
+        let mut style = store.get_text_style(id);
+
+        update(&mut style);
+        store.set_text_style(id, style);
+    }
+
+    /// Высота строки в пикселях. 0.0 возвращает высоту по умолчанию (1.2 от
+    /// размера шрифта)
+    pub fn set_line_height(&mut self, id: ObjectId, line_height: f32) {
+        self.update_text_style(id, |style| style.line_height = line_height.max(0.0));
+    }
+
+    /// Получить высоту строки текста в пикселях
+    pub fn get_line_height(&self, id: ObjectId) -> f32 {
+        let store = &self.renderer.state.store;
+        store.get_text_style(id).line_height(store.get_font_size(id))
+    }
+
+    /// Межбуквенный интервал в пикселях, может быть отрицательным. Перенос
+    /// строк учитывает интервал
+    pub fn set_letter_spacing(&mut self, id: ObjectId, spacing: f32) {
+        self.update_text_style(id, |style| style.letter_spacing = spacing);
+    }
+
+    pub fn get_letter_spacing(&self, id: ObjectId) -> f32 {
+        self.renderer.state.store.get_text_style(id).letter_spacing
+    }
+
+    /// Насыщенность шрифта от 100 до 900 (400 обычный, 700 жирный). Берётся
+    /// ближайшее начертание из загруженных шрифтов семейства
+    pub fn set_font_weight(&mut self, id: ObjectId, weight: u16) {
+        self.update_text_style(id, |style| style.weight = weight.clamp(1, 1000));
+    }
+
+    pub fn get_font_weight(&self, id: ObjectId) -> u16 {
+        self.renderer.state.store.get_text_style(id).weight
+    }
+
+    pub fn set_font_style(&mut self, id: ObjectId, font_style: FontStyle) {
+        self.update_text_style(id, |style| style.style = font_style.to_u8());
+    }
+
+    pub fn get_font_style(&self, id: ObjectId) -> FontStyle {
+        FontStyle::from_u8(self.renderer.state.store.get_text_style(id).style)
+    }
+
+    /// OpenType фичи в формате "tnum", "liga=0", "+kern", "-liga". Пустой
+    /// список сбрасывает фичи. Максимум 8 фич.
+    ///  [!] Работает только tnum (цифры выравниваются по ширине самой
+    ///      широкой цифры шрифта): cosmic-text 0.11 не передаёт фичи в
+    ///      шейпинг. Остальные фичи, в том числе liga=0 и kern, ни на что не
+    ///      влияют, о каждой из них пишется предупреждение в лог
+    pub fn set_font_features(&mut self, id: ObjectId, features: &[&str]) {
+        let features = FontFeatures::parse(features);
+        self.update_text_style(id, |style| style.features = features);
+    }
+
+    /// Оси вариативного шрифта, например &[("wght", 650.0), ("wdth", 75.0)].
+    /// wght работает как set_font_weight, wdth это ширина в процентах,
+    /// остальные оси пропускаются с предупреждением в лог.
+    ///  [!] Значения осей в шрифт не передаются: по ним выбирается ближайшее
+    ///      начертание из загруженных шрифтов, так что wght 650 у
+    ///      вариативного шрифта нарисуется его ближайшим статичным начертанием
+    pub fn set_font_variations(&mut self, id: ObjectId, variations: &[(&str, f32)]) {
+        self.update_text_style(id, |style| {
+            for &(axis, value) in variations {
+                style.set_variation(axis, value);
+            }
+        });
+    }
+
+    /// Устанавливает режим рендеринга для конкретного текстового объекта.
+    /// TextRenderMode::Default возвращает объект к режиму его шрифта
+    pub fn set_text_render_mode(&mut self, id: ObjectId, mode: TextRenderMode) {
+        self.renderer.state.store.set_text_render_mode(id, mode.to_u8());
+    }
+
+    /// Получить режим рендеринга текстового объекта
+    pub fn get_text_render_mode(&self, id: ObjectId) -> TextRenderMode {
+        TextRenderMode::from_u8(self.renderer.state.store.get_text_render_mode(id))
+    }
+
+    /// Устанавливает режим рендеринга по умолчанию для всех текстов с этим
+    /// шрифтом (в том числе в контейнерах). Объекты с явно заданным режимом
+    /// через set_text_render_mode его не меняют
+    pub fn set_font_render_mode(&mut self, font: FontAsset, mode: TextRenderMode) {
+        let font_id = crate::textware::FontId(font.0);
+
+        self.renderer.text_engine.font_system.set_sdf(font_id, mode == TextRenderMode::Sdf);
+
+        // Шрифт влияет на все тексты, поэтому батч нужно пересобрать
+        self.renderer.state.store.dirty = true;
+    }
+
+    /// Обводка текста шириной width (в пикселях) и цветом color. Ширина 0.0
+    /// выключает обводку. Эффекты текста рисуются через SDF атлас, поэтому
+    /// работают в любом режиме рендеринга.
+    /// [!] Ширина обводки, тени и свечения ограничена четвертью размера шрифта
+    pub fn set_text_outline(&mut self, id: ObjectId, width: f32, color: Vec4) {
+        let outline = if width > 0.0 { Some((width, color)) } else { None };
+        self.renderer.state.store.set_text_outline(id, outline);
+    }
+
+    /// Тень текста. offset это смещение тени в пикселях, blur это ширина
+    /// размытия края. Тень повторяет контур вместе с обводкой
+    pub fn set_text_shadow(&mut self, id: ObjectId, offset: Vec2, blur: f32, color: Vec4) {
+        self.renderer.state.store.set_text_shadow(id, Some((offset, blur, color)));
+    }
+
+    /// Убирает тень текста, обводка и свечение остаются
+    pub fn clear_text_shadow(&mut self, id: ObjectId) {
+        self.renderer.state.store.set_text_shadow(id, None);
+    }
+
+    /// Убирает обводку текста, то же что set_text_outline с шириной 0.0
+    pub fn clear_text_outline(&mut self, id: ObjectId) {
+        self.renderer.state.store.set_text_outline(id, None);
+    }
+
+    /// Свечение вокруг текста радиусом radius (в пикселях). Радиус 0.0
+    /// выключает свечение
+    pub fn set_text_glow(&mut self, id: ObjectId, radius: f32, color: Vec4) {
+        let glow = if radius > 0.0 { Some((radius, color)) } else { None };
+        self.renderer.state.store.set_text_glow(id, glow);
+    }
+
+    /// Убирает свечение текста, то же что set_text_glow с радиусом 0.0
+    pub fn clear_text_glow(&mut self, id: ObjectId) {
+        self.renderer.state.store.set_text_glow(id, None);
+    }
+
+    /// Убирает обводку, тень и свечение с текста
+    pub fn reset_text_effects(&mut self, id: ObjectId) {
+        self.clear_text_outline(id);
+        self.clear_text_shadow(id);
+        self.clear_text_glow(id);
+    }
+
+    /// Рисует текст вдоль пути (координаты пути относительно позиции
+    /// объекта). Каждый глиф ставится на путь и поворачивается по
+    /// касательной, интервал (set_letter_spacing) работает как обычно.
+    /// Выравнивание задаёт что привязано к start_offset, а Justified
+    /// растягивает текст до конца пути, на замкнутом пути (круглые бейджи)
+    /// глифы распределяются по всей длине. Используется первый подпуть.
+    /// [?] Каретка, выделение и попадание работают в обычной, не изогнутой
+    /// раскладке текста
+    pub fn set_text_path(&mut self, id: ObjectId, path: PathBuilder, options: TextPathOptions) {
+        let mut text_path = path.into_text_path();
+        text_path.start_offset = options.start_offset;
+        text_path.baseline_offset = options.baseline_offset;
+
+        self.renderer.state.store.set_text_path(id, Some(text_path));
+    }
+
+    /// То же что set_text_path, но путь задаётся строкой SVG (атрибут d)
+    pub fn set_text_path_svg(&mut self, id: ObjectId, data: &str, options: TextPathOptions) -> Result<(), String> {
+        let mut path = PathBuilder::new();
+        crate::path::svg::parse_svg_path(&mut path, data)?;
+
+        self.set_text_path(id, path, options);
+        Ok(())
+    }
+
+    /// Меняет настройки пути без его перестроения. Удобно для анимации
+    /// бегущей строки через start_offset
+    pub fn set_text_path_options(&mut self, id: ObjectId, options: TextPathOptions) {
+        self.renderer.state.store.set_text_path_offsets(id, options.start_offset, options.baseline_offset);
+    }
+
+    /// Возвращает текст к обычной раскладке
+    pub fn clear_text_path(&mut self, id: ObjectId) {
+        self.renderer.state.store.set_text_path(id, None);
+    }
+
+    /// Длина пути текста в пикселях, 0.0 если пути нет
+    pub fn get_text_path_length(&self, id: ObjectId) -> f32 {
+        self.renderer.state.store.get_text_path(id).map_or(0.0, |path| path.length())
+    }
+}
@@ -0,0 +1,352 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Анимированные текстуры из GIF, APNG и анимированных WebP. Все кадры один
+// раз декодируются и раскладываются сеткой по страницам в видеопамяти. У
+// анимации есть своя текстура показа размером в один кадр, при смене кадра
+// он копируется в неё на видеокарте. Объекты просто ставят айди этой
+// текстуры и анимируются сами, менять им uv или текстуру не нужно
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use image::{AnimationDecoder, ImageFormat, RgbaImage};
+use parking_lot::Mutex;
+
+use crate::error::MoonWalkError;
+use crate::gpu::Context;
+use crate::rendering::texture::{Texture, TextureFormat};
+use crate::MoonWalk;
+
+// Размер страницы с кадрами не больше этого, даже если видеокарта позволяет
+const MAX_PAGE_SIZE: u32 = 4096;
+
+// Задержки короче этой браузеры считают ошибкой файла и показывают кадр
+// 100 мс, здесь так же
+const MIN_DELAY: f32 = 0.02;
+const FALLBACK_DELAY: f32 = 0.1;
+
+/// Кадр анимации: картинка и сколько секунд её показывать
+pub struct AnimationFrame {
+    pub image: RgbaImage,
+    pub delay: f32,
+}
+
+/// Декодирует все кадры GIF, APNG или WebP. Обычная картинка даёт один кадр
+pub fn decode_animation(bytes: &[u8]) -> Result<Vec<AnimationFrame>, MoonWalkError> {
+    let error = |e: image::ImageError| MoonWalkError::TextureLoading(e.to_string());
+    let format = image::guess_format(bytes).map_err(error)?;
+
+    let frames = match format {
+        ImageFormat::Gif => {
+            image::codecs::gif::GifDecoder::new(Cursor::new(bytes)).map_err(error)?
+                .into_frames().collect_frames().map_err(error)?
+        }
+
+        ImageFormat::Png => {
+            let decoder = image::codecs::png::PngDecoder::new(Cursor::new(bytes)).map_err(error)?;
+
+            if !decoder.is_apng().map_err(error)? {
+                return single_frame(bytes);
+            }
+
+            decoder.apng().map_err(error)?
+                .into_frames().collect_frames().map_err(error)?
+        }
+
+        ImageFormat::WebP => {
+            let decoder = image::codecs::webp::WebPDecoder::new(Cursor::new(bytes)).map_err(error)?;
+
+            if !decoder.has_animation() {
+                return single_frame(bytes);
+            }
+
+            decoder.into_frames().collect_frames().map_err(error)?
+        }
+
+        _ => return single_frame(bytes),
+    };
+
+    if frames.is_empty() {
+        return Err(MoonWalkError::TextureLoading("Animation has no frames".into()));
+    }
+
+    Ok(frames.into_iter()
+        .map(|frame| {
+            let (numer, denom) = frame.delay().numer_denom_ms();
+            let delay = numer as f32 / denom.max(1) as f32 / 1000.0;
+
+            AnimationFrame {
+                delay: if delay < MIN_DELAY { FALLBACK_DELAY } else { delay },
+                image: frame.into_buffer(),
+            }
+        })
+        .collect())
+}
+
+fn single_frame(bytes: &[u8]) -> Result<Vec<AnimationFrame>, MoonWalkError> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| MoonWalkError::TextureLoading(e.to_string()))?;
+
+    Ok(vec![AnimationFrame { image: image.to_rgba8(), delay: FALLBACK_DELAY }])
+}
+
+// Состояние проигрывания, общее для всех клонов ручки
+struct Playback {
+    frame: usize,
+    elapsed: f32,
+    playing: bool,
+    looping: bool,
+    speed: f32,
+}
+
+/// Ручка анимированной текстуры. Айди текстуры (texture_id) ставится
+/// объектам как обычная текстура, время двигается через
+/// mw.update_animated_textures(dt). Новая анимация играет с первого кадра с
+/// повтором. Клоны общие. Удаляется через mw.remove_texture(texture_id)
+#[derive(Clone)]
+pub struct AnimatedTexture {
+    texture_id: u32,
+    width: u32,
+    height: u32,
+    delays: Arc<[f32]>,
+    playback: Arc<Mutex<Playback>>,
+}
+
+impl AnimatedTexture {
+    pub fn texture_id(&self) -> u32 {
+        self.texture_id
+    }
+
+    /// Размер кадра в пикселях
+    pub fn size(&self) -> glam::Vec2 {
+        glam::Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.delays.len()
+    }
+
+    /// Длительность одного прохода в секундах при скорости 1
+    pub fn duration(&self) -> f32 {
+        self.delays.iter().sum()
+    }
+
+    /// Задержка кадра в секундах
+    pub fn frame_delay(&self, frame: usize) -> Option<f32> {
+        self.delays.get(frame).copied()
+    }
+
+    /// Номер текущего кадра
+    pub fn frame(&self) -> usize {
+        self.playback.lock().frame
+    }
+
+    pub fn play(&self) {
+        let mut playback = self.playback.lock();
+
+        // Закончившаяся анимация без повтора начинается заново
+        if !playback.looping && playback.frame + 1 == self.delays.len() {
+            playback.frame = 0;
+            playback.elapsed = 0.0;
+        }
+
+        playback.playing = true;
+    }
+
+    pub fn pause(&self) {
+        self.playback.lock().playing = false;
+    }
+
+    /// Пауза и возврат на первый кадр
+    pub fn stop(&self) {
+        let mut playback = self.playback.lock();
+        playback.playing = false;
+        playback.frame = 0;
+        playback.elapsed = 0.0;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playback.lock().playing
+    }
+
+    /// Повтор анимации. Без повтора она останавливается на последнем кадре
+    pub fn set_loop(&self, looping: bool) {
+        self.playback.lock().looping = looping;
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.playback.lock().looping
+    }
+
+    /// Множитель скорости, отрицательные значения считаются нулём
+    pub fn set_speed(&self, speed: f32) {
+        self.playback.lock().speed = speed.max(0.0);
+    }
+
+    /// Переходит на кадр frame (ограничивается числом кадров)
+    pub fn seek(&self, frame: usize) {
+        let mut playback = self.playback.lock();
+        playback.frame = frame.min(self.delays.len() - 1);
+        playback.elapsed = 0.0;
+    }
+}
+
+// Анимация внутри движка: где лежат кадры и какой сейчас показан
+pub(crate) struct AnimatedFrames {
+    handle: AnimatedTexture,
+
+    // Айди текстур страниц и положение каждого кадра на них
+    pages: Vec<u32>,
+    frames: Vec<(usize, u32, u32)>,
+
+    shown: usize,
+}
+
+impl AnimatedFrames {
+    /// Раскладывает кадры по страницам и создаёт текстуру показа
+    pub fn upload(mw: &mut MoonWalk, frames: Vec<AnimationFrame>, label: &str) -> Result<AnimatedTexture, MoonWalkError> {
+        let (width, height) = frames.first()
+            .map(|frame| frame.image.dimensions())
+            .ok_or_else(|| MoonWalkError::TextureLoading("Animation has no frames".into()))?;
+
+        let ctx = &mw.renderer.context;
+        let page_limit = ctx.device.limits().max_texture_dimension_2d.min(MAX_PAGE_SIZE);
+
+        if width == 0 || height == 0 || width > page_limit || height > page_limit {
+            return Err(MoonWalkError::TextureLoading(format!("Unsupported animation frame size {}x{}", width, height)));
+        }
+
+        let columns = (page_limit / width) as usize;
+        let per_page = columns * (page_limit / height) as usize;
+
+        let mut pages = Vec::new();
+        let mut placed = Vec::with_capacity(frames.len());
+
+        for (page, chunk) in frames.chunks(per_page).enumerate() {
+            let page_columns = chunk.len().min(columns) as u32;
+            let page_rows = chunk.len().div_ceil(columns) as u32;
+            let mut sheet = RgbaImage::new(page_columns * width, page_rows * height);
+
+            for (i, frame) in chunk.iter().enumerate() {
+                let x = (i % columns) as u32 * width;
+                let y = (i / columns) as u32 * height;
+
+                // Кадры разного размера бывают только в битых файлах, лишнее
+                // обрезается
+                image::imageops::replace(&mut sheet, &frame.image, x as i64, y as i64);
+                placed.push((page, x, y));
+            }
+
+            pages.push(Texture::from_raw(ctx, &sheet, sheet.width(), sheet.height(), &format!("{} frames {}", label, page))?);
+        }
+
+        // Текстуры регистрируются только когда все созданы без ошибок
+        let display = Texture::create_dynamic(ctx, width, height, TextureFormat::Rgba8UnormSrgb, label)?;
+        let pages = pages.into_iter().map(|page| mw.renderer.register_texture(page)).collect();
+        let texture_id = mw.renderer.register_texture(display);
+
+        let handle = AnimatedTexture {
+            texture_id,
+            width,
+            height,
+            delays: frames.iter().map(|frame| frame.delay).collect(),
+            playback: Arc::new(Mutex::new(Playback {
+                frame: 0,
+                elapsed: 0.0,
+                playing: true,
+                looping: true,
+                speed: 1.0,
+            })),
+        };
+
+        let animation = AnimatedFrames {
+            handle: handle.clone(),
+            pages,
+            frames: placed,
+            shown: usize::MAX,
+        };
+
+        animation.show(mw, 0);
+        mw.resources.animations.push(AnimatedFrames { shown: 0, ..animation });
+
+        Ok(handle)
+    }
+
+    pub fn texture_id(&self) -> u32 {
+        self.handle.texture_id
+    }
+
+    pub fn pages(&self) -> &[u32] {
+        &self.pages
+    }
+
+    /// Двигает время на dt секунд и копирует новый кадр в текстуру показа
+    pub fn update(&mut self, mw: &MoonWalk, dt: f32) {
+        let frame = {
+            let delays = &self.handle.delays;
+            let mut playback = self.handle.playback.lock();
+
+            if playback.playing && dt > 0.0 && delays.len() > 1 {
+                playback.elapsed += dt * playback.speed;
+
+                while playback.elapsed >= delays[playback.frame] {
+                    playback.elapsed -= delays[playback.frame];
+
+                    if playback.frame + 1 < delays.len() {
+                        playback.frame += 1;
+                    } else if playback.looping {
+                        playback.frame = 0;
+                    } else {
+                        playback.playing = false;
+                        playback.elapsed = 0.0;
+                        break;
+                    }
+                }
+            }
+
+            playback.frame
+        };
+
+        // Кадр мог смениться и через seek на паузе
+        if frame != self.shown {
+            self.show(mw, frame);
+            self.shown = frame;
+        }
+    }
+
+    fn show(&self, mw: &MoonWalk, frame: usize) {
+        let textures = &mw.renderer.state.textures;
+        let (page, x, y) = self.frames[frame];
+
+        let (Some(source), Some(target)) = (textures.get(&self.pages[page]), textures.get(&self.handle.texture_id)) else {
+            return;
+        };
+
+        copy_frame(&mw.renderer.context, &source.texture, &target.texture, x, y);
+    }
+}
+
+fn copy_frame(ctx: &Context, source: &wgpu::Texture, target: &wgpu::Texture, x: u32, y: u32) {
+    let mut encoder = ctx.create_encoder();
+
+    encoder.copy_texture_to_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: source,
+            mip_level: 0,
+            origin: wgpu::Origin3d { x, y, z: 0 },
+            aspect: wgpu::TextureAspect::All,
+        },
+
+        wgpu::TexelCopyTextureInfo {
+            texture: target,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+
+        target.size(),
+    );
+
+    ctx.submit(encoder);
+}
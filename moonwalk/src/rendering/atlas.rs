@@ -0,0 +1,452 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Упаковка многих маленьких картинок в одну или несколько больших текстур
+// (страниц атласа). Батчер начинает новый вызов отрисовки каждый раз когда
+// меняется текстура, а спрайты из одной страницы рисуются одним вызовом.
+// Картинки раскладываются по полкам: сначала самые высокие, каждая полка
+// заполняется слева направо. Вокруг картинки крайние пиксели повторяются
+// (extrude), а между картинками остаётся прозрачный зазор (padding), чтобы
+// при фильтрации и масштабировании соседи не просвечивали
+
+use std::collections::HashMap;
+
+use image::RgbaImage;
+use serde_json::{json, Value};
+
+use crate::error::MoonWalkError;
+use crate::rendering::texture::Texture;
+use crate::MoonWalk;
+
+/// Часть страницы атласа. Задаёт объекту текстуру и uv через mw.set_sprite
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasSprite {
+    /// Айди текстуры страницы
+    pub texture_id: u32,
+    /// Номер страницы атласа
+    pub page: usize,
+    /// x, y, ширина, высота в долях страницы (как в set_uv)
+    pub uv: [f32; 4],
+    /// x, y, ширина, высота в пикселях страницы
+    pub rect: [u32; 4],
+}
+
+/// Раскладка до загрузки в видеопамять: картинки страниц и места спрайтов
+pub struct AtlasLayout {
+    pub pages: Vec<RgbaImage>,
+    /// Номер страницы и x, y, ширина, высота в пикселях
+    pub sprites: HashMap<String, (usize, [u32; 4])>,
+}
+
+/// Строитель атласа. Картинки добавляются по именам, по этим же именам
+/// потом берутся спрайты
+pub struct TextureAtlas {
+    images: Vec<(String, RgbaImage)>,
+    max_size: u32,
+    padding: u32,
+    extrude: u32,
+}
+
+impl Default for TextureAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextureAtlas {
+    /// Страницы до 2048x2048, зазор 2 пикселя и 1 пиксель повтора краёв
+    pub fn new() -> Self {
+        Self {
+            images: Vec::new(),
+            max_size: 2048,
+            padding: 2,
+            extrude: 1,
+        }
+    }
+
+    /// Наибольшая ширина и высота страницы
+    pub fn max_size(mut self, size: u32) -> Self {
+        self.max_size = size.max(1);
+        self
+    }
+
+    /// Прозрачный зазор между картинками в пикселях
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// На сколько пикселей повторить крайние пиксели каждой картинки
+    pub fn extrude(mut self, extrude: u32) -> Self {
+        self.extrude = extrude;
+        self
+    }
+
+    /// Добавляет картинку. Картинка с тем же именем заменяется
+    pub fn add_image(&mut self, name: &str, image: RgbaImage) {
+        self.images.retain(|(existing, _)| existing != name);
+        self.images.push((name.to_string(), image));
+    }
+
+    /// Добавляет картинку из байт файла (png, jpeg и всё что понимает image)
+    pub fn add_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<(), MoonWalkError> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| MoonWalkError::TextureLoading(e.to_string()))?;
+
+        self.add_image(name, image.to_rgba8());
+        Ok(())
+    }
+
+    /// Добавляет картинку из файла. Файл читается через ResourceManager, так
+    /// что работают точки монтирования и assets на android
+    pub fn add_file(&mut self, mw: &MoonWalk, name: &str, path: &str) -> Result<(), MoonWalkError> {
+        let bytes = mw.resources.read_bytes(path)?;
+        self.add_bytes(name, &bytes)
+    }
+
+    /// Добавляет уже загруженную текстуру. Её пиксели скачиваются из
+    /// видеопамяти, сама текстура не удаляется
+    pub fn add_texture(&mut self, mw: &MoonWalk, name: &str, texture_id: u32) -> Result<(), MoonWalkError> {
+        let texture = mw.renderer.state.textures.get(&texture_id)
+            .ok_or_else(|| MoonWalkError::TextureLoading(format!("Texture {} not found", texture_id)))?;
+
+        self.add_image(name, texture.download(&mw.renderer.context)?);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    /// Раскладывает картинки по страницам. Ошибка если какая-то картинка
+    /// вместе с краями не помещается на страницу
+    pub fn pack(self) -> Result<AtlasLayout, MoonWalkError> {
+        let border = self.extrude * 2 + self.padding;
+
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|&i| {
+            let image = &self.images[i].1;
+            (std::cmp::Reverse(image.height()), std::cmp::Reverse(image.width()))
+        });
+
+        // Места картинок: страница, x и y ячейки
+        let mut places = vec![(0usize, 0u32, 0u32); self.images.len()];
+        let mut shelves: Vec<Shelves> = Vec::new();
+
+        for &i in &order {
+            let (name, image) = &self.images[i];
+            let (w, h) = (image.width() + border, image.height() + border);
+
+            // Зазор нужен только между картинками, у края страницы его нет
+            if w - self.padding > self.max_size || h - self.padding > self.max_size {
+                return Err(MoonWalkError::TextureLoading(format!(
+                    "Image {} ({}x{}) does not fit into a {}x{} atlas page",
+                    name, image.width(), image.height(), self.max_size, self.max_size
+                )));
+            }
+
+            let placed = shelves.iter_mut()
+                .enumerate()
+                .find_map(|(page, shelves)| Some((page, shelves.place(w, h, self.max_size + self.padding)?)));
+
+            places[i] = match placed {
+                Some((page, (x, y))) => (page, x, y),
+
+                None => {
+                    let mut page = Shelves::default();
+                    let (x, y) = page.place(w, h, self.max_size + self.padding)
+                        .expect("image fits into an empty page");
+
+                    shelves.push(page);
+                    (shelves.len() - 1, x, y)
+                }
+            };
+        }
+
+        let mut pages: Vec<RgbaImage> = shelves.iter()
+            .map(|page| {
+                let (w, h) = page.used(self.padding);
+                RgbaImage::new(w.max(1), h.max(1))
+            })
+            .collect();
+
+        let mut sprites = HashMap::new();
+
+        for ((name, image), (page, x, y)) in self.images.into_iter().zip(places) {
+            let (ix, iy) = (x + self.extrude, y + self.extrude);
+            blit_extruded(&mut pages[page], &image, ix, iy, self.extrude);
+            sprites.insert(name, (page, [ix, iy, image.width(), image.height()]));
+        }
+
+        Ok(AtlasLayout { pages, sprites })
+    }
+}
+
+impl AtlasLayout {
+    /// Загружает страницы в видеопамять
+    pub fn upload(self, mw: &mut MoonWalk) -> Result<PackedAtlas, MoonWalkError> {
+        let mut pages = Vec::with_capacity(self.pages.len());
+
+        for (i, image) in self.pages.iter().enumerate() {
+            let label = format!("Atlas page {}", i);
+            let texture = Texture::from_raw(&mw.renderer.context, image, image.width(), image.height(), &label)?;
+            pages.push((mw.renderer.register_texture(texture), image.width(), image.height()));
+        }
+
+        Ok(PackedAtlas::from_parts(pages, self.sprites))
+    }
+
+    /// Метаданные атласа в JSON. Файлы страниц называются как files[i]
+    pub fn to_json(&self, files: &[String]) -> Value {
+        let pages: Vec<Value> = self.pages.iter()
+            .zip(files)
+            .map(|(page, file)| json!({ "file": file, "width": page.width(), "height": page.height() }))
+            .collect();
+
+        let sprites: serde_json::Map<String, Value> = self.sprites.iter()
+            .map(|(name, (page, [x, y, w, h]))| {
+                (name.clone(), json!({ "page": page, "x": x, "y": y, "w": w, "h": h }))
+            })
+            .collect();
+
+        json!({ "pages": pages, "sprites": sprites })
+    }
+}
+
+/// Атлас в видеопамяти: текстуры страниц и спрайты по именам
+#[derive(Debug, Clone)]
+pub struct PackedAtlas {
+    // Айди текстуры, ширина и высота каждой страницы
+    pages: Vec<(u32, u32, u32)>,
+    sprites: HashMap<String, AtlasSprite>,
+}
+
+impl PackedAtlas {
+    fn from_parts(pages: Vec<(u32, u32, u32)>, rects: HashMap<String, (usize, [u32; 4])>) -> Self {
+        let sprites = rects.into_iter()
+            .map(|(name, (page, rect))| {
+                let (texture_id, width, height) = pages[page];
+                let uv = sprite_uv(rect, width, height);
+
+                (name, AtlasSprite { texture_id, page, uv, rect })
+            })
+            .collect();
+
+        Self { pages, sprites }
+    }
+
+    pub fn sprite(&self, name: &str) -> Option<AtlasSprite> {
+        self.sprites.get(name).copied()
+    }
+
+    pub fn sprites(&self) -> impl Iterator<Item = (&str, &AtlasSprite)> {
+        self.sprites.iter().map(|(name, sprite)| (name.as_str(), sprite))
+    }
+
+    /// Айди текстур страниц
+    pub fn pages(&self) -> Vec<u32> {
+        self.pages.iter().map(|(id, _, _)| *id).collect()
+    }
+
+    /// Сохраняет страницы в png рядом с файлом метаданных (имя_0.png,
+    /// имя_1.png...) и метаданные в JSON по пути path. Пишет в обычную
+    /// файловую систему
+    pub fn save(&self, mw: &MoonWalk, path: &str) -> Result<(), MoonWalkError> {
+        let target = std::path::Path::new(path);
+        let stem = target.file_stem().and_then(|s| s.to_str()).unwrap_or("atlas");
+        let dir = target.parent().unwrap_or(std::path::Path::new(""));
+
+        let mut images = Vec::with_capacity(self.pages.len());
+        let mut files = Vec::with_capacity(self.pages.len());
+
+        for (i, (id, _, _)) in self.pages.iter().enumerate() {
+            let texture = mw.renderer.state.textures.get(id)
+                .ok_or_else(|| MoonWalkError::TextureLoading(format!("Atlas page {} was removed", i)))?;
+
+            let image = texture.download(&mw.renderer.context)?;
+            let file = format!("{}_{}.png", stem, i);
+
+            image.save(dir.join(&file)).map_err(|e| MoonWalkError::IOError(e.to_string()))?;
+
+            images.push(image);
+            files.push(file);
+        }
+
+        let rects = self.sprites.iter()
+            .map(|(name, sprite)| (name.clone(), (sprite.page, sprite.rect)))
+            .collect();
+
+        let layout = AtlasLayout { pages: images, sprites: rects };
+        let data = serde_json::to_string_pretty(&layout.to_json(&files))
+            .map_err(|e| MoonWalkError::IOError(e.to_string()))?;
+
+        std::fs::write(target, data).map_err(|e| MoonWalkError::IOError(e.to_string()))
+    }
+
+    /// Загружает атлас сохранённый через save. Метаданные и страницы
+    /// читаются через ResourceManager, страницы ищутся рядом с метаданными
+    pub fn load(mw: &mut MoonWalk, path: &str) -> Result<Self, MoonWalkError> {
+        let bytes = mw.resources.read_bytes(path)?;
+        let data = String::from_utf8(bytes)
+            .map_err(|e| MoonWalkError::TextureLoading(format!("Invalid atlas metadata: {}", e)))?;
+
+        let (files, rects) = parse_atlas_json(&data)?;
+
+        let dir = match path.rfind(['/', '\\']) {
+            Some(i) => &path[..=i],
+            None => "",
+        };
+
+        let mut pages: Vec<(u32, u32, u32)> = Vec::with_capacity(files.len());
+
+        for (file, width, height) in files {
+            match mw.load_texture(&format!("{}{}", dir, file)) {
+                Ok(id) => pages.push((id, width, height)),
+
+                Err(e) => {
+                    for (id, _, _) in pages {
+                        mw.remove_texture(id);
+                    }
+
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(Self::from_parts(pages, rects))
+    }
+
+    /// Удаляет текстуры страниц
+    pub fn remove(self, mw: &mut MoonWalk) {
+        for (id, _, _) in self.pages {
+            mw.remove_texture(id);
+        }
+    }
+}
+
+impl TextureAtlas {
+    /// Раскладывает картинки и загружает страницы в видеопамять
+    pub fn build(self, mw: &mut MoonWalk) -> Result<PackedAtlas, MoonWalkError> {
+        self.pack()?.upload(mw)
+    }
+}
+
+/// Файл страницы атласа с её шириной и высотой
+pub type AtlasPage = (String, u32, u32);
+
+/// Номер страницы и прямоугольник x, y, w, h спрайта в пикселях
+pub type AtlasRect = (usize, [u32; 4]);
+
+/// Разбирает метаданные атласа: файлы страниц с их размерами и места спрайтов
+pub fn parse_atlas_json(data: &str) -> Result<(Vec<AtlasPage>, HashMap<String, AtlasRect>), MoonWalkError> {
+    let invalid = |what: &str| MoonWalkError::TextureLoading(format!("Invalid atlas metadata: {}", what));
+
+    let root: Value = serde_json::from_str(data)
+        .map_err(|e| MoonWalkError::TextureLoading(format!("Invalid atlas metadata: {}", e)))?;
+
+    let number = |value: &Value, key: &str| -> Result<u32, MoonWalkError> {
+        value.get(key)
+            .and_then(Value::as_u64)
+            .map(|n| n as u32)
+            .ok_or_else(|| invalid(key))
+    };
+
+    let mut pages = Vec::new();
+
+    for page in root.get("pages").and_then(Value::as_array).ok_or_else(|| invalid("pages"))? {
+        let file = page.get("file").and_then(Value::as_str).ok_or_else(|| invalid("file"))?;
+        pages.push((file.to_string(), number(page, "width")?, number(page, "height")?));
+    }
+
+    let mut sprites = HashMap::new();
+
+    for (name, sprite) in root.get("sprites").and_then(Value::as_object).ok_or_else(|| invalid("sprites"))? {
+        let page = number(sprite, "page")? as usize;
+
+        if page >= pages.len() {
+            return Err(invalid("page"));
+        }
+
+        let rect = [number(sprite, "x")?, number(sprite, "y")?, number(sprite, "w")?, number(sprite, "h")?];
+        sprites.insert(name.clone(), (page, rect));
+    }
+
+    Ok((pages, sprites))
+}
+
+/// uv спрайта из прямоугольника в пикселях и размера страницы
+pub(crate) fn sprite_uv(rect: [u32; 4], page_width: u32, page_height: u32) -> [f32; 4] {
+    let (pw, ph) = (page_width.max(1) as f32, page_height.max(1) as f32);
+
+    [
+        rect[0] as f32 / pw,
+        rect[1] as f32 / ph,
+        rect[2] as f32 / pw,
+        rect[3] as f32 / ph,
+    ]
+}
+
+// Полки одной страницы: y начала, высота и занятая ширина каждой
+#[derive(Default)]
+struct Shelves {
+    shelves: Vec<(u32, u32, u32)>,
+}
+
+impl Shelves {
+    // Ячейка w на h (вместе с зазором). limit это размер страницы плюс
+    // зазор, потому что у последней ячейки зазор выходит за край
+    fn place(&mut self, w: u32, h: u32, limit: u32) -> Option<(u32, u32)> {
+        // Картинки идут по убыванию высоты, так что подходит любая полка
+        // не ниже картинки
+        for (y, shelf_h, used) in &mut self.shelves {
+            if h <= *shelf_h && *used + w <= limit {
+                let x = *used;
+                *used += w;
+                return Some((x, *y));
+            }
+        }
+
+        let y = self.shelves.last().map_or(0, |(y, h, _)| y + h);
+
+        if y + h > limit || w > limit {
+            return None;
+        }
+
+        self.shelves.push((y, h, w));
+        Some((0, y))
+    }
+
+    // Занятый размер страницы без зазора после последних ячеек
+    fn used(&self, padding: u32) -> (u32, u32) {
+        let width = self.shelves.iter().map(|(_, _, used)| *used).max().unwrap_or(0);
+        let height = self.shelves.last().map_or(0, |(y, h, _)| y + h);
+
+        (width.saturating_sub(padding), height.saturating_sub(padding))
+    }
+}
+
+// Копирует картинку в страницу и повторяет её крайние пиксели на extrude
+// пикселей во все стороны (включая углы)
+fn blit_extruded(page: &mut RgbaImage, image: &RgbaImage, x: u32, y: u32, extrude: u32) {
+    let (w, h) = (image.width() as i64, image.height() as i64);
+    let e = extrude as i64;
+
+    if w == 0 || h == 0 {
+        return;
+    }
+
+    for dy in -e..h + e {
+        for dx in -e..w + e {
+            let source = image.get_pixel(dx.clamp(0, w - 1) as u32, dy.clamp(0, h - 1) as u32);
+            let (px, py) = (x as i64 + dx, y as i64 + dy);
+
+            if px >= 0 && py >= 0 && (px as u32) < page.width() && (py as u32) < page.height() {
+                page.put_pixel(px as u32, py as u32, *source);
+            }
+        }
+    }
+}
@@ -11,10 +11,16 @@ use crate::batching::shapes::uber::UberBatch;
 use crate::rendering::snapshot::ClippedSnapshot;
 use crate::rendering::state::GlobalUniform;
 use crate::rendering::texture::Texture;
+use crate::rendering::memory::TrackedBytes;
 use crate::textware::FontId;
+use crate::textware::{TextStyle, FontFeatures};
 use crate::MoonWalk;
 use crate::FontAsset;
 use crate::TextAlign;
+use crate::TextRenderMode;
+use crate::{Overflow, TextDirection, TextPathOptions};
+use crate::PathBuilder;
+use crate::FontStyle;
 
 pub struct RenderContainer {
     pub store: ObjectStore,
@@ -23,6 +29,9 @@ pub struct RenderContainer {
     pub target: Texture,
     pub width: u32,
     pub height: u32,
+
+    // Учёт цели рендера в memory_stats
+    _memory: TrackedBytes,
 }
 
 impl RenderContainer {
@@ -71,6 +80,7 @@ impl RenderContainer {
             store: ObjectStore::new(),
             batch: UberBatch::new(ctx),
             proj_bind_group,
+            _memory: TrackedBytes::new(target.memory_bytes()),
             target,
             width,
             height,
@@ -262,6 +272,8 @@ impl RenderContainer {
             TextAlign::Center => 1,
             TextAlign::Right => 2,
             TextAlign::Justified => 3,
+            TextAlign::Start => 4,
+            TextAlign::End => 5,
         };
         self.store.set_text_align(id, val);
     }
@@ -273,10 +285,170 @@ impl RenderContainer {
             1 => TextAlign::Center,
             2 => TextAlign::Right,
             3 => TextAlign::Justified,
+            4 => TextAlign::Start,
+            5 => TextAlign::End,
             _ => TextAlign::Left,
         }
     }
 
+    #[inline]
+    pub fn set_text_render_mode(&mut self, id: crate::objects::ObjectId, mode: TextRenderMode) {
+        self.store.set_text_render_mode(id, mode.to_u8());
+    }
+
+    #[inline]
+    pub fn get_text_render_mode(&self, id: crate::objects::ObjectId) -> TextRenderMode {
+        TextRenderMode::from_u8(self.store.get_text_render_mode(id))
+    }
+
+    #[inline]
+    pub fn set_text_outline(&mut self, id: crate::objects::ObjectId, width: f32, color: Vec4) {
+        let outline = if width > 0.0 { Some((width, color)) } else { None };
+        self.store.set_text_outline(id, outline);
+    }
+
+    #[inline]
+    pub fn set_text_shadow(&mut self, id: crate::objects::ObjectId, offset: Vec2, blur: f32, color: Vec4) {
+        self.store.set_text_shadow(id, Some((offset, blur, color)));
+    }
+
+    #[inline]
+    pub fn set_text_glow(&mut self, id: crate::objects::ObjectId, radius: f32, color: Vec4) {
+        let glow = if radius > 0.0 { Some((radius, color)) } else { None };
+        self.store.set_text_glow(id, glow);
+    }
+
+    #[inline]
+    pub fn reset_text_effects(&mut self, id: crate::objects::ObjectId) {
+        self.store.set_text_outline(id, None);
+        self.store.set_text_shadow(id, None);
+        self.store.set_text_glow(id, None);
+    }
+
+    #[inline]
+    pub fn set_text_overflow(&mut self, id: crate::objects::ObjectId, overflow: Overflow) {
+        self.store.set_text_overflow(id, overflow.to_u8());
+    }
+
+    #[inline]
+    pub fn get_text_overflow(&self, id: crate::objects::ObjectId) -> Overflow {
+        Overflow::from_u8(self.store.get_text_overflow(id))
+    }
+
+    #[inline]
+    pub fn set_text_direction(&mut self, id: crate::objects::ObjectId, direction: TextDirection) {
+        self.store.set_text_direction(id, direction.to_u8());
+    }
+
+    #[inline]
+    pub fn get_text_direction(&self, id: crate::objects::ObjectId) -> TextDirection {
+        TextDirection::from_u8(self.store.get_text_direction(id))
+    }
+
+    #[inline]
+    pub fn set_max_lines(&mut self, id: crate::objects::ObjectId, max_lines: usize) {
+        self.store.set_text_max_lines(id, max_lines as u32);
+    }
+
+    #[inline]
+    pub fn get_max_lines(&self, id: crate::objects::ObjectId) -> usize {
+        self.store.get_text_max_lines(id) as usize
+    }
+
+    fn update_text_style(&mut self, id: ObjectId, update: impl FnOnce(&mut TextStyle)) {
+        let mut style = self.store.get_text_style(id);
+        update(&mut style);
+        self.store.set_text_style(id, style);
+    }
+
+    #[inline]
+    pub fn set_line_height(&mut self, id: crate::objects::ObjectId, line_height: f32) {
+        self.update_text_style(id, |style| style.line_height = line_height.max(0.0));
+    }
+
+    #[inline]
+    pub fn get_line_height(&self, id: crate::objects::ObjectId) -> f32 {
+        self.store.get_text_style(id).line_height(self.store.get_font_size(id))
+    }
+
+    #[inline]
+    pub fn set_letter_spacing(&mut self, id: crate::objects::ObjectId, spacing: f32) {
+        self.update_text_style(id, |style| style.letter_spacing = spacing);
+    }
+
+    #[inline]
+    pub fn set_font_weight(&mut self, id: crate::objects::ObjectId, weight: u16) {
+        self.update_text_style(id, |style| style.weight = weight.clamp(1, 1000));
+    }
+
+    #[inline]
+    pub fn set_font_style(&mut self, id: crate::objects::ObjectId, font_style: FontStyle) {
+        self.update_text_style(id, |style| style.style = font_style.to_u8());
+    }
+
+    /// Как MoonWalk::set_font_features, работает только tnum
+    pub fn set_font_features(&mut self, id: crate::objects::ObjectId, features: &[&str]) {
+        let features = FontFeatures::parse(features);
+        self.update_text_style(id, |style| style.features = features);
+    }
+
+    pub fn set_font_variations(&mut self, id: crate::objects::ObjectId, variations: &[(&str, f32)]) {
+        self.update_text_style(id, |style| {
+            for &(axis, value) in variations {
+                style.set_variation(axis, value);
+            }
+        });
+    }
+
+    /// Текст вдоль пути, см. MoonWalk::set_text_path
+    pub fn set_text_path(&mut self, id: ObjectId, path: PathBuilder, options: TextPathOptions) {
+        let mut text_path = path.into_text_path();
+        text_path.start_offset = options.start_offset;
+        text_path.baseline_offset = options.baseline_offset;
+
+        self.store.set_text_path(id, Some(text_path));
+    }
+
+    pub fn set_text_path_svg(&mut self, id: ObjectId, data: &str, options: TextPathOptions) -> Result<(), String> {
+        let mut path = PathBuilder::new();
+        crate::path::svg::parse_svg_path(&mut path, data)?;
+
+        self.set_text_path(id, path, options);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_text_path_options(&mut self, id: ObjectId, options: TextPathOptions) {
+        self.store.set_text_path_offsets(id, options.start_offset, options.baseline_offset);
+    }
+
+    #[inline]
+    pub fn clear_text_path(&mut self, id: ObjectId) {
+        self.store.set_text_path(id, None);
+    }
+
+    #[inline]
+    pub fn get_text_path_length(&self, id: ObjectId) -> f32 {
+        self.store.get_text_path(id).map_or(0.0, |path| path.length())
+    }
+
+    /// Размер текстового объекта контейнера с учётом обрезки и многоточия
+    pub fn measure_text_object(&mut self, mw: &mut MoonWalk, id: ObjectId) -> Vec2 {
+        let idx = id.index();
+
+        if !self.store.is_alive(id) || idx >= self.store.text_contents.len() {
+            return Vec2::ZERO;
+        }
+
+        let key = idx as u64;
+        let text_engine = &mut mw.renderer.text_engine;
+
+        text_engine.process_text(key, &self.store.text_contents[idx], self.store.text_params(idx));
+
+        let (w, h) = text_engine.measure_buffer(key);
+        Vec2::new(w, h)
+    }
+
     pub fn measure_text(&mut self, mw: &mut MoonWalk, text: &str, font: FontAsset, size: f32, max_width: f32) -> Vec2 {
         let (w, h) = mw.renderer.text_engine.measure_text(
             text, 
@@ -297,14 +469,18 @@ impl RenderContainer {
     }
 
     pub fn draw(&mut self, mw: &mut MoonWalk, clear_color: Option<Vec4>) {
+        // Выгруженные бюджетом текстуры загружаются обратно до рисования
+        mw.restore_textures(&self.store);
+
         let renderer = &mut mw.renderer;
         let ctx = &renderer.context;
         let text_engine = &mut renderer.text_engine;
         
-        self.batch.prepare(ctx, &self.store, text_engine);
+        self.batch.prepare(ctx, &self.store, text_engine, &[]);
 
         text_engine.prepare(&ctx.queue);
         let atlas_bg = text_engine.get_bind_group();
+        let sdf_bg = text_engine.get_sdf_bind_group();
         
         let wgpu_clear_color = clear_color.map(|c| wgpu::Color {
             r: c.x as f64,
@@ -331,6 +507,7 @@ impl RenderContainer {
                     &renderer.state.white_texture, 
                     &renderer.state.textures,
                     Some(&atlas_bg),
+                    Some(&sdf_bg),
                 );
             }
         }
@@ -394,6 +571,7 @@ impl RenderContainer {
     }
 
     pub fn update_snapshot(&mut self, mw: &mut MoonWalk, x: u32, y: u32, w: u32, h: u32, id: u32) {
+        mw.keep_texture(id);
         let renderer = &mut mw.renderer;
         
         let mut snapshot_region = ClippedSnapshot::new(
@@ -406,7 +584,9 @@ impl RenderContainer {
             self.height as f32
         ));
 
-        let target_tex = renderer.state.textures.get(&id).unwrap();
+        let Some(target_tex) = renderer.state.textures.get(&id) else {
+            return;
+        };
         
         let mut encoder = renderer.context.create_encoder();
         encoder.copy_texture_to_texture(
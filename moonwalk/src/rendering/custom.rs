@@ -4,15 +4,20 @@
 #![allow(dead_code)]
 
 use std::ops::Range;
+use std::sync::Arc;
 use glam::Vec4;
+use parking_lot::RwLock;
 
 use crate::gpu::Context;
 use crate::rendering::texture::Texture;
+use crate::rendering::memory::TrackedBytes;
 use crate::MoonWalk;
 
+/// Собранный пайплайн. Клоны общие: если пайплайн пересобран (hot-reload
+/// шейдера), новый вариант сразу виден через все клоны
 #[derive(Clone)]
 pub struct CustomPipeline {
-    pub(crate) raw: wgpu::RenderPipeline,
+    pub(crate) raw: Arc<RwLock<wgpu::RenderPipeline>>,
 }
 
 #[derive(Clone)]
@@ -68,7 +73,7 @@ pub struct ActiveMoonRenderPass<'a> {
 
 impl<'a> ActiveMoonRenderPass<'a> {
     pub fn set_pipeline(&mut self, pipeline: &'a CustomPipeline) {
-        self.pass.set_pipeline(&pipeline.raw);
+        self.pass.set_pipeline(&pipeline.raw.read());
     }
 
     pub fn set_bind_group(&mut self, index: u32, bg: &'a MoonBindGroup) {
@@ -119,6 +124,9 @@ pub struct CustomPaint {
     current_bind_group_0: Option<MoonBindGroup>,
     current_vertex_buffer: Option<MoonBuffer>,
     current_index_buffer: Option<MoonBuffer>,
+
+    // Учёт цели рендера и глубины в memory_stats
+    _memory: TrackedBytes,
 }
 
 impl CustomPaint {
@@ -133,6 +141,7 @@ impl CustomPaint {
         let depth = Texture::create_depth_texture(ctx, width, height, &depth_label);
 
         Self {
+            _memory: TrackedBytes::new(target.memory_bytes() + depth.memory_bytes()),
             target,
             depth,
             width,
@@ -288,6 +297,7 @@ impl CustomPaint {
     }
 
     fn copy_to_texture(&self, mw: &mut MoonWalk, target_id: u32) {
+        mw.keep_texture(target_id);
         let renderer = &mut mw.renderer;
         if let Some(target_tex) = renderer.state.textures.get(&target_id) {
             let mut encoder = renderer.context.create_encoder();
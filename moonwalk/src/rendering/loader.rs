@@ -0,0 +1,233 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Фоновая загрузка текстур. Файл читается и декодируется в пуле потоков
+// rayon, а в видеопамять картинка загружается уже в потоке рендера (при
+// следующем render_frame). До этого под айди текстуры лежит белая заглушка
+// 1x1, так что объекты с этой текстурой можно создавать сразу
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::error::MoonWalkError;
+use crate::rendering::texture::TextureOptions;
+
+/// Чтение файла которое можно выполнить в другом потоке
+pub(crate) type DetachedRead = Box<dyn FnOnce() -> Result<Vec<u8>, MoonWalkError> + Send>;
+
+/// Функция которая вызывается в потоке рендера когда текстура загружена
+/// (айди) или не загрузилась (ошибка)
+pub type TextureCallback = Box<dyn FnOnce(Result<u32, MoonWalkError>) + Send>;
+
+/// Состояние фоновой загрузки текстуры
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextureLoadState {
+    Loading,
+    Ready,
+    /// Текст ошибки. Под айди так и остаётся заглушка
+    Failed(String),
+}
+
+/// Ручка фоновой загрузки. Айди текстуры известен сразу и не меняется,
+/// состояние обновляется когда картинка загружена в видеопамять. Клоны
+/// общие
+#[derive(Debug, Clone)]
+pub struct TextureHandle {
+    id: u32,
+    state: Arc<Mutex<TextureLoadState>>,
+}
+
+impl TextureHandle {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn state(&self) -> TextureLoadState {
+        self.state.lock().clone()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        *self.state.lock() == TextureLoadState::Ready
+    }
+
+    /// Загрузка закончилась, успешно или нет
+    pub fn is_finished(&self) -> bool {
+        *self.state.lock() != TextureLoadState::Loading
+    }
+
+    pub fn error(&self) -> Option<String> {
+        match &*self.state.lock() {
+            TextureLoadState::Failed(error) => Some(error.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Декодированная картинка или ошибка из пула потоков
+pub(crate) struct DecodedTexture {
+    pub id: u32,
+    pub label: String,
+    pub result: Result<image::RgbaImage, MoonWalkError>,
+}
+
+/// Состояние ручки и колбэк одной загрузки
+pub(crate) struct LoadNotifier {
+    state: Arc<Mutex<TextureLoadState>>,
+    callback: Option<TextureCallback>,
+}
+
+impl LoadNotifier {
+    /// Обновляет состояние ручки и вызывает колбэк
+    pub fn notify(self, result: Result<u32, MoonWalkError>) {
+        *self.state.lock() = match &result {
+            Ok(_) => TextureLoadState::Ready,
+            Err(e) => TextureLoadState::Failed(e.to_string()),
+        };
+
+        if let Some(callback) = self.callback {
+            callback(result);
+        }
+    }
+}
+
+// Загрузка которая ещё читается или декодируется
+struct PendingLoad {
+    path: String,
+    options: TextureOptions,
+    notifier: LoadNotifier,
+}
+
+/// Загрузка которая закончила декодирование и ждёт загрузки в видеопамять
+pub(crate) struct FinishedLoad {
+    pub decoded: DecodedTexture,
+    pub path: String,
+    pub options: TextureOptions,
+    pub notifier: LoadNotifier,
+}
+
+#[derive(Default)]
+pub(crate) struct BackgroundLoader {
+    // Сюда потоки пула складывают результаты
+    decoded: Arc<Mutex<Vec<DecodedTexture>>>,
+
+    // Загрузки по айди текстур
+    pending: HashMap<u32, PendingLoad>,
+}
+
+impl BackgroundLoader {
+    /// Запускает чтение и декодирование в пуле потоков
+    pub fn start(
+        &mut self,
+        id: u32,
+        path: &str,
+        options: TextureOptions,
+        read: DetachedRead,
+        callback: Option<TextureCallback>
+    ) -> TextureHandle {
+        let state = Arc::new(Mutex::new(TextureLoadState::Loading));
+        let decoded = self.decoded.clone();
+
+        let label = std::path::Path::new(path).file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Unknown Texture")
+            .to_string();
+
+        let notifier = LoadNotifier { state: state.clone(), callback };
+        self.pending.insert(id, PendingLoad { path: path.to_string(), options, notifier });
+
+        rayon::spawn(move || {
+            let result = read().and_then(|bytes| {
+                image::load_from_memory(&bytes)
+                    .map(|img| img.to_rgba8())
+                    .map_err(|e| MoonWalkError::TextureLoading(e.to_string()))
+            });
+
+            decoded.lock().push(DecodedTexture { id, label, result });
+        });
+
+        TextureHandle { id, state }
+    }
+
+    /// Загрузки которые закончили декодирование
+    pub fn take_finished(&mut self) -> Vec<FinishedLoad> {
+        let decoded = std::mem::take(&mut *self.decoded.lock());
+
+        decoded.into_iter()
+            .filter_map(|decoded| {
+                let PendingLoad { path, options, notifier } = self.pending.remove(&decoded.id)?;
+                Some(FinishedLoad { decoded, path, options, notifier })
+            })
+            .collect()
+    }
+
+    /// Сколько текстур ещё читается или декодируется
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[test]
+fn background_loader_test() {
+    let mut png = Vec::new();
+    image::RgbaImage::from_pixel(2, 3, image::Rgba([10, 20, 30, 255]))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+
+    let called = Arc::new(Mutex::new(Vec::new()));
+    let calls = called.clone();
+
+    let mut loader = BackgroundLoader::default();
+    let options = TextureOptions::default().mipmaps(true);
+
+    let good = loader.start(1, "images/good.png", options, Box::new(move || Ok(png)), None);
+    let bad = loader.start(2, "bad.png", TextureOptions::default(), Box::new(|| Ok(vec![1, 2, 3])), Some(Box::new(move |result| {
+        calls.lock().push(result.is_ok());
+    })));
+
+    assert_eq!(loader.pending(), 2);
+    assert_eq!(good.id(), 1);
+    assert_eq!(good.state(), TextureLoadState::Loading);
+    assert!(!good.is_finished() && !bad.is_finished());
+
+    let mut finished = Vec::new();
+    let start = std::time::Instant::now();
+
+    while finished.len() < 2 && start.elapsed() < std::time::Duration::from_secs(10) {
+        finished.extend(loader.take_finished());
+        std::thread::yield_now();
+    }
+
+    assert_eq!(finished.len(), 2);
+    assert_eq!(loader.pending(), 0);
+    finished.sort_by_key(|load| load.decoded.id);
+
+    // Пока картинка не загружена в видеопамять ручка остаётся в Loading
+    assert_eq!(good.state(), TextureLoadState::Loading);
+
+    let mut finished = finished.into_iter();
+    let load = finished.next().unwrap();
+
+    assert_eq!(load.decoded.label, "good.png");
+    assert_eq!(load.path, "images/good.png");
+    assert_eq!(load.options, options);
+    assert_eq!(load.decoded.result.as_ref().unwrap().dimensions(), (2, 3));
+
+    load.notifier.notify(Ok(1));
+    assert!(good.is_ready() && good.is_finished());
+    assert_eq!(good.error(), None);
+
+    // Битый файл декодируется с ошибкой, её получают и ручка и колбэк
+    let load = finished.next().unwrap();
+    assert!(load.decoded.result.is_err());
+
+    load.notifier.notify(Err(MoonWalkError::TextureLoading("broken".into())));
+    assert!(bad.is_finished() && !bad.is_ready());
+    assert!(matches!(bad.state(), TextureLoadState::Failed(_)));
+    assert!(bad.error().unwrap().contains("broken"));
+    assert_eq!(*called.lock(), [false]);
+
+    // Клоны ручки общие
+    assert!(good.clone().is_ready());
+}
@@ -0,0 +1,266 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Учёт видеопамяти под текстуры и бюджет. Размер считается по описанию
+// текстуры (формат, размер, мипмапы), реальный расход у драйвера может быть
+// чуть больше из-за выравнивания. Текстуры загруженные из файлов помнят
+// путь и параметры, поэтому при превышении бюджета давно не рисовавшиеся
+// из них выгружаются и загружаются снова когда объект с ними рисуется
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::objects::store::ObjectStore;
+use crate::rendering::texture::TextureOptions;
+
+// Цели рендера которыми владеет приложение (контейнеры, CustomPaint).
+// Счётчик общий на процесс
+static RENDER_TARGET_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Сколько байт видеопамяти занимает текстура со всеми уровнями детализации
+pub(crate) fn texture_bytes(texture: &wgpu::Texture) -> u64 {
+    let format = texture.format();
+    let (block_width, block_height) = format.block_dimensions();
+
+    // У форматов глубины нет размера для копирования, Depth24Plus и
+    // Depth32Float занимают 4 байта на пиксель
+    let block_size = format.block_copy_size(None).unwrap_or(4) as u64;
+
+    let layers = texture.depth_or_array_layers() as u64;
+    let samples = texture.sample_count() as u64;
+
+    (0..texture.mip_level_count())
+        .map(|level| {
+            let width = (texture.width() >> level).max(1).div_ceil(block_width) as u64;
+            let height = (texture.height() >> level).max(1).div_ceil(block_height) as u64;
+
+            width * height * block_size
+        })
+        .sum::<u64>() * layers * samples
+}
+
+/// Байты цели рендера которой владеет приложение. Пока значение живо они
+/// учитываются в MemoryStats::render_targets
+pub(crate) struct TrackedBytes(u64);
+
+impl TrackedBytes {
+    pub fn new(bytes: u64) -> Self {
+        RENDER_TARGET_BYTES.fetch_add(bytes, Ordering::Relaxed);
+        Self(bytes)
+    }
+
+    pub fn total() -> u64 {
+        RENDER_TARGET_BYTES.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for TrackedBytes {
+    fn drop(&mut self) {
+        RENDER_TARGET_BYTES.fetch_sub(self.0, Ordering::Relaxed);
+    }
+}
+
+/// Расход видеопамяти движком в байтах, возвращается из mw.memory_stats()
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Зарегистрированные текстуры (загруженные, снапшоты, svg, кадры анимаций)
+    pub textures: u64,
+    pub texture_count: usize,
+
+    /// Сколько текстур сейчас выгружено бюджетом
+    pub evicted_textures: usize,
+
+    /// Буфер кадра, контейнеры и CustomPaint
+    pub render_targets: u64,
+
+    /// Промежуточная текстура фильтров
+    pub filters: u64,
+
+    /// Атласы глифов обычного и sdf текста
+    pub glyph_atlases: u64,
+
+    /// Промежуточные буферы update_texture_region
+    pub staging: u64,
+
+    pub budget: Option<u64>,
+
+    /// Сколько раз текстуры выгружались и загружались обратно
+    pub evictions: u64,
+    pub reloads: u64,
+}
+
+impl MemoryStats {
+    /// Всё вместе, с этим числом сравнивается бюджет
+    pub fn total(&self) -> u64 {
+        self.textures + self.render_targets + self.filters + self.glyph_atlases + self.staging
+    }
+}
+
+// Откуда текстуру можно загрузить снова
+pub(crate) struct TextureSource {
+    pub path: String,
+    pub options: TextureOptions,
+}
+
+// Бюджет текстур и всё что нужно для выгрузки по давности использования
+#[derive(Default)]
+pub(crate) struct TextureBudget {
+    pub limit: Option<u64>,
+
+    // Номер кадра, растёт после каждого render_frame
+    frame: u64,
+    last_used: HashMap<u32, u64>,
+
+    sources: HashMap<u32, TextureSource>,
+
+    // Выгруженные текстуры и их размер, чтобы get_texture_size не менялся
+    evicted: HashMap<u32, [u32; 2]>,
+
+    pub evictions: u64,
+    pub reloads: u64,
+}
+
+impl TextureBudget {
+    /// Запоминает файл текстуры, такую текстуру можно выгрузить
+    pub fn set_source(&mut self, id: u32, path: &str, options: TextureOptions) {
+        self.sources.insert(id, TextureSource { path: path.to_string(), options });
+    }
+
+    /// Текстура больше не совпадает с файлом или удалена
+    pub fn forget(&mut self, id: u32) {
+        self.sources.remove(&id);
+        self.last_used.remove(&id);
+        self.evicted.remove(&id);
+    }
+
+    pub fn source(&self, id: u32) -> Option<&TextureSource> {
+        self.sources.get(&id)
+    }
+
+    pub fn is_evicted(&self, id: u32) -> bool {
+        self.evicted.contains_key(&id)
+    }
+
+    pub fn evicted_size(&self, id: u32) -> Option<[u32; 2]> {
+        self.evicted.get(&id).copied()
+    }
+
+    pub fn evicted_count(&self) -> usize {
+        self.evicted.len()
+    }
+
+    pub fn mark_evicted(&mut self, id: u32, size: [u32; 2]) {
+        self.evicted.insert(id, size);
+        self.evictions += 1;
+    }
+
+    pub fn mark_reloaded(&mut self, id: u32) {
+        self.evicted.remove(&id);
+        self.reloads += 1;
+    }
+
+    pub fn touch(&mut self, id: u32) {
+        self.last_used.insert(id, self.frame);
+    }
+
+    pub fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Загруженные из файлов текстуры которые не рисовались в этом кадре,
+    /// самые давние первыми. Ни разу не рисовавшиеся идут в самом начале
+    pub fn candidates(&self) -> Vec<u32> {
+        let mut candidates: Vec<(Option<u64>, u32)> = self.sources.keys()
+            .filter(|id| !self.evicted.contains_key(id))
+            .map(|id| (self.last_used.get(id).copied(), *id))
+            .filter(|(used, _)| used.is_none_or(|frame| frame < self.frame))
+            .collect();
+
+        candidates.sort_unstable();
+        candidates.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+/// Айди текстур которые рисуют живые объекты хранилища
+pub(crate) fn used_textures(store: &ObjectStore) -> HashSet<u32> {
+    let mut used = HashSet::new();
+
+    for (idx, alive) in store.alive.iter().enumerate() {
+        if !alive {
+            continue;
+        }
+
+        if store.texture_ids[idx] != 0 {
+            used.insert(store.texture_ids[idx]);
+        }
+
+        if let Some(Some(shape)) = store.path_shapes.get(idx) {
+            used.extend(shape.layers.iter().filter_map(|(_, paint)| paint.texture_id()));
+        }
+    }
+
+    used
+}
+
+#[test]
+fn texture_budget_test() {
+    let mut budget = TextureBudget { limit: Some(250), ..Default::default() };
+
+    for id in 1..=4 {
+        budget.set_source(id, &format!("{}.png", id), TextureOptions::default());
+    }
+
+    // Кадр 0: рисуются все кроме 4, кадр 1: только 2 и 3, кадр 2: только 3
+    for id in 1..=3 {
+        budget.touch(id);
+    }
+
+    budget.next_frame();
+    budget.touch(2);
+    budget.touch(3);
+
+    budget.next_frame();
+    budget.touch(3);
+
+    // Ни разу не рисовавшаяся идёт первой, потом самые давние. То что
+    // рисуется в этом кадре не выгружается
+    assert_eq!(budget.candidates(), [4, 1, 2]);
+
+    // Изменённая на видеокарте текстура (keep_texture) не выгружается никогда
+    budget.forget(2);
+    assert!(budget.source(2).is_none());
+    assert_eq!(budget.candidates(), [4, 1]);
+
+    // Выгрузка как в evict_textures: по 100 байт на текстуру, пока расход
+    // больше бюджета
+    let mut total = 400;
+
+    for id in budget.candidates() {
+        if total <= budget.limit.unwrap() {
+            break;
+        }
+
+        total -= 100;
+        budget.mark_evicted(id, [10, 10]);
+    }
+
+    assert_eq!(total, 200);
+    assert!(budget.is_evicted(4) && budget.is_evicted(1));
+    assert!(!budget.is_evicted(2) && !budget.is_evicted(3));
+    assert_eq!(budget.evicted_size(1), Some([10, 10]));
+    assert_eq!((budget.evicted_count(), budget.evictions), (2, 2));
+
+    // Выгруженные больше не кандидаты, а путь для загрузки остаётся
+    budget.next_frame();
+    assert_eq!(budget.candidates(), [3]);
+    assert_eq!(budget.source(1).unwrap().path, "1.png");
+
+    // После загрузки текстура снова может быть выгружена
+    budget.mark_reloaded(1);
+    budget.touch(1);
+    budget.next_frame();
+
+    assert_eq!(budget.reloads, 1);
+    assert_eq!(budget.evicted_size(1), None);
+    assert_eq!(budget.candidates(), [3, 1]);
+}
@@ -0,0 +1,88 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Генерация мипмапов на видеокарте. Каждый уровень рисуется полноэкранным
+// треугольником из предыдущего, так что картинка не возвращается в озу.
+// Пайплайн один на формат и лежит в общем кэше MoonPipeline
+
+use crate::gpu::{Buffer, Context};
+use crate::r#abstract::*;
+use crate::error::MoonWalkError;
+use crate::filters::uniforms::DummyVertex;
+
+/// Заполняет уровни 1.. текстуры из уровня 0. Текстура должна быть создана
+/// с RENDER_ATTACHMENT и TEXTURE_BINDING
+pub(crate) fn generate_mipmaps(ctx: &Context, texture: &wgpu::Texture) -> Result<(), MoonWalkError> {
+    let format = texture.format();
+
+    let layout = BindGroup::new()
+        .add_texture(0, TextureType::Float)
+        .add_sampler(1, SamplerType::Linear)
+        .build(ctx)?;
+
+    let pipeline = MoonPipeline::new(include_str!("../shaders/mipmap.wgsl"))
+        .vertex_shader("vs_main")
+        .fragment_shader("fs_main")
+        .add_vertex_layout(VertexLayout::new().stride(0).step_mode(StepMode::Vertex))
+        .blend(BlendMode::None)
+        .label("mipmap_blit")
+        .build(ctx, format, &[&layout])?
+        .pipeline
+        .raw;
+
+    let sampler = ctx.device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("Mipmap sampler"),
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+
+    // Пайплайн ждёт вершинный буфер, хотя вершины берутся из vertex_index
+    let dummy_vbo = Buffer::vertex(ctx, &[DummyVertex { _dummy: 0.0 }]);
+
+    let views: Vec<wgpu::TextureView> = (0..texture.mip_level_count())
+        .map(|level| texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Mipmap level"),
+            base_mip_level: level,
+            mip_level_count: Some(1),
+            ..Default::default()
+        }))
+        .collect();
+
+    let mut encoder = ctx.create_encoder();
+
+    for pair in views.windows(2) {
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Mipmap bind group"),
+            layout: &layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&pair[0]) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&sampler) },
+            ],
+        });
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Mipmap Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &pair[1],
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.set_vertex_buffer(0, dummy_vbo.raw.slice(..));
+        pass.draw(0..3, 0..1);
+    }
+
+    ctx.submit(encoder);
+
+    Ok(())
+}
@@ -6,6 +6,12 @@ pub mod renderer;
 pub mod vertex;
 pub mod pipeline;
 pub mod texture;
+pub mod mipmap;
+pub mod staging;
+pub mod memory;
+pub mod loader;
+pub mod atlas;
+pub mod animated;
 pub mod container;
 pub mod custom;
 pub mod video;
@@ -13,6 +13,7 @@ use crate::rendering::snapshot::ClippedSnapshot;
 use crate::rendering::state::RenderState;
 use crate::objects::ObjectId;
 use crate::filters::FilterSystem;
+use crate::filters::chain::FilterChain;
 use crate::path::VectorSystem;
 use crate::debug_println;
 use crate::painting::PaintingSystem;
@@ -325,6 +326,18 @@ impl MoonRenderer {
         }
     }
 
+    pub fn apply_filter_chain(&mut self, chain: &FilterChain, source_id: u32, target_id: u32) -> Result<(), MoonWalkError> {
+        let textures = &self.state.textures;
+
+        let source = textures.get(&source_id)
+            .ok_or_else(|| MoonWalkError::FilterError(format!("Texture {} not found", source_id)))?;
+
+        let target = textures.get(&target_id)
+            .ok_or_else(|| MoonWalkError::FilterError(format!("Texture {} not found", target_id)))?;
+
+        self.filters.apply_chain(&self.context, chain, source, textures, target)
+    }
+
     /// Прокси методы
 
     #[inline]
@@ -372,6 +385,11 @@ impl MoonRenderer {
         self.state.store.config_effect_data(id, effect_data);
     }
 
+    /// Размер буфера кадра в байтах
+    pub(crate) fn offscreen_bytes(&self) -> u64 {
+        self.offscreen.as_ref().map_or(0, Texture::memory_bytes)
+    }
+
     #[inline]
     pub fn register_texture(&mut self, texture: Texture) -> u32 {
         self.state.add_texture(texture)
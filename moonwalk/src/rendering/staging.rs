@@ -0,0 +1,282 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Кольцо промежуточных буферов для частых загрузок в текстуры (камера,
+// процедурный шум, миникарта каждый кадр). Буферы создаются отображёнными в
+// память, пиксели пишутся прямо в них, после копирования в текстуру буфер
+// снова отображается и возвращается в кольцо. Так повторные загрузки того же
+// размера не выделяют память ни на cpu ни на gpu
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::gpu::Context;
+use crate::error::MoonWalkError;
+
+// Минимальный размер буфера, в него помещается RGBA8 кадр 512x512
+const MIN_CHUNK_SIZE: u64 = 1024 * 1024;
+
+// Больше буферов не создаётся, следующая загрузка ждёт пока видеокарта
+// вернёт один из них
+const MAX_CHUNKS: usize = 8;
+
+struct Chunk {
+    buffer: wgpu::Buffer,
+    size: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct StagingRing {
+    chunks: Vec<Chunk>,
+
+    // Отображённые буферы готовые к записи
+    free: Vec<usize>,
+
+    // Сюда колбэки map_async складывают вернувшиеся буферы и получилось ли
+    // их отобразить
+    returned: Arc<Mutex<Vec<(usize, bool)>>>,
+}
+
+impl StagingRing {
+    /// Копирует плотно упакованные строки data в прямоугольник текстуры.
+    /// Размер пикселя берётся из формата текстуры
+    pub fn upload(
+        &mut self,
+        ctx: &Context,
+        texture: &wgpu::Texture,
+        origin: [u32; 2],
+        size: [u32; 2],
+        data: &[u8],
+    ) -> Result<(), MoonWalkError> {
+        let [x, y] = origin;
+        let [width, height] = size;
+
+        let bytes_per_pixel = texture.format().block_copy_size(None)
+            .ok_or_else(|| MoonWalkError::TextureLoading(format!("Can't upload to {:?} texture", texture.format())))?;
+
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        if x.checked_add(width).is_none_or(|right| right > texture.width())
+            || y.checked_add(height).is_none_or(|bottom| bottom > texture.height())
+        {
+            return Err(MoonWalkError::TextureLoading(format!(
+                "Region {}x{} at ({}, {}) is outside of {}x{} texture",
+                width, height, x, y, texture.width(), texture.height()
+            )));
+        }
+
+        let row = (width * bytes_per_pixel) as usize;
+
+        if data.len() < row * height as usize {
+            return Err(MoonWalkError::TextureLoading(format!(
+                "Region {}x{} needs {} bytes, got {}", width, height, row * height as usize, data.len()
+            )));
+        }
+
+        // Строки в буфере для копирования выравниваются по 256 байт
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+        let padded_row = row.div_ceil(align) * align;
+        let needed = (padded_row * height as usize) as u64;
+
+        let index = self.acquire(ctx, needed);
+        let chunk = &self.chunks[index];
+
+        {
+            let mut mapped = chunk.buffer.slice(..needed).get_mapped_range_mut();
+
+            for (dst, src) in mapped.chunks_mut(padded_row).zip(data.chunks(row).take(height as usize)) {
+                dst[..row].copy_from_slice(src);
+            }
+        }
+
+        chunk.buffer.unmap();
+
+        let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Texture upload encoder"),
+        });
+
+        encoder.copy_buffer_to_texture(
+            wgpu::TexelCopyBufferInfo {
+                buffer: &chunk.buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row as u32),
+                    rows_per_image: Some(height),
+                },
+            },
+
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        ctx.submit(encoder);
+
+        // Буфер вернётся в кольцо когда видеокарта закончит копирование.
+        // Неотобразившийся буфер тоже возвращается, его пересоздаст
+        // collect_returned, иначе кольцо потеряло бы место
+        let returned = self.returned.clone();
+
+        chunk.buffer.slice(..).map_async(wgpu::MapMode::Write, move |result| {
+            returned.lock().push((index, result.is_ok()));
+        });
+
+        Ok(())
+    }
+
+    /// Сколько байт занимают все буферы кольца
+    pub fn allocated(&self) -> u64 {
+        self.chunks.iter().map(|chunk| chunk.size).sum()
+    }
+
+    // Свободный буфер размером не меньше size
+    fn acquire(&mut self, ctx: &Context, size: u64) -> usize {
+        ctx.device.poll(wgpu::Maintain::Poll);
+        self.collect_returned(ctx);
+
+        if let Some(index) = self.take_free(size) {
+            return index;
+        }
+
+        if self.chunks.len() < MAX_CHUNKS {
+            self.chunks.push(create_chunk(ctx, size));
+            return self.chunks.len() - 1;
+        }
+
+        // Кольцо заполнено. После Wait колбэки map_async всех буферов в
+        // работе уже вызваны, так что хотя бы один буфер свободен
+        loop {
+            ctx.device.poll(wgpu::Maintain::Wait);
+            self.collect_returned(ctx);
+
+            if !self.free.is_empty() {
+                break;
+            }
+        }
+
+        if let Some(index) = self.take_free(size) {
+            return index;
+        }
+
+        // Все свободные буферы малы, самый маленький заменяется
+        let position = (0..self.free.len())
+            .min_by_key(|i| self.chunks[self.free[*i]].size)
+            .unwrap_or_default();
+
+        let index = self.free.swap_remove(position);
+        self.chunks[index] = create_chunk(ctx, size);
+        index
+    }
+
+    // Самый маленький из подходящих свободных буферов
+    fn take_free(&mut self, size: u64) -> Option<usize> {
+        let position = (0..self.free.len())
+            .filter(|i| self.chunks[self.free[*i]].size >= size)
+            .min_by_key(|i| self.chunks[self.free[*i]].size)?;
+
+        Some(self.free.swap_remove(position))
+    }
+
+    // Забирает вернувшиеся буферы. Буфер который не удалось отобразить
+    // пересоздаётся того же размера, новый буфер сразу отображён
+    fn collect_returned(&mut self, ctx: &Context) {
+        let returned = std::mem::take(&mut *self.returned.lock());
+
+        for (index, mapped) in returned {
+            if !mapped {
+                self.chunks[index] = create_chunk(ctx, self.chunks[index].size);
+            }
+
+            self.free.push(index);
+        }
+    }
+}
+
+fn create_chunk(ctx: &Context, size: u64) -> Chunk {
+    let size = size.max(MIN_CHUNK_SIZE);
+
+    let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Texture staging buffer"),
+        size,
+        usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: true,
+    });
+
+    Chunk { buffer, size }
+}
+
+#[test]
+fn staging_ring_test() {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).unwrap();
+    let (device, queue) = pollster::block_on(adapter.request_device(&Default::default(), None)).unwrap();
+
+    let ctx = Context {
+        config: wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: 1,
+            height: 1,
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: Vec::new(),
+        },
+        adapter_info: adapter.get_info(),
+        device,
+        queue,
+        surface: None,
+        adapter,
+        instance,
+    };
+
+    let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d { width: 1024, height: 1024, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+
+    let mut ring = StagingRing::default();
+    let data = vec![255u8; 1024 * 1024 * 4];
+
+    // Каждая загрузка больше всех буферов кольца, так что после MAX_CHUNKS
+    // буферы начинают заменяться, а не добавляться
+    for i in 0..MAX_CHUNKS as u32 + 4 {
+        let height = 300 + i * 60;
+        ring.upload(&ctx, &texture, [0, 0], [1024, height], &data).unwrap();
+
+        assert!(ring.chunks.len() <= MAX_CHUNKS);
+    }
+
+    assert_eq!(ring.chunks.len(), MAX_CHUNKS);
+
+    // Когда видеокарта закончила, все буферы снова свободны и по одному разу
+    ctx.device.poll(wgpu::Maintain::Wait);
+    ring.collect_returned(&ctx);
+
+    let mut free = ring.free.clone();
+    free.sort_unstable();
+    assert_eq!(free, (0..MAX_CHUNKS).collect::<Vec<_>>());
+
+    // Ошибки копирования вне текстуры не трогают кольцо
+    assert!(ring.upload(&ctx, &texture, [1000, 0], [100, 1], &data).is_err());
+    assert!(ring.upload(&ctx, &texture, [0, 0], [1024, 1024], &data[..10]).is_err());
+    assert_eq!(ring.free.len(), MAX_CHUNKS);
+}
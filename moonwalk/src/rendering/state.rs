@@ -9,6 +9,7 @@ use crate::gpu::{Context, Buffer, MatrixStack, RenderPass};
 use crate::batching::group::BatchGroup;
 use crate::rendering::pipeline::ShaderStore;
 use crate::rendering::texture::Texture;
+use crate::rendering::staging::StagingRing;
 use crate::objects::store::ObjectStore;
 use crate::objects::ShaderId;
 use crate::error::MoonWalkError;
@@ -34,6 +35,7 @@ pub struct RenderState {
     pub rect_shader: ShaderId, // Пайплайн для прямоугольника
     pub white_texture: Texture,
     pub textures: HashMap<u32, Texture>,
+    pub(crate) staging: StagingRing, // Буферы для загрузки пикселей в текстуры
     next_texture_id: u32,
 }
 
@@ -83,7 +85,7 @@ impl RenderState {
         
         Ok(Self {
             store: ObjectStore::new(),
-            batches: BatchGroup::new(ctx),
+            batches: BatchGroup::new(ctx)?,
             shaders,
             matrix_stack,
             uniform_buffer,
@@ -91,6 +93,7 @@ impl RenderState {
             rect_shader,
             white_texture,
             textures: HashMap::new(),
+            staging: StagingRing::default(),
             next_texture_id: 1, // 0 занят под white_texture
         })
     }
@@ -115,7 +118,10 @@ impl RenderState {
     /// Функция для рисования всех объектов
     pub fn draw(&mut self, ctx: &Context, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView, text_engine: &mut TextWare, atlas_bg: Option<&wgpu::BindGroup>, clear_color: Vec4) {
         // Подготавливаем батчи
-        self.batches.objects.prepare(ctx, &self.store, text_engine);
+        self.batches.prepare(ctx, &self.store, text_engine, &self.white_texture, &self.textures);
+
+        // Атлас глифов sdf текста
+        let sdf_bg = text_engine.get_sdf_bind_group();
         
         // Если объекты грязные (dirty) - снимаем флаг 
         // (так как изменения уже отрисованы)
@@ -144,8 +150,8 @@ impl RenderState {
             // Устаналиваем пайплайн
             pass.set_pipeline(pipeline);
             
-            // Отрисовываем прямоугольники
-            self.batches.objects.render(&mut pass, &self.white_texture, &self.textures, atlas_bg);
+            // Отрисовываем прямоугольники и пути между ними
+            self.batches.render(&mut pass, pipeline, &self.white_texture, &self.textures, atlas_bg, Some(&sdf_bg));
         }
     }
 
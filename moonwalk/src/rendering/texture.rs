@@ -5,42 +5,232 @@ use crate::gpu::Context;
 use crate::error::MoonWalkError;
 use image::GenericImageView;
 
+/// Фильтрация текстуры при увеличении и уменьшении
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextureFilter {
+    /// Сглаживание между соседними пикселями
+    #[default]
+    Linear,
+    /// Ближайший пиксель, для пиксель-арта
+    Nearest,
+}
+
+/// Что текстура показывает при uv за пределами 0..1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextureWrap {
+    /// Растягивается крайний пиксель
+    #[default]
+    ClampToEdge,
+    /// Текстура повторяется (тайлинг)
+    Repeat,
+    /// Текстура повторяется с отражением через раз
+    MirrorRepeat,
+}
+
+/// Параметры сэмплирования текстуры. По умолчанию линейная фильтрация без
+/// мипмапов, края растягиваются, цвета в sRGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureOptions {
+    pub filter: TextureFilter,
+
+    /// Генерировать уровни детализации. Уменьшенная текстура тогда не
+    /// рябит, но занимает на треть больше видеопамяти
+    pub mipmaps: bool,
+
+    pub wrap_u: TextureWrap,
+    pub wrap_v: TextureWrap,
+
+    /// Данные картинки в sRGB (обычные png/jpg). false для карт нормалей,
+    /// масок и других данных которые не являются цветом
+    pub srgb: bool,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        Self {
+            filter: TextureFilter::Linear,
+            mipmaps: false,
+            wrap_u: TextureWrap::ClampToEdge,
+            wrap_v: TextureWrap::ClampToEdge,
+            srgb: true,
+        }
+    }
+}
+
+impl TextureOptions {
+    /// Одинаковый режим краёв по обеим осям
+    pub fn wrap(mut self, wrap: TextureWrap) -> Self {
+        self.wrap_u = wrap;
+        self.wrap_v = wrap;
+        self
+    }
+
+    pub fn filter(mut self, filter: TextureFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    pub fn srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    pub(crate) fn format(&self) -> wgpu::TextureFormat {
+        if self.srgb {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        }
+    }
+
+    /// Сколько уровней детализации у текстуры такого размера
+    pub(crate) fn mip_level_count(&self, width: u32, height: u32) -> u32 {
+        if self.mipmaps {
+            32 - width.max(height).max(1).leading_zeros()
+        } else {
+            1
+        }
+    }
+
+    pub(crate) fn create_sampler(&self, ctx: &Context) -> wgpu::Sampler {
+        let filter = match self.filter {
+            TextureFilter::Linear => wgpu::FilterMode::Linear,
+            TextureFilter::Nearest => wgpu::FilterMode::Nearest,
+        };
+
+        ctx.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: address_mode(self.wrap_u),
+            address_mode_v: address_mode(self.wrap_v),
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: filter,
+            min_filter: filter,
+            mipmap_filter: filter,
+            ..Default::default()
+        })
+    }
+}
+
+fn address_mode(wrap: TextureWrap) -> wgpu::AddressMode {
+    match wrap {
+        TextureWrap::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        TextureWrap::Repeat => wgpu::AddressMode::Repeat,
+        TextureWrap::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+    }
+}
+
+/// Формат пикселей текстуры которую заполняет приложение (create_texture).
+/// Стандартный шейдер объектов читает из R8 и RG8 только красный и зелёный
+/// каналы, такие текстуры в первую очередь для своих пайплайнов
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextureFormat {
+    /// Обычный цвет, 4 байта на пиксель
+    #[default]
+    Rgba8UnormSrgb,
+    /// Линейные данные, 4 байта на пиксель
+    Rgba8Unorm,
+    /// Один канал, 1 байт на пиксель (маски, шум, карты высот)
+    R8Unorm,
+    /// Два канала, 2 байта на пиксель (векторы, uv)
+    Rg8Unorm,
+    /// HDR цвет, 4 f16 и 8 байт на пиксель
+    Rgba16Float,
+}
+
+impl TextureFormat {
+    pub fn bytes_per_pixel(&self) -> u32 {
+        match self {
+            TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm => 4,
+            TextureFormat::R8Unorm => 1,
+            TextureFormat::Rg8Unorm => 2,
+            TextureFormat::Rgba16Float => 8,
+        }
+    }
+
+    pub(crate) fn to_wgpu(self) -> wgpu::TextureFormat {
+        match self {
+            TextureFormat::Rgba8UnormSrgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+            TextureFormat::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm,
+            TextureFormat::R8Unorm => wgpu::TextureFormat::R8Unorm,
+            TextureFormat::Rg8Unorm => wgpu::TextureFormat::Rg8Unorm,
+            TextureFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+        }
+    }
+}
+
 pub struct Texture {
     pub texture: wgpu::Texture,
     pub view: wgpu::TextureView,
     pub sampler: wgpu::Sampler,
     pub bind_group: wgpu::BindGroup,
+
+    /// С какими параметрами текстура создана. Для служебных текстур
+    /// (цели рендера, глубина) это параметры по умолчанию
+    pub options: TextureOptions,
 }
 
 impl Texture {
     pub fn from_bytes(ctx: &Context, bytes: &[u8], label: &str) -> Result<Self, MoonWalkError> {
+        Self::from_bytes_with_options(ctx, bytes, label, &TextureOptions::default())
+    }
+
+    pub fn from_bytes_with_options(ctx: &Context, bytes: &[u8], label: &str, options: &TextureOptions) -> Result<Self, MoonWalkError> {
         let img = image::load_from_memory(bytes)
             .map_err(|e| MoonWalkError::TextureLoading(e.to_string()))?;
             
-        Self::from_image(ctx, &img, label)
+        Self::from_image_with_options(ctx, &img, label, options)
     }
 
     pub fn from_image(ctx: &Context, img: &image::DynamicImage, label: &str) -> Result<Self, MoonWalkError> {
+        Self::from_image_with_options(ctx, img, label, &TextureOptions::default())
+    }
+
+    pub fn from_image_with_options(ctx: &Context, img: &image::DynamicImage, label: &str, options: &TextureOptions) -> Result<Self, MoonWalkError> {
         let rgba = img.to_rgba8();
         let dimensions = img.dimensions();
 
-        Self::from_raw(ctx, &rgba, dimensions.0, dimensions.1, label)
+        Self::from_raw_with_options(ctx, &rgba, dimensions.0, dimensions.1, label, options)
     }
 
     pub fn from_raw(ctx: &Context, bytes: &[u8], width: u32, height: u32, label: &str) -> Result<Self, MoonWalkError> {
+        Self::from_raw_with_options(ctx, bytes, width, height, label, &TextureOptions::default())
+    }
+
+    /// Создаёт текстуру из пикселей RGBA8. Если в параметрах включены мипмапы,
+    /// уменьшенные уровни сразу считаются на видеокарте
+    pub fn from_raw_with_options(
+        ctx: &Context,
+        bytes: &[u8],
+        width: u32,
+        height: u32,
+        label: &str,
+        options: &TextureOptions,
+    ) -> Result<Self, MoonWalkError> {
+        if width == 0 || height == 0 || bytes.len() < width as usize * height as usize * 4 {
+            return Err(MoonWalkError::TextureLoading(format!(
+                "Invalid texture data for {}: {}x{}, {} bytes", label, width, height, bytes.len()
+            )));
+        }
+
         let size = wgpu::Extent3d {
             width,
             height,
             depth_or_array_layers: 1,
         };
 
+        let mip_level_count = options.mip_level_count(width, height);
+
         let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
             label: Some(label),
             size,
-            mip_level_count: 1,
+            mip_level_count,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            format: options.format(),
             usage: wgpu::TextureUsages::TEXTURE_BINDING 
                  | wgpu::TextureUsages::RENDER_ATTACHMENT 
                  | wgpu::TextureUsages::COPY_SRC
@@ -64,60 +254,67 @@ impl Texture {
             size,
         );
 
+        if mip_level_count > 1 {
+            crate::rendering::mipmap::generate_mipmaps(ctx, &texture)?;
+        }
+
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        
-        let sampler = ctx.device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Linear,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
+        let sampler = options.create_sampler(ctx);
+        let bind_group = texture_bind_group(ctx, &view, &sampler);
 
-        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                },
-            ],
-            label: Some("texture_bind_group_layout"),
-        });
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+            bind_group,
+            options: *options,
+        })
+    }
 
-        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-            ],
-            label: Some("texture_bind_group"),
+    /// Создаёт прозрачную текстуру которую приложение заполняет само через
+    /// StagingRing (update_texture_region). Мипмапов у таких текстур нет
+    pub fn create_dynamic(ctx: &Context, width: u32, height: u32, format: TextureFormat, label: &str) -> Result<Self, MoonWalkError> {
+        let limit = ctx.device.limits().max_texture_dimension_2d;
+
+        if width == 0 || height == 0 || width > limit || height > limit {
+            return Err(MoonWalkError::TextureLoading(format!(
+                "Invalid texture size for {}: {}x{} (limit {})", label, width, height, limit
+            )));
+        }
+
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: format.to_wgpu(),
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                 | wgpu::TextureUsages::RENDER_ATTACHMENT
+                 | wgpu::TextureUsages::COPY_SRC
+                 | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
         });
 
+        let options = TextureOptions {
+            srgb: format == TextureFormat::Rgba8UnormSrgb,
+            ..Default::default()
+        };
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = options.create_sampler(ctx);
+        let bind_group = texture_bind_group(ctx, &view, &sampler);
+
         Ok(Self {
             texture,
             view,
             sampler,
             bind_group,
+            options,
         })
     }
 
@@ -207,6 +404,7 @@ impl Texture {
             view,
             sampler,
             bind_group,
+            options: TextureOptions::default(),
         }
     }
 
@@ -291,6 +489,7 @@ impl Texture {
             view,
             sampler,
             bind_group,
+            options: TextureOptions::default(),
         }
     }
 
@@ -366,15 +565,23 @@ impl Texture {
             view,
             sampler,
             bind_group,
+            options: TextureOptions::default(),
         }
     }
 
+    /// Сколько байт видеопамяти занимает текстура вместе с мипмапами
+    pub fn memory_bytes(&self) -> u64 {
+        crate::rendering::memory::texture_bytes(&self.texture)
+    }
+
     /// Скачивает данные текстуры из видеокарты в озу и возвращает буфер изображения
     /// Эта операция блокирующая и относительно медленная
     pub fn download(&self, ctx: &crate::gpu::Context) -> Result<image::RgbaImage, crate::MoonWalkError> {
         let device = &ctx.device;
         let queue = &ctx.queue;
 
+        self.check_rgba8()?;
+
         let width = self.texture.width();
         let height = self.texture.height();
         
@@ -451,10 +658,23 @@ impl Texture {
             .ok_or_else(|| crate::MoonWalkError::IOError("Failed to create image buffer".to_string()))
     }
 
+    // Скачивание умеет только 4 байта на пиксель (RGBA8 и BGRA8 поверхности)
+    fn check_rgba8(&self) -> Result<(), MoonWalkError> {
+        let format = self.texture.format();
+
+        if format.block_copy_size(None) != Some(4) || format.components() != 4 {
+            return Err(MoonWalkError::IOError(format!("Reading {:?} textures is not supported", format)));
+        }
+
+        Ok(())
+    }
+
     pub fn read_pixel(&self, ctx: &crate::gpu::Context, x: u32, y: u32) -> Result<[u8; 4], crate::MoonWalkError> {
         let device = &ctx.device;
         let queue = &ctx.queue;
 
+        self.check_rgba8()?;
+
         // Проверка границ для безопасности
         if x >= self.texture.width() || y >= self.texture.height() {
             return Err(crate::MoonWalkError::IOError("Pixel coordinates out of bounds".to_string()));
@@ -524,4 +744,44 @@ impl Texture {
 
         Ok(pixel)
     }
-}
\ No newline at end of file
+}
+
+// Группа привязки текстуры и сэмплера для стандартного шейдера объектов
+fn texture_bind_group(ctx: &Context, view: &wgpu::TextureView, sampler: &wgpu::Sampler) -> wgpu::BindGroup {
+    let layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+        label: Some("texture_bind_group_layout"),
+    });
+
+    ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+        label: Some("texture_bind_group"),
+    })
+}
@@ -95,14 +95,20 @@ impl ObjectInstance {
         ]
     }
 
-    /// [WAIT DOC]
+    /// Упаковывает uv [x, y, w, h] в 4 u16. Значения в 0..1 (обычный случай,
+    /// спрайты атласа) хранятся с шагом 1/32767. Значения вне 0..1 нужны для
+    /// тайлинга текстур с TextureWrap::Repeat, они помечаются старшим битом и
+    /// хранятся с шагом 1/128 в диапазоне -128..128. Распаковка в unpack_uv
+    /// из shape.wgsl
     pub fn pack_uv(uv: [f32; 4]) -> [u16; 4] {
-        [
-            (uv[0] * 65535.0) as u16,
-            (uv[1] * 65535.0) as u16,
-            (uv[2] * 65535.0) as u16,
-            (uv[3] * 65535.0) as u16,
-        ]
+        uv.map(|value| {
+            if (0.0..=1.0).contains(&value) {
+                (value * 32767.0).round() as u16
+            } else {
+                let wide = ((value.clamp(-128.0, 128.0) + 128.0) * 128.0).round() as u16;
+                0x8000 | wide.min(0x7FFF)
+            }
+        })
     }
 
     /// [WAIT DOC]
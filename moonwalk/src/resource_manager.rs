@@ -1,14 +1,19 @@
 // Часть проекта MoonWalk с открытым исходным кодом.
 // Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2025 MoonWalk
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[cfg(target_os = "android")]
 use std::ffi::CString;
 
 use crate::gpu::Context;
-use crate::rendering::texture::Texture;
+use crate::rendering::texture::{Texture, TextureOptions};
+use crate::rendering::loader::{BackgroundLoader, DetachedRead};
+use crate::rendering::animated::AnimatedFrames;
+use crate::rendering::memory::TextureBudget;
 use crate::error::MoonWalkError;
+use crate::vfs::{DirectorySource, MemorySource, MountId, Vfs, VfsSource, ZipSource};
 
 pub struct ResourceManager {
     // Для windows, linux, macos, bsd и ios - он пустой, так как на этих ОС получение файлов
@@ -17,27 +22,158 @@ pub struct ResourceManager {
     // файловой системе.
     #[cfg(target_os = "android")]
     asset_manager: ndk::asset::AssetManager,
+
+    // Разобранные svg документы по айди текстур в которые они растеризованы,
+    // нужны чтобы перерисовать текстуру при смене размера или масштаба
+    pub(crate) svgs: HashMap<u32, crate::public::SvgSource>,
+
+    // Смонтированные папки, архивы и файлы в памяти. Опрашиваются до
+    // обычного чтения с диска или из AssetManager
+    vfs: Vfs,
+
+    // Текстуры которые читаются и декодируются в пуле потоков
+    pub(crate) loader: BackgroundLoader,
+
+    // Анимированные текстуры (GIF, APNG, WebP) которые двигает
+    // update_animated_textures
+    pub(crate) animations: Vec<AnimatedFrames>,
+
+    // Бюджет видеопамяти и файлы текстур которые можно выгрузить
+    pub(crate) budget: TextureBudget,
+
+    // Файлы загруженных ресурсов за которыми следит hot-reload
+    #[cfg(feature = "hot-reload")]
+    pub(crate) watcher: crate::hot_reload::Watcher,
 }
 
 impl ResourceManager {
     #[cfg(not(target_os = "android"))]
     pub fn new() -> Self {
-        Self {}
+        Self {
+            svgs: HashMap::new(),
+            vfs: Vfs::new(),
+            loader: BackgroundLoader::default(),
+            animations: Vec::new(),
+            budget: TextureBudget::default(),
+            #[cfg(feature = "hot-reload")]
+            watcher: Default::default(),
+        }
     }
 
     #[cfg(target_os = "android")]
     pub fn new(asset_manager: ndk::asset::AssetManager) -> Self {
         Self {
-            asset_manager
+            asset_manager,
+            svgs: HashMap::new(),
+            vfs: Vfs::new(),
+            loader: BackgroundLoader::default(),
+            animations: Vec::new(),
+            budget: TextureBudget::default(),
+            #[cfg(feature = "hot-reload")]
+            watcher: Default::default(),
         }
     }
 
-    /// Главный загрузчик. Работает очень хитро, на desktop и IOS он просто берёт путь
-    /// и читает файл по этому пути через fs, но на android есть два способа чтения.
+    /// Монтирует папку файловой системы в логическую папку point ("" это
+    /// корень). Файлы из неё читаются по путям point/имя на любой платформе
+    pub fn mount_dir(&mut self, point: &str, dir: &str, priority: i32) -> Result<MountId, MoonWalkError> {
+        if !Path::new(dir).is_dir() {
+            return Err(MoonWalkError::IOError(format!("Directory not found: {}", dir)));
+        }
+
+        Ok(self.vfs.mount(point, DirectorySource::new(dir), priority))
+    }
+
+    /// Монтирует zip архив (.pak). Сам архив читается через read_bytes, так
+    /// что на android он может лежать в assets
+    pub fn mount_zip(&mut self, point: &str, path: &str, priority: i32) -> Result<MountId, MoonWalkError> {
+        let bytes = self.read_bytes(path)?;
+        self.mount_zip_bytes(point, bytes, priority)
+    }
+
+    /// Монтирует zip архив из памяти, например из include_bytes!
+    pub fn mount_zip_bytes(&mut self, point: &str, bytes: Vec<u8>, priority: i32) -> Result<MountId, MoonWalkError> {
+        Ok(self.vfs.mount(point, ZipSource::new(bytes)?, priority))
+    }
+
+    /// Монтирует файлы из памяти
+    pub fn mount_memory(&mut self, point: &str, files: MemorySource, priority: i32) -> MountId {
+        self.vfs.mount(point, files, priority)
+    }
+
+    /// Монтирует свой источник файлов
+    pub fn mount(&mut self, point: &str, source: impl VfsSource + 'static, priority: i32) -> MountId {
+        self.vfs.mount(point, source, priority)
+    }
+
+    pub fn unmount(&mut self, id: MountId) -> bool {
+        self.vfs.unmount(id)
+    }
+
+    /// Файлы в логической папке dir и её подпапках из всех точек
+    /// монтирования. Обычная файловая система и assets не перечисляются
+    pub fn list_files(&self, dir: &str) -> Vec<String> {
+        self.vfs.list(dir)
+    }
+
+    /// Есть ли файл по этому пути (в точках монтирования или там же, где
+    /// его ищет read_bytes)
+    pub fn exists(&self, path: &str) -> bool {
+        if self.vfs.exists(path) {
+            return true;
+        }
+
+        #[cfg(target_os = "android")]
+        {
+            if path.starts_with('/') {
+                Path::new(path).is_file()
+            } else {
+                CString::new(path).ok()
+                    .and_then(|c_path| self.asset_manager.open(&c_path))
+                    .is_some()
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            Path::new(path).is_file()
+        }
+    }
+
+    /// Путь в настоящей файловой системе из которого read_bytes прочитает
+    /// файл. None для архивов, файлов в памяти и assets на android
+    pub fn real_path(&self, path: &str) -> Option<PathBuf> {
+        if let Some(real) = self.vfs.real_path(path) {
+            return real;
+        }
+
+        #[cfg(target_os = "android")]
+        {
+            path.starts_with('/').then(|| PathBuf::from(path))
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            Some(PathBuf::from(path))
+        }
+    }
+
+    /// Точки монтирования. Клон дешёвый и годится для чтения из другого потока
+    pub fn vfs(&self) -> &Vfs {
+        &self.vfs
+    }
+
+    /// Главный загрузчик. Сначала путь ищется в точках монтирования (папки,
+    /// архивы, память) по приоритету. Если там файла нет то на desktop и IOS
+    /// файл читается по этому пути через fs, а на android есть два способа чтения.
     /// Если первый символ пути это "/" то файл также загружается из файловой системы
     /// (Пример: data/data/com.example.package/files/test.txt), а если символ любой
     /// другой кроме "/" - файл получается и читается через AssetManager
     pub fn read_bytes(&self, path: &str) -> Result<Vec<u8>, MoonWalkError> {
+        if let Some(result) = self.vfs.read(path) {
+            return result;
+        }
+
         #[cfg(target_os = "android")]
         {
             if path.starts_with('/') {
@@ -60,21 +196,52 @@ impl ResourceManager {
         }
     }
 
+    /// Готовит чтение файла для другого потока. Точки монтирования и обычные
+    /// файлы читаются уже там, а assets на android сразу: AssetManager нельзя
+    /// передать в другой поток
+    pub(crate) fn read_bytes_detached(&self, path: &str) -> DetachedRead {
+        #[cfg(target_os = "android")]
+        {
+            if !path.starts_with('/') && !self.vfs.exists(path) {
+                let bytes = self.read_bytes(path);
+                return Box::new(move || bytes);
+            }
+        }
+
+        let vfs = self.vfs.clone();
+        let path = path.to_string();
+
+        Box::new(move || match vfs.read(&path) {
+            Some(result) => result,
+            None => std::fs::read(&path).map_err(|e| MoonWalkError::IOError(e.to_string())),
+        })
+    }
+
     /// Загружает текстуру из файла через gpu контекст и путь к нему
     pub fn load_texture(&self, ctx: &Context, path: &str) -> Result<Texture, MoonWalkError> {
+        self.load_texture_with_options(ctx, path, &TextureOptions::default())
+    }
+
+    /// Загружает текстуру из файла с заданными фильтрацией, краями и мипмапами
+    pub fn load_texture_with_options(&self, ctx: &Context, path: &str, options: &TextureOptions) -> Result<Texture, MoonWalkError> {
         let bytes = self.read_bytes(path)?;
         
         let label = Path::new(path).file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("Unknown Texture");
             
-        Texture::from_bytes(ctx, &bytes, label)
+        Texture::from_bytes_with_options(ctx, &bytes, label, options)
     }
 
     /// Асинхронная версия читалки байтов. Требует включенной фичи async. На десктопе
     /// (для обычных файлов) использует tokio для неблокирующего чтения
     #[cfg(feature = "async")]
     pub async fn read_bytes_async(&self, path: &str) -> Result<Vec<u8>, MoonWalkError> {
+        // Точки монтирования уже в памяти или читаются быстро
+        if let Some(result) = self.vfs.read(path) {
+            return result;
+        }
+
         #[cfg(target_os = "android")]
         {
             // Если путь начинается с / то это файловая система, чтение асинхронно, но
@@ -9,13 +9,17 @@ struct Uniforms {
 @group(1) @binding(0) var t_diffuse: texture_2d<f32>;
 @group(1) @binding(1) var s_diffuse: sampler;
 
+// Должны совпадать с SDF_ATLAS_SIZE и SDF_SPREAD из textware/sdf.rs
+const SDF_ATLAS_SIZE: f32 = 2048.0;
+const SDF_SPREAD: f32 = 12.0;
+
 struct VertexInput {
     @location(0) position: vec2<f32>,
 };
 
 struct InstanceInput {
     @location(1) pos_size: vec4<f32>,
-    @location(2) uv: vec4<f32>,
+    @location(2) uv_packed: vec4<u32>,
     @location(3) radii_packed: vec4<u32>,
     @location(4) gradient_data: vec4<f32>,
     @location(5) extra: vec2<f32>,
@@ -38,6 +42,15 @@ struct VertexOutput {
     @location(8) @interpolate(flat) effect_data: vec2<u32>,
 };
 
+// Распаковка uv из ObjectInstance::pack_uv. Со старшим битом лежат
+// значения вне 0..1 (тайлинг), без него обычные 0..1
+fn unpack_uv(packed: vec4<u32>) -> vec4<f32> {
+    let wide = (packed & vec4<u32>(0x8000u)) != vec4<u32>(0u);
+    let value = vec4<f32>(packed & vec4<u32>(0x7FFFu));
+
+    return select(value / 32767.0, value / 128.0 - 128.0, wide);
+}
+
 @vertex
 fn vs_main(in: VertexInput, instance: InstanceInput) -> VertexOutput {
     var out: VertexOutput;
@@ -67,7 +80,8 @@ fn vs_main(in: VertexInput, instance: InstanceInput) -> VertexOutput {
     out.size = size;
     out.local_pos = in.position * size;
 
-    out.uv = instance.uv.xy + (in.position * instance.uv.zw);
+    let uv = unpack_uv(instance.uv_packed);
+    out.uv = uv.xy + (in.position * uv.zw);
     out.type_id = instance.type_id;
     out.gradient_data = instance.gradient_data;
     out.effect_data = instance.effect_data;
@@ -158,13 +172,32 @@ fn sd_rounded_box(p: vec2<f32>, b: vec2<f32>, r: vec4<f32>) -> f32 {
     return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - radius;
 }
 
+// Переводит значение из SDF атласа в покрытие пикселя. Расстояние хранится
+// в текселях атласа, поэтому через производную uv оно переводится в пиксели
+// экрана, так край остаётся шириной в 1 пиксель при любом масштабе.
+// dilate расширяет контур (обводка), blur расширяет переход (тень, свечение),
+// оба в текселях атласа
+fn sdf_coverage(value: f32, uv_fwidth: vec2<f32>, dilate: f32, blur: f32) -> f32 {
+    let texels_per_pixel = max((uv_fwidth.x + uv_fwidth.y) * 0.5 * SDF_ATLAS_SIZE, 0.0001);
+    let texel_distance = (value - 0.5) * 2.0 * SDF_SPREAD + dilate;
+    let edge = max(texels_per_pixel, blur);
+    
+    return clamp(texel_distance / edge + 0.5, 0.0, 1.0);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Производные нужно считать до любого ветвления
+    let uv_fwidth = fwidth(in.uv);
+
     let half_size = in.size * 0.5;
     let p = in.local_pos - half_size;
 
+    // У SDF глифов в radii лежат параметры эффектов, а не скругление
+    let is_sdf = in.type_id == 4294967294u; // u32::MAX - 1
+
     let min_half = min(half_size.x, half_size.y);
-    let r = min(in.radii, vec4<f32>(min_half));
+    let r = select(min(in.radii, vec4<f32>(min_half)), vec4<f32>(0.0), is_sdf);
 
     let border_width = f32(in.effect_data.x) / 16.0;
     let shadow_soft = f32(in.effect_data.y) / 16.0;
@@ -198,6 +231,9 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     if (in.type_id == 4294967295u) { // u32::MAX
         let mask = textureSample(t_diffuse, s_diffuse, in.uv).r;
         final_color.a = final_color.a * mask;
+    } else if (is_sdf) {
+        let distance = textureSample(t_diffuse, s_diffuse, in.uv).r;
+        final_color.a = final_color.a * sdf_coverage(distance, uv_fwidth, in.radii.x, in.radii.y);
     } else if (in.type_id > 0u) {
         let tex_color = textureSample(t_diffuse, s_diffuse, in.uv);
         final_color = tex_color * final_color;
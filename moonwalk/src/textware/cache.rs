@@ -105,6 +105,10 @@ impl GlyphCache {
         &self.bind_group
     }
 
+    pub(crate) fn memory_bytes(&self) -> u64 {
+        crate::rendering::memory::texture_bytes(&self.texture)
+    }
+
     pub fn upload_pending(&mut self, queue: &wgpu::Queue) {
         if self.pending_uploads.is_empty() {
             return;
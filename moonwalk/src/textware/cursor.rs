@@ -0,0 +1,530 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Работа с позициями внутри текста: попадание точкой, позиция каретки,
+// прямоугольники выделения и навигация. Всё считается по буферам cosmic-text
+// которые TextWare уже кэширует для каждого объекта. Снаружи позиция это
+// байтовый индекс в исходной строке, внутри cosmic-text это пара (абзац,
+// индекс в абзаце), переводом между ними занимается LineMap. Позиции глифов
+// берутся из RunPlacement, а не из LayoutGlyph, чтобы учитывать межбуквенный
+// интервал и табличные цифры.
+//
+// Индексы всегда логические (порядок символов в строке), а x визуальные. В
+// двунаправленном тексте соседние по индексу позиции могут оказаться на разных
+// концах строки, поэтому Left/Right двигают каретку по экрану, а Previous/Next
+// по тексту
+
+use cosmic_text::{Affinity, Buffer, Cursor, LayoutRun};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::textware::{direction_mark_len, BufferState, TextWare};
+use crate::textware::style::{GlyphBox, RunPlacement};
+
+/// Движение каретки. Previous/Next логические (к соседней графеме в
+/// тексте), Left/Right визуальные (к соседней позиции на экране)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Motion {
+    Previous,
+    Next,
+    Left,
+    Right,
+    PreviousWord,
+    NextWord,
+    LineStart,
+    LineEnd,
+    Up,
+    Down,
+    TextStart,
+    TextEnd,
+}
+
+/// Соответствие абзацев буфера и байтовых смещений исходного текста. prefix
+/// это длина метки направления в начале каждого абзаца буфера, её нет в
+/// исходном тексте
+pub(crate) struct LineMap {
+    starts: Vec<usize>,
+    lens: Vec<usize>,
+    prefix: usize,
+}
+
+impl LineMap {
+    pub(crate) fn new(text: &str, buffer: &Buffer, prefix: usize) -> Self {
+        let mut starts = Vec::with_capacity(buffer.lines.len());
+        let mut lens = Vec::with_capacity(buffer.lines.len());
+        let mut offset = 0;
+
+        for line in buffer.lines.iter() {
+            let len = line.text().len().saturating_sub(prefix).min(text.len() - offset);
+
+            starts.push(offset);
+            lens.push(len);
+            offset += len;
+
+            // cosmic-text отрезает разделитель абзаца, его длину нужно
+            // пропустить вручную
+            let rest = &text[offset..];
+            if rest.starts_with("\r\n") {
+                offset += 2;
+            } else if let Some(c) = rest.chars().next() {
+                if is_paragraph_separator(c) {
+                    offset += c.len_utf8();
+                }
+            }
+        }
+
+        if starts.is_empty() {
+            starts.push(0);
+            lens.push(0);
+        }
+
+        Self { starts, lens, prefix }
+    }
+
+    pub(crate) fn to_cursor(&self, index: usize, affinity: Affinity) -> Cursor {
+        let line = self.starts.partition_point(|&start| start <= index).saturating_sub(1);
+        let local = (index - self.starts[line].min(index)).min(self.lens[line]);
+
+        Cursor::new_with_affinity(line, local + self.prefix, affinity)
+    }
+
+    pub(crate) fn to_index(&self, cursor: Cursor) -> usize {
+        let line = cursor.line.min(self.starts.len() - 1);
+        self.starts[line] + cursor.index.saturating_sub(self.prefix).min(self.lens[line])
+    }
+}
+
+pub(crate) fn is_paragraph_separator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{1c}'..='\u{1e}' | '\u{85}' | '\u{2029}')
+}
+
+/// Позиция каретки внутри одной визуальной строки. Второе значение говорит
+/// совпала ли сторона (affinity) с запрошенной, при переносе строки один и
+/// тот же индекс есть в конце одной строки и в начале следующей
+fn caret_in_run(run: &LayoutRun, boxes: &[GlyphBox], cursor: Cursor) -> Option<(f32, bool)> {
+    if run.line_i != cursor.line {
+        return None;
+    }
+
+    if run.glyphs.is_empty() {
+        return if cursor.index == 0 { Some((0.0, true)) } else { None };
+    }
+
+    let mut fallback = None;
+
+    for (glyph, glyph_box) in run.glyphs.iter().zip(boxes) {
+        let rtl = glyph.level.is_rtl();
+        let (gx, gw) = (glyph_box.x, glyph_box.w);
+
+        if cursor.index == glyph.start {
+            let x = if rtl { gx + gw } else { gx };
+            if cursor.affinity == Affinity::After {
+                return Some((x, true));
+            }
+            fallback = fallback.or(Some((x, false)));
+        }
+
+        if cursor.index == glyph.end {
+            let x = if rtl { gx } else { gx + gw };
+            if cursor.affinity == Affinity::Before {
+                return Some((x, true));
+            }
+            fallback = fallback.or(Some((x, false)));
+        }
+
+        // Индекс внутри кластера (лигатура): делим ширину глифа
+        // пропорционально символам
+        if cursor.index > glyph.start && cursor.index < glyph.end {
+            let fraction = cluster_fraction(run.text, glyph.start, glyph.end, cursor.index);
+            let x = if rtl {
+                gx + gw * (1.0 - fraction)
+            } else {
+                gx + gw * fraction
+            };
+
+            return Some((x, true));
+        }
+    }
+
+    fallback
+}
+
+fn cluster_fraction(text: &str, start: usize, end: usize, index: usize) -> f32 {
+    let (Some(head), Some(whole)) = (text.get(start..index), text.get(start..end)) else {
+        return (index - start) as f32 / (end - start).max(1) as f32;
+    };
+
+    head.chars().count() as f32 / whole.chars().count().max(1) as f32
+}
+
+/// Логические границы визуальной строки
+fn run_range(run: &LayoutRun) -> (usize, usize) {
+    let start = run.glyphs.iter().map(|g| g.start).min().unwrap_or(0);
+    let end = run.glyphs.iter().map(|g| g.end).max().unwrap_or(0);
+    (start, end)
+}
+
+impl TextWare {
+    /// Буфер вместе с итоговыми позициями глифов и длиной метки направления
+    fn cached_layout(&self, id: u64) -> Option<(&Buffer, &[RunPlacement], usize)> {
+        let (buffer, state) = self.buffers.get(&id)?;
+        let state = state.as_ref()?;
+
+        Some((buffer, state.placements.as_slice(), direction_mark_len(state.params.direction)))
+    }
+
+    /// Текст который реально разложен в буфере и длина его части общей с
+    /// исходным текстом. У обрезанного текста в буфере лежит префикс с
+    /// многоточием, позиции внутри многоточия прижимаются к концу префикса
+    fn laid_out_text<'a>(&'a self, id: u64, text: &'a str) -> (&'a str, usize) {
+        match self.buffers.get(&id) {
+            Some((_, Some(BufferState { truncated: Some((display, prefix)), .. }))) => (display, *prefix),
+            _ => (text, text.len()),
+        }
+    }
+
+    /// Находит позицию в тексте по точке (координаты относительно объекта).
+    /// Буфер должен быть подготовлен через process_text
+    pub fn hit(&self, id: u64, text: &str, x: f32, y: f32) -> Option<(usize, Affinity)> {
+        let (buffer, placements, prefix) = self.cached_layout(id)?;
+        let (text, limit) = self.laid_out_text(id, text);
+        let map = LineMap::new(text, buffer, prefix);
+        let cursor = hit_layout(buffer, placements, x, y)?;
+
+        Some((map.to_index(cursor).min(limit), cursor.affinity))
+    }
+
+    /// Позиция каретки: x, верх строки и высота строки
+    pub fn caret_position(&self, id: u64, text: &str, index: usize, affinity: Affinity) -> Option<(f32, f32, f32)> {
+        let (buffer, placements, prefix) = self.cached_layout(id)?;
+        let (text, limit) = self.laid_out_text(id, text);
+        let map = LineMap::new(text, buffer, prefix);
+        let cursor = map.to_cursor(index.min(limit), affinity);
+        let line_height = buffer.metrics().line_height;
+
+        let (_, x, top) = locate_caret(buffer, placements, cursor)?;
+        Some((x, top, line_height))
+    }
+
+    /// Прямоугольники [x, y, w, h] выделения диапазона start..end. Для
+    /// двунаправленного текста логический диапазон может давать несколько
+    /// прямоугольников в одной строке
+    pub fn selection_rects(&self, id: u64, text: &str, start: usize, end: usize) -> Vec<[f32; 4]> {
+        let mut rects: Vec<[f32; 4]> = Vec::new();
+
+        let Some((buffer, placements, prefix)) = self.cached_layout(id) else {
+            return rects;
+        };
+
+        let (text, limit) = self.laid_out_text(id, text);
+        let (start, end) = (start.min(end).min(limit), start.max(end).min(limit));
+        if start == end {
+            return rects;
+        }
+
+        let map = LineMap::new(text, buffer, prefix);
+        let line_height = buffer.metrics().line_height;
+
+        for (run, placement) in buffer.layout_runs().zip(placements) {
+            let run_rects_from = rects.len();
+
+            for (glyph, glyph_box) in run.glyphs.iter().zip(placement.boxes.iter()) {
+                // Глиф метки направления сжимается в пустой диапазон
+                let glyph_start = map.to_index(Cursor::new(run.line_i, glyph.start));
+                let glyph_end = map.to_index(Cursor::new(run.line_i, glyph.end));
+
+                let from = start.max(glyph_start);
+                let to = end.min(glyph_end);
+
+                if from >= to {
+                    continue;
+                }
+
+                // Частичное выделение кластера
+                let f0 = cluster_fraction(run.text, glyph.start, glyph.end, glyph.start + from - glyph_start);
+                let f1 = cluster_fraction(run.text, glyph.start, glyph.end, glyph.start + to - glyph_start);
+
+                let (x0, x1) = if glyph.level.is_rtl() {
+                    (glyph_box.x + glyph_box.w * (1.0 - f1), glyph_box.x + glyph_box.w * (1.0 - f0))
+                } else {
+                    (glyph_box.x + glyph_box.w * f0, glyph_box.x + glyph_box.w * f1)
+                };
+
+                // Соседние по x глифы склеиваются в один прямоугольник
+                let merged = rects[run_rects_from..].iter_mut().any(|rect| {
+                    let rect_end = rect[0] + rect[2];
+
+                    if (rect_end - x0).abs() < 0.5 {
+                        rect[2] = x1 - rect[0];
+                        true
+                    } else if (x1 - rect[0]).abs() < 0.5 {
+                        rect[2] = rect_end - x0;
+                        rect[0] = x0;
+                        true
+                    } else {
+                        false
+                    }
+                });
+
+                if !merged {
+                    rects.push([x0, run.line_top, x1 - x0, line_height]);
+                }
+            }
+        }
+
+        rects
+    }
+
+    /// Двигает каретку. preferred_x это сохранённая позиция по горизонтали для
+    /// движения вверх/вниз, возвращается новая позиция и x который нужно
+    /// передать в следующее вертикальное движение
+    pub fn motion(
+        &self,
+        id: u64,
+        text: &str,
+        index: usize,
+        affinity: Affinity,
+        motion: Motion,
+        preferred_x: Option<f32>,
+    ) -> (usize, Affinity, Option<f32>) {
+        let (text, limit) = self.laid_out_text(id, text);
+        let (index, affinity, x) = self.motion_in(id, text, index.min(limit), affinity, motion, preferred_x);
+
+        (index.min(limit), affinity, x)
+    }
+
+    fn motion_in(
+        &self,
+        id: u64,
+        text: &str,
+        index: usize,
+        affinity: Affinity,
+        motion: Motion,
+        preferred_x: Option<f32>,
+    ) -> (usize, Affinity, Option<f32>) {
+
+        let Some((buffer, placements, prefix)) = self.cached_layout(id) else {
+            return (index, affinity, None);
+        };
+
+        let map = LineMap::new(text, buffer, prefix);
+        let cursor = map.to_cursor(index, affinity);
+
+        match motion {
+            Motion::Previous => (previous_grapheme(text, index), Affinity::After, None),
+            Motion::Next => (next_grapheme(text, index), Affinity::Before, None),
+
+            // Визуальное движение: к ближайшей позиции слева или справа в той
+            // же строке. На краю строки каретка переходит в начало следующей
+            // или в конец предыдущей строки, смотря куда идёт абзац
+            Motion::Left | Motion::Right => {
+                if let Some(target) = visual_step(buffer, placements, cursor, motion == Motion::Right) {
+                    return (map.to_index(target), target.affinity, None);
+                }
+
+                let Some((run_i, _, _)) = locate_caret(buffer, placements, cursor) else {
+                    return (index, affinity, None);
+                };
+
+                let rtl = buffer.layout_runs().nth(run_i).is_some_and(|run| run.rtl);
+
+                if (motion == Motion::Right) != rtl {
+                    match buffer.layout_runs().nth(run_i + 1) {
+                        Some(run) => (map.to_index(Cursor::new(run.line_i, run_range(&run).0)), Affinity::After, None),
+                        None => (index, affinity, None),
+                    }
+                } else {
+                    match run_i.checked_sub(1).and_then(|i| buffer.layout_runs().nth(i)) {
+                        Some(run) => (map.to_index(Cursor::new(run.line_i, run_range(&run).1)), Affinity::Before, None),
+                        None => (index, affinity, None),
+                    }
+                }
+            }
+
+            Motion::PreviousWord => {
+                let target = text.unicode_word_indices()
+                    .map(|(start, _)| start)
+                    .rfind(|&start| start < index)
+                    .unwrap_or(0);
+
+                (target, Affinity::After, None)
+            }
+
+            Motion::NextWord => {
+                let target = text.unicode_word_indices()
+                    .map(|(start, word)| start + word.len())
+                    .find(|&end| end > index)
+                    .unwrap_or(text.len());
+
+                (target, Affinity::Before, None)
+            }
+
+            Motion::LineStart | Motion::LineEnd => {
+                let Some((run_i, _, _)) = locate_caret(buffer, placements, cursor) else {
+                    return (index, affinity, None);
+                };
+
+                let Some(run) = buffer.layout_runs().nth(run_i) else {
+                    return (index, affinity, None);
+                };
+
+                let (start, end) = run_range(&run);
+
+                if motion == Motion::LineStart {
+                    (map.to_index(Cursor::new(run.line_i, start)), Affinity::After, None)
+                } else {
+                    (map.to_index(Cursor::new(run.line_i, end)), Affinity::Before, None)
+                }
+            }
+
+            Motion::Up | Motion::Down => {
+                let Some((run_i, x, _)) = locate_caret(buffer, placements, cursor) else {
+                    return (index, affinity, preferred_x);
+                };
+
+                let x = preferred_x.unwrap_or(x);
+
+                let target_i = if motion == Motion::Up {
+                    run_i.checked_sub(1)
+                } else {
+                    Some(run_i + 1)
+                };
+
+                let target = target_i.and_then(|i| buffer.layout_runs().nth(i));
+
+                match target {
+                    Some(run) => {
+                        let y = run.line_top + buffer.metrics().line_height * 0.5;
+
+                        match hit_layout(buffer, placements, x, y) {
+                            Some(hit) => (map.to_index(hit), hit.affinity, Some(x)),
+                            None => (index, affinity, Some(x)),
+                        }
+                    }
+
+                    // Выше первой строки - в начало, ниже последней - в конец
+                    None if motion == Motion::Up => (0, Affinity::After, Some(x)),
+                    None => (text.len(), Affinity::Before, Some(x)),
+                }
+            }
+
+            Motion::TextStart => (0, Affinity::After, None),
+            Motion::TextEnd => (text.len(), Affinity::Before, None),
+        }
+    }
+}
+
+/// Ищет визуальную строку с кареткой. Возвращает номер строки в layout_runs,
+/// x каретки и верх строки
+fn locate_caret(buffer: &Buffer, placements: &[RunPlacement], cursor: Cursor) -> Option<(usize, f32, f32)> {
+    let mut fallback = None;
+
+    for (i, (run, placement)) in buffer.layout_runs().zip(placements).enumerate() {
+        if let Some((x, exact)) = caret_in_run(&run, &placement.boxes, cursor) {
+            if exact {
+                return Some((i, x, run.line_top));
+            }
+
+            fallback = fallback.or(Some((i, x, run.line_top)));
+        }
+    }
+
+    fallback
+}
+
+/// Соседняя позиция каретки по горизонтали в той же визуальной строке.
+/// Кандидаты это края глифов, поэтому каретка не встаёт внутрь кластера, а
+/// на стыке LTR и RTL фрагментов проходит их в экранном порядке. None если
+/// каретка уже у края строки
+fn visual_step(buffer: &Buffer, placements: &[RunPlacement], cursor: Cursor, right: bool) -> Option<Cursor> {
+    let (run_i, x, _) = locate_caret(buffer, placements, cursor)?;
+    let run = buffer.layout_runs().nth(run_i)?;
+    let placement = placements.get(run_i)?;
+
+    let mut nearest: Option<(f32, Cursor)> = None;
+
+    for (glyph, glyph_box) in run.glyphs.iter().zip(placement.boxes.iter()) {
+        // Невидимые глифы (метки направления, управляющие символы) не
+        // дают отдельной остановки
+        if glyph_box.w <= 0.0 {
+            continue;
+        }
+
+        let start = Cursor::new_with_affinity(run.line_i, glyph.start, Affinity::After);
+        let end = Cursor::new_with_affinity(run.line_i, glyph.end, Affinity::Before);
+
+        let (left, right_edge) = if glyph.level.is_rtl() { (end, start) } else { (start, end) };
+
+        for (edge_x, edge) in [(glyph_box.x, left), (glyph_box.x + glyph_box.w, right_edge)] {
+            let distance = if right { edge_x - x } else { x - edge_x };
+
+            if distance > 0.5 && nearest.is_none_or(|(best, _)| distance < best) {
+                nearest = Some((distance, edge));
+            }
+        }
+    }
+
+    nearest.map(|(_, cursor)| cursor)
+}
+
+/// Попадание точкой в раскладку. Строка выбирается по y (выше первой это
+/// первая, ниже последней это последняя), внутри строки ближайший край
+/// глифа: левая половина глифа даёт его левый край, правая половина правый
+fn hit_layout(buffer: &Buffer, placements: &[RunPlacement], x: f32, y: f32) -> Option<Cursor> {
+    let line_height = buffer.metrics().line_height;
+    let mut target = None;
+
+    for (run, placement) in buffer.layout_runs().zip(placements) {
+        let bottom = run.line_top + line_height;
+        target = Some((run, placement));
+
+        if y < bottom {
+            break;
+        }
+    }
+
+    let (run, placement) = target?;
+
+    if run.glyphs.is_empty() {
+        return Some(Cursor::new(run.line_i, 0));
+    }
+
+    let mut nearest: Option<(f32, Cursor)> = None;
+
+    for (glyph, glyph_box) in run.glyphs.iter().zip(placement.boxes.iter()) {
+        let start = Cursor::new_with_affinity(run.line_i, glyph.start, Affinity::After);
+        let end = Cursor::new_with_affinity(run.line_i, glyph.end, Affinity::Before);
+
+        let (left, right) = if glyph.level.is_rtl() { (end, start) } else { (start, end) };
+
+        if x >= glyph_box.x && x < glyph_box.x + glyph_box.w {
+            return Some(if x < glyph_box.x + glyph_box.w * 0.5 { left } else { right });
+        }
+
+        let (distance, cursor) = if x < glyph_box.x {
+            (glyph_box.x - x, left)
+        } else {
+            (x - glyph_box.x - glyph_box.w, right)
+        };
+
+        if nearest.is_none_or(|(best, _)| distance < best) {
+            nearest = Some((distance, cursor));
+        }
+    }
+
+    nearest.map(|(_, cursor)| cursor)
+}
+
+fn previous_grapheme(text: &str, index: usize) -> usize {
+    text.grapheme_indices(true)
+        .map(|(start, _)| start)
+        .take_while(|&start| start < index)
+        .last()
+        .unwrap_or(0)
+}
+
+fn next_grapheme(text: &str, index: usize) -> usize {
+    text.grapheme_indices(true)
+        .map(|(start, grapheme)| start + grapheme.len())
+        .find(|&end| end > index)
+        .unwrap_or(text.len())
+}
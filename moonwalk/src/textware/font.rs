@@ -3,7 +3,7 @@
 
 use crate::textware::TextError;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct FontId(pub u64);
@@ -12,6 +12,12 @@ pub struct FontSystem {
     pub(crate) sys: cosmic_text::FontSystem,
     next_id: u64,
     families: HashMap<FontId, String>,
+
+    // Начертания в базе cosmic-text которые загрузил каждый шрифт
+    faces: HashMap<FontId, Vec<cosmic_text::fontdb::ID>>,
+
+    // Шрифты которые по умолчанию рисуются через SDF атлас
+    sdf_fonts: HashSet<FontId>,
 }
 
 impl FontSystem {
@@ -20,26 +26,61 @@ impl FontSystem {
             sys: cosmic_text::FontSystem::new(),
             next_id: 1,
             families: HashMap::new(),
+            faces: HashMap::new(),
+            sdf_fonts: HashSet::new(),
         }
     }
 
     pub fn load_font_from_bytes(&mut self, data: &[u8], _name: &str) -> Result<FontId, TextError> {
         // Имя игнорируется так как реальное имя берётся из метаданных. _name остаётся
         // из-за обратной совместимости
-        let count_before = self.sys.db().faces().count();
-        
-        self.sys.db_mut().load_font_data(data.to_vec());
-        
-        let count_after = self.sys.db().faces().count();
-        
-        if count_after <= count_before {
-            return Err(TextError::FontLoading("Failed to parse font data".to_string()));
+        let (faces, real_family_name) = self.add_faces(data)?;
+
+        let id = FontId(self.next_id);
+        self.next_id += 1;
+
+        self.families.insert(id, real_family_name);
+        self.faces.insert(id, faces);
+
+        Ok(id)
+    }
+
+    /// Заменяет шрифт id новыми данными. Старые начертания удаляются только
+    /// если новые разобрались, иначе шрифт остаётся прежним
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_font_from_bytes(&mut self, id: FontId, data: &[u8]) -> Result<(), TextError> {
+        if !self.families.contains_key(&id) {
+            return Err(TextError::FontLoading(format!("Font {} not found", id.0)));
+        }
+
+        let (faces, real_family_name) = self.add_faces(data)?;
+
+        for face in self.faces.insert(id, faces).into_iter().flatten() {
+            self.sys.db_mut().remove_face(face);
         }
 
+        self.families.insert(id, real_family_name);
+
+        Ok(())
+    }
+
+    // Загружает данные шрифта в базу и возвращает новые начертания и имя
+    // семейства
+    fn add_faces(&mut self, data: &[u8]) -> Result<(Vec<cosmic_text::fontdb::ID>, String), TextError> {
+        let before: HashSet<_> = self.sys.db().faces().map(|face| face.id).collect();
+
+        self.sys.db_mut().load_font_data(data.to_vec());
+
+        let faces: Vec<_> = self.sys.db().faces()
+            .map(|face| face.id)
+            .filter(|id| !before.contains(id))
+            .collect();
+
         // Получение последнего добавленного шрифта из базы faces возвращает итератор
         // по всем шрифтам
-        let face = self.sys.db().faces().last()
-            .ok_or_else(|| TextError::FontLoading("Database error after loading".to_string()))?;
+        let face = faces.last()
+            .and_then(|id| self.sys.db().face(*id))
+            .ok_or_else(|| TextError::FontLoading("Failed to parse font data".to_string()))?;
 
         // Получение настоящего имени семейства из метаданных families возвращает список 
         // кортежей имя и язык поэтому используется первое имя
@@ -47,15 +88,24 @@ impl FontSystem {
             .map(|(name, _)| name.clone())
             .unwrap_or_else(|| "Unknown Family".to_string());
 
-        let id = FontId(self.next_id);
-        self.next_id += 1;
-
-        self.families.insert(id, real_family_name);
-
-        Ok(id)
+        Ok((faces, real_family_name))
     }
 
     pub fn get_family_name(&self, id: FontId) -> Option<&String> {
         self.families.get(&id)
     }
+
+    /// Включает или выключает SDF режим по умолчанию для всех текстов с этим
+    /// шрифтом
+    pub fn set_sdf(&mut self, id: FontId, enabled: bool) {
+        if enabled {
+            self.sdf_fonts.insert(id);
+        } else {
+            self.sdf_fonts.remove(&id);
+        }
+    }
+
+    pub fn is_sdf(&self, id: FontId) -> bool {
+        self.sdf_fonts.contains(&id)
+    }
 }
@@ -10,16 +10,25 @@
 #![allow(unused_imports)]
 
 pub mod cache;
+pub mod sdf;
+pub mod cursor;
+pub mod style;
+pub mod text_path;
 mod error;
 mod font;
 
 pub use error::TextError;
 pub use font::{FontSystem, FontId};
 pub use cache::GlyphCache;
+pub use sdf::{SdfCache, SdfGlyph};
+pub use cursor::Motion;
+pub use style::{TextStyle, FontFeatures, GlyphBox, RunPlacement};
+pub use text_path::TextPath;
 pub use cosmic_text::{Attrs, Metrics, Family, Wrap};
 
 use bytemuck::{Pod, Zeroable};
 use cosmic_text::Shaping;
+use unicode_segmentation::UnicodeSegmentation;
 use std::collections::HashMap;
 
 /// DONT TOUCH / НЕ ТРОГАТЬ
@@ -27,6 +36,11 @@ use std::collections::HashMap;
 /// системы. Это критически важно
 pub const ATLAS_ID: u32 = u32::MAX;
 
+/// DONT TOUCH / НЕ ТРОГАТЬ
+/// Аналогично ATLAS_ID, только для атласа полей расстояний (SDF). В шейдере
+/// идёт хардкод на u32::MAX - 1
+pub const SDF_ATLAS_ID: u32 = u32::MAX - 1;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 pub struct TextVertex {
@@ -41,13 +55,52 @@ pub struct TextMesh {
     pub indices: Vec<u16>,
 }
 
-/// [WAIT DOC]
+/// Параметры раскладки текста. Значения хранятся так же как в ObjectStore:
+/// - align: 0 - лево, 1 - центр, 2 - право, 3 - по ширине, 4 - начало, 5 - конец
+/// - overflow: 0 - видимо, 1 - обрезка, 2 - многоточие
+/// - max_lines: 0 - без лимита
+/// - direction: 0 - авто, 1 - LTR, 2 - RTL
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextParams {
+    pub font_id: FontId,
+    pub font_size: f32,
+    pub max_width: f32,
+    pub max_height: f32,
+    pub align: u8,
+    pub overflow: u8,
+    pub max_lines: u32,
+    pub direction: u8,
+    pub style: TextStyle,
+}
+
+/// Глиф готовый к батчингу: позиция пера относительно объекта, поворот
+/// глифа вокруг пера (не 0 только у текста вдоль пути) и ключ в кэше
+#[derive(Clone, Copy, Debug)]
+pub struct PlacedGlyph {
+    pub x: f32,
+    pub y: f32,
+    pub angle: f32,
+    pub key: cosmic_text::CacheKey,
+}
+
+/// Символ который дописывается к обрезанному тексту
+// Ключ буфера для measure_text_params. Ключи объектов это их индексы,
+// до него они не доходят
+const MEASURE_KEY: u64 = u64::MAX;
+
+pub const ELLIPSIS: &str = "\u{2026}";
+
+/// Состояние с которым был разложен кэшированный буфер
 struct BufferState {
     text_hash: u64,
-    font_id: u64,
-    font_size_bits: u32,
-    bounds_bits: (u32, u32),
-    align_byte: u8,
+    params: TextParams,
+
+    // Если текст был обрезан, в буфере лежит не исходный текст а его префикс
+    // с многоточием. Тут хранится этот текст и длина префикса
+    truncated: Option<(String, usize)>,
+
+    // Итоговые позиции глифов по строкам (см. style::place_runs)
+    placements: Vec<RunPlacement>,
 }
 
 /// Основная структура для этого модуля, хранит кэщ и шрифтовую систему 
@@ -55,7 +108,8 @@ pub struct TextWare {
     pub atlas_id: Option<u32>,
     pub font_system: FontSystem,
     pub glyph_cache: GlyphCache,
-    buffers: HashMap<u64, (cosmic_text::Buffer, BufferState)>,
+    pub sdf_cache: SdfCache,
+    buffers: HashMap<u64, (cosmic_text::Buffer, Option<BufferState>)>,
     scratch_buffer: cosmic_text::Buffer,
 }
 
@@ -89,6 +143,7 @@ impl TextWare {
             atlas_id: Some(ATLAS_ID),
             font_system,
             glyph_cache: GlyphCache::new(device, queue),
+            sdf_cache: SdfCache::new(device),
             buffers: HashMap::new(),
             scratch_buffer,
         }
@@ -100,6 +155,19 @@ impl TextWare {
         self.font_system.load_font_from_bytes(data, name)
     }
 
+    /// Заменяет данные шрифта не меняя его айди. Разметка текстов сцены
+    /// сбрасывается, чтобы при следующей отрисовке они собрались с новым шрифтом
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_font_bytes(&mut self, id: FontId, data: &[u8]) -> Result<(), TextError> {
+        self.font_system.reload_font_from_bytes(id, data)?;
+
+        for (_, state) in self.buffers.values_mut() {
+            *state = None;
+        }
+
+        Ok(())
+    }
+
     pub fn create_text(&mut self, content: &str, font_id: Option<FontId>, font_size: f32, line_height: Option<f32>) -> Text {
         let metrics = Metrics::new(font_size, line_height.unwrap_or(font_size * 1.2));
         let mut buffer = cosmic_text::Buffer::new(&mut self.font_system.sys, metrics);
@@ -158,12 +226,22 @@ impl TextWare {
 
     pub fn prepare(&mut self, queue: &wgpu::Queue) {
         self.glyph_cache.upload_pending(queue);
+        self.sdf_cache.upload_pending(queue);
+    }
+
+    /// Размер обоих атласов глифов в байтах
+    pub(crate) fn atlas_bytes(&self) -> u64 {
+        self.glyph_cache.memory_bytes() + self.sdf_cache.memory_bytes()
     }
 
     pub fn get_bind_group(&self) -> wgpu::BindGroup {
         self.glyph_cache.get_bind_group().clone()
     }
 
+    pub fn get_sdf_bind_group(&self) -> wgpu::BindGroup {
+        self.sdf_cache.get_bind_group().clone()
+    }
+
     pub fn generate_mesh(&mut self, text: &mut Text) -> TextMesh {
         text.buffer.shape_until_scroll(&mut self.font_system.sys, false);
 
@@ -209,108 +287,143 @@ impl TextWare {
 
     /// Подготавливает текст (layout) и возвращает буфер с глифами.
     /// Используется в UberBatch.
-    pub fn process_text(
-        &mut self,
-        id: u64,
-        text: &str,
-        font_id: FontId,
-        font_size: f32,
-        max_width: f32,
-        max_height: f32,
-        align: u8,
-    ) -> &cosmic_text::Buffer {
-        let current_state = BufferState {
-            text_hash: hash_str(text),
-            font_id: font_id.0,
-            font_size_bits: font_size.to_bits(),
-            bounds_bits: (max_width.to_bits(), max_height.to_bits()),
-            align_byte: align,
-        };
+    pub fn process_text(&mut self, id: u64, text: &str, params: TextParams) -> &cosmic_text::Buffer {
+        let text_hash = hash_str(text);
 
-        let entry = self.buffers.entry(id).or_insert_with(|| {
+        let (buffer, cached_state) = self.buffers.entry(id).or_insert_with(|| {
             let font_system = &mut self.font_system.sys;
-            let buffer = cosmic_text::Buffer::new(font_system, Metrics::new(font_size, font_size));
+            let buffer = cosmic_text::Buffer::new(font_system, Metrics::new(params.font_size, params.font_size));
 
-            (buffer, BufferState { 
-                text_hash: 0, font_id: u64::MAX, font_size_bits: 0, bounds_bits: (0,0), align_byte: 255 
-            })
+            (buffer, None)
         });
-        
-        let (buffer, cached_state) = entry;
 
-        let changed = 
-            cached_state.text_hash != current_state.text_hash ||
-            cached_state.font_id != current_state.font_id ||
-            cached_state.font_size_bits != current_state.font_size_bits ||
-            cached_state.bounds_bits != current_state.bounds_bits;
+        let changed = !matches!(
+            cached_state,
+            Some(state) if state.text_hash == text_hash && state.params == params
+        );
 
         if changed {
-            let family_name_str = if let Some(name) = self.font_system.get_family_name(font_id) {
-                 Some(name.clone())
-            } else {
-                 None
-            };
+            let family_name_str = self.font_system.get_family_name(params.font_id).cloned();
 
-            let font_system = &mut self.font_system.sys;
-            
-            let metrics = Metrics::new(font_size, font_size * 1.2);
-            buffer.set_metrics(font_system, metrics);
-            buffer.set_size(font_system, max_width, max_height); 
-            
             let mut attrs = Attrs::new();
             if let Some(name) = family_name_str.as_deref() {
                  attrs = attrs.family(Family::Name(name));
             }
 
-            buffer.set_text(font_system, text, attrs, Shaping::Advanced);
+            let style = params.style;
+            let attrs = style.apply(attrs);
+            let font_system = &mut self.font_system.sys;
+            let scratch = &mut self.scratch_buffer;
+            
+            let metrics = Metrics::new(params.font_size, style.line_height(params.font_size));
+            buffer.set_metrics(font_system, metrics);
+
+            // Высота буфера не ограничивается: cosmic-text молча отбрасывает
+            // строки ниже границы, а обрезкой по высоте занимается truncate
+            buffer.set_size(font_system, params.max_width, f32::MAX);
+
+            shape_buffer(buffer, font_system, text, attrs, &params);
 
-            let cosmic_align = match align {
-                1 => Some(cosmic_text::Align::Center),
-                2 => Some(cosmic_text::Align::End),
-                3 => Some(cosmic_text::Align::Justified),
-                _ => Some(cosmic_text::Align::Left), // 0
+            let digit_width = if style.tabular_numbers() {
+                Some(measure_digit_width(scratch, font_system, metrics, attrs))
+            } else {
+                None
             };
 
-            for line in buffer.lines.iter_mut() {
-                line.set_align(cosmic_align);
+            let mut correction = 0.0;
+            let mut placements = style::place_runs(buffer, &style, params.align, params.max_width, digit_width, correction);
+
+            // Интервал и табличные цифры расширяют строки уже после переноса,
+            // поэтому ширина переноса уменьшается пока строки не влезут
+            if style.needs_placement() {
+                for _ in 0..3 {
+                    let overflow = placements.iter()
+                        .map(|placement| placement.line_w - params.max_width)
+                        .fold(0.0f32, f32::max);
+
+                    if overflow <= 0.5 || correction + overflow >= params.max_width {
+                        break;
+                    }
+
+                    correction += overflow;
+
+                    buffer.set_size(font_system, params.max_width - correction, f32::MAX);
+                    buffer.shape_until_scroll(font_system, false);
+
+                    placements = style::place_runs(buffer, &style, params.align, params.max_width, digit_width, correction);
+                }
             }
 
-            buffer.shape_until_scroll(font_system, false);
+            let truncated = truncate(buffer, font_system, scratch, text, attrs, &params, &placements);
+
+            if truncated.is_some() {
+                placements = style::place_runs(buffer, &style, params.align, params.max_width, digit_width, correction);
+            }
              
-            *cached_state = current_state;
+            *cached_state = Some(BufferState { text_hash, params, truncated, placements });
         }
 
         buffer
     }
 
-    pub fn collect_glyphs(
-        &mut self, 
-        id: u64, 
-        text: &str,
-        font_id: FontId,
-        font_size: f32,
-        max_width: f32,
-        max_height: f32,
-        align: u8,
-    ) -> Vec<(f32, f32, cosmic_text::CacheKey)> {
-        self.process_text(id, text, font_id, font_size, max_width, max_height, align);
+    /// Раскладывает текст (если нужно) и возвращает глифы для отрисовки. Если
+    /// у текста есть путь, глифы ставятся вдоль него
+    pub fn collect_glyphs(&mut self, id: u64, text: &str, params: TextParams, path: Option<&TextPath>) -> Vec<PlacedGlyph> {
+        self.process_text(id, text, params);
+
+        let Some((buffer, Some(state))) = self.buffers.get(&id) else {
+            return Vec::new();
+        };
 
-        let buffer = self.buffers.get(&id).expect("Buffer not found after process");
+        if let Some(path) = path {
+            return place_along_path(buffer, &state.placements, &params, path);
+        }
 
         let mut glyphs = Vec::new();
         
-        for run in buffer.0.layout_runs() {
-            for glyph in run.glyphs {
+        for (run, placement) in buffer.layout_runs().zip(state.placements.iter()) {
+            for (glyph, glyph_box) in run.glyphs.iter().zip(placement.boxes.iter()) {
                 let physical = glyph.physical((0., 0.), 1.0);
-                let x = glyph.x; 
-                let y = run.line_y + physical.y as f32;
-                glyphs.push((x, y, physical.cache_key));
+
+                glyphs.push(PlacedGlyph {
+                    x: glyph_box.draw_x,
+                    y: run.line_y + physical.y as f32,
+                    angle: 0.0,
+                    key: physical.cache_key,
+                });
             }
         }
         
         glyphs
     }
 
+    /// Размер уже разложенного текста (после process_text), с учётом
+    /// обрезки по строкам и многоточия
+    pub fn measure_buffer(&self, id: u64) -> (f32, f32) {
+        let Some((buffer, Some(state))) = self.buffers.get(&id) else {
+            return (0.0, 0.0);
+        };
+
+        let line_height = buffer.metrics().line_height;
+
+        let mut width = 0.0f32;
+        let mut height = 0.0f32;
+
+        for (run, placement) in buffer.layout_runs().zip(state.placements.iter()) {
+            width = width.max(placement.line_w);
+            height = height.max(run.line_top + line_height);
+        }
+
+        (width, height)
+    }
+
+    /// Размер текста с параметрами текстового объекта, с обрезкой по
+    /// max_lines и многоточием. Раскладка кэшируется в отдельном буфере
+    pub fn measure_text_params(&mut self, text: &str, params: TextParams) -> (f32, f32) {
+        self.process_text(MEASURE_KEY, text, params);
+        self.measure_buffer(MEASURE_KEY)
+    }
+
     pub fn measure_text(
         &mut self,
         text: &str,
@@ -357,3 +470,328 @@ impl TextWare {
         (width, height)
     }
 }
+
+/// Невидимая метка направления (LRM или RLM) которой начинается каждый
+/// абзац если направление задано явно. У cosmic-text нет способа задать
+/// направление абзаца, а метка это сильный символ, по которому bidi
+/// определяет направление. None для автоопределения
+fn direction_mark(direction: u8) -> Option<char> {
+    match direction {
+        1 => Some('\u{200e}'),
+        2 => Some('\u{200f}'),
+        _ => None,
+    }
+}
+
+/// На сколько байт каждый абзац в буфере длиннее абзаца исходного текста
+pub(crate) fn direction_mark_len(direction: u8) -> usize {
+    direction_mark(direction).map_or(0, char::len_utf8)
+}
+
+/// Ставит глифы вдоль пути. Визуальные строки идут друг за другом в одну
+/// линию, каждый глиф встаёт на путь серединой своей ширины (с интервалом),
+/// базовая линия совпадает с путём. Выравнивание задаёт что привязано к
+/// start_offset: начало, середина или конец текста, а по ширине растягивает
+/// текст до конца пути (на замкнутом пути равномерно по всей длине). Глифы
+/// которые не попали на открытый путь не рисуются
+fn place_along_path(
+    buffer: &cosmic_text::Buffer,
+    placements: &[RunPlacement],
+    params: &TextParams,
+    path: &TextPath,
+) -> Vec<PlacedGlyph> {
+    // (середина глифа вдоль текста, сдвиг пера от середины, y глифа, ключ)
+    let mut items: Vec<(f32, f32, f32, cosmic_text::CacheKey)> = Vec::new();
+    let mut total = 0.0;
+    let mut rtl = None;
+
+    for (run, placement) in buffer.layout_runs().zip(placements) {
+        let left = placement.boxes.iter().map(|b| b.x).fold(f32::INFINITY, f32::min);
+        let right = placement.boxes.iter().map(|b| b.x + b.w).fold(f32::NEG_INFINITY, f32::max);
+
+        if !left.is_finite() || !right.is_finite() {
+            continue;
+        }
+
+        rtl.get_or_insert(run.rtl);
+
+        for (glyph, glyph_box) in run.glyphs.iter().zip(placement.boxes.iter()) {
+            let physical = glyph.physical((0., 0.), 1.0);
+            let center = glyph_box.x + glyph_box.w * 0.5;
+
+            items.push((total + center - left, glyph_box.draw_x - center, physical.y as f32, physical.cache_key));
+        }
+
+        total += right - left;
+    }
+
+    items.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let length = path.length();
+    let count = items.len();
+
+    let spread = match (params.align, path.is_closed()) {
+        (3, true) if count > 0 => ((length - total) / count as f32).max(0.0),
+        (3, false) if count > 1 => ((length - path.start_offset - total) / (count - 1) as f32).max(0.0),
+        _ => 0.0,
+    };
+
+    let base = path.start_offset - style::anchor_factor(params.align, rtl.unwrap_or(false)) * total;
+
+    let mut glyphs = Vec::with_capacity(count);
+
+    for (i, (center, pen_dx, glyph_y, key)) in items.into_iter().enumerate() {
+        let Some((point, angle)) = path.sample(base + center + spread * i as f32) else {
+            continue;
+        };
+
+        let (sin, cos) = angle.sin_cos();
+        let local_y = glyph_y - path.baseline_offset;
+
+        glyphs.push(PlacedGlyph {
+            x: point.x + pen_dx * cos - local_y * sin,
+            y: point.y + pen_dx * sin + local_y * cos,
+            angle,
+            key,
+        });
+    }
+
+    glyphs
+}
+
+/// Заполняет буфер текстом с выравниванием и направлением и делает шейпинг
+fn shape_buffer(
+    buffer: &mut cosmic_text::Buffer,
+    font_system: &mut cosmic_text::FontSystem,
+    text: &str,
+    attrs: Attrs,
+    params: &TextParams,
+) {
+    match direction_mark(params.direction) {
+        Some(mark) => {
+            let mut marked = String::with_capacity(text.len() + 16);
+            let mut chars = text.chars().peekable();
+            marked.push(mark);
+
+            while let Some(c) = chars.next() {
+                marked.push(c);
+
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    continue;
+                }
+
+                if cursor::is_paragraph_separator(c) {
+                    marked.push(mark);
+                }
+            }
+
+            buffer.set_text(font_system, &marked, attrs, Shaping::Advanced);
+        }
+
+        None => buffer.set_text(font_system, text, attrs, Shaping::Advanced),
+    }
+
+    // Left и Right абсолютные, Start это выравнивание cosmic-text по
+    // умолчанию (по направлению абзаца), End прижимает к концу абзаца. Если
+    // глифы потом сдвигаются (style::place_runs), по ширине растягивает уже
+    // place_runs, иначе растянутые пробелы не знают про интервал
+    let cosmic_align = match params.align {
+        1 => Some(cosmic_text::Align::Center),
+        2 => Some(cosmic_text::Align::Right),
+        3 if params.style.needs_placement() => Some(cosmic_text::Align::Left),
+        3 => Some(cosmic_text::Align::Justified),
+        4 => None,
+        5 => Some(cosmic_text::Align::End),
+        _ => Some(cosmic_text::Align::Left), // 0
+    };
+
+    for line in buffer.lines.iter_mut() {
+        line.set_align(cosmic_align);
+    }
+
+    buffer.shape_until_scroll(font_system, false);
+}
+
+/// Обрезает разложенный текст по лимиту строк и (для overflow обрезки и
+/// многоточия) по высоте границ. Обрезка идёт по тексту, а не по глифам:
+/// в буфер заново раскладывается префикс до конца последней видимой строки,
+/// для многоточия префикс укорачивается по графемам пока вместе с ELLIPSIS
+/// не влезет в строку. Благодаря этому многоточие встаёт по правилам bidi
+/// (в RTL строке оно окажется слева) и не разрывает кластеры.
+/// Возвращает текст который теперь лежит в буфере и длину префикса
+fn truncate(
+    buffer: &mut cosmic_text::Buffer,
+    font_system: &mut cosmic_text::FontSystem,
+    scratch: &mut cosmic_text::Buffer,
+    text: &str,
+    attrs: Attrs,
+    params: &TextParams,
+    placements: &[RunPlacement],
+) -> Option<(String, usize)> {
+    let line_height = buffer.metrics().line_height;
+
+    let mut limit = usize::MAX;
+
+    if params.max_lines > 0 {
+        limit = params.max_lines as usize;
+    }
+
+    // Хотя бы одна строка остаётся даже если границы ниже строки
+    if params.overflow != 0 && line_height > 0.0 {
+        limit = limit.min((params.max_height / line_height).floor().max(1.0) as usize);
+    }
+
+    if buffer.layout_runs().count() <= limit {
+        return None;
+    }
+
+    let map = cursor::LineMap::new(text, buffer, direction_mark_len(params.direction));
+    let run = buffer.layout_runs().nth(limit - 1)?;
+
+    let to_index = |index: usize| map.to_index(cosmic_text::Cursor::new(run.line_i, index));
+
+    let line_start = to_index(run.glyphs.iter().map(|g| g.start).min().unwrap_or(0));
+    let line_end = to_index(run.glyphs.iter().map(|g| g.end).max().unwrap_or(0));
+
+    // Глифы последней строки в виде (начало, конец, ширина) в индексах текста
+    let boxes = &placements.get(limit - 1)?.boxes;
+
+    let glyphs: Vec<(usize, usize, f32)> = run.glyphs.iter()
+        .zip(boxes.iter())
+        .map(|(g, glyph_box)| (to_index(g.start), to_index(g.end), glyph_box.w))
+        .collect();
+
+    if params.overflow != 2 {
+        let prefix = text[..line_end].trim_end();
+        shape_buffer(buffer, font_system, prefix, attrs, params);
+
+        return Some((prefix.to_string(), prefix.len()));
+    }
+
+    scratch.set_metrics(font_system, buffer.metrics());
+    scratch.set_size(font_system, f32::MAX, f32::MAX);
+    scratch.set_text(font_system, ELLIPSIS, attrs, Shaping::Advanced);
+    scratch.shape_until_scroll(font_system, false);
+
+    let ellipsis_width = scratch.layout_runs().map(|run| run.line_w).fold(0.0f32, f32::max)
+        + params.style.letter_spacing;
+    let available = params.max_width - ellipsis_width;
+
+    // Границы графем строки от конца к началу. Ширина префикса считается по
+    // глифам которые целиком в него попадают
+    let mut cuts: Vec<usize> = text[line_start..line_end]
+        .grapheme_indices(true)
+        .map(|(i, _)| line_start + i)
+        .chain(std::iter::once(line_end))
+        .collect();
+
+    cuts.reverse();
+
+    let first = cuts.iter()
+        .position(|&cut| {
+            let width: f32 = glyphs.iter().filter(|g| g.1 <= cut).map(|g| g.2).sum();
+            width <= available
+        })
+        .unwrap_or(cuts.len() - 1);
+
+    // Шейпинг префикса вместе с многоточием может дать чуть другую ширину,
+    // поэтому результат проверяется и при переносе укорачивается дальше
+    for &cut in cuts[first..].iter().take(8) {
+        let prefix = text[..cut].trim_end();
+        let display = format!("{}{}", prefix, ELLIPSIS);
+
+        shape_buffer(buffer, font_system, &display, attrs, params);
+
+        if buffer.layout_runs().count() <= limit {
+            return Some((display, prefix.len()));
+        }
+    }
+
+    let prefix = text[..line_end].trim_end();
+    shape_buffer(buffer, font_system, prefix, attrs, params);
+
+    Some((prefix.to_string(), prefix.len()))
+}
+
+/// Ширина табличной цифры: самая широкая из цифр шрифта
+fn measure_digit_width(
+    scratch: &mut cosmic_text::Buffer,
+    font_system: &mut cosmic_text::FontSystem,
+    metrics: Metrics,
+    attrs: Attrs,
+) -> f32 {
+    scratch.set_metrics(font_system, metrics);
+    scratch.set_size(font_system, f32::MAX, f32::MAX);
+    scratch.set_text(font_system, "0123456789", attrs, Shaping::Advanced);
+    scratch.shape_until_scroll(font_system, false);
+
+    scratch.layout_runs()
+        .flat_map(|run| run.glyphs.iter().map(|glyph| glyph.w))
+        .fold(0.0f32, f32::max)
+}
+
+#[test]
+fn truncate_test() {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).unwrap();
+    let (device, queue) = pollster::block_on(adapter.request_device(&Default::default(), None)).unwrap();
+
+    let mut text_engine = TextWare::new(&device, &queue);
+    let font_id = text_engine.load_font_bytes(include_bytes!("../../../assets/font.ttf"), "font").unwrap();
+
+    let params = TextParams {
+        font_id,
+        font_size: 20.0,
+        max_width: 120.0,
+        max_height: f32::MAX,
+        align: 0,
+        overflow: 0,
+        max_lines: 1,
+        direction: 0,
+        style: TextStyle::default(),
+    };
+
+    let truncated = |text_engine: &mut TextWare, id: u64, text: &str, params: TextParams| {
+        text_engine.process_text(id, text, params);
+
+        let (buffer, state) = &text_engine.buffers[&id];
+        (buffer.layout_runs().count(), state.as_ref().unwrap().truncated.clone())
+    };
+
+    // max_lines 1 без многоточия оставляет только первую строку
+    let text = "one two three four five six seven eight nine ten";
+    let (lines, result) = truncated(&mut text_engine, 1, text, params);
+    let (display, prefix_len) = result.unwrap();
+
+    assert_eq!(lines, 1);
+    assert_eq!(display, text[..prefix_len]);
+    assert!(prefix_len < text.len());
+    assert!(!display.ends_with(ELLIPSIS));
+
+    // Каждая графема тут это e с комбинируемым акутом (3 байта), многоточие
+    // не должно отрезать акут от буквы
+    let text = "e\u{301}".repeat(40);
+    let ellipsis = TextParams { overflow: 2, ..params };
+    let (lines, result) = truncated(&mut text_engine, 2, &text, ellipsis);
+    let (display, prefix_len) = result.unwrap();
+
+    assert_eq!(lines, 1);
+    assert!(display.ends_with(ELLIPSIS));
+    assert_eq!(&display[..prefix_len], &text[..prefix_len]);
+    assert!(prefix_len > 0 && prefix_len % 3 == 0);
+
+    // Короткий текст не обрезается
+    let (lines, result) = truncated(&mut text_engine, 3, "one", ellipsis);
+
+    assert_eq!(lines, 1);
+    assert!(result.is_none());
+
+    // Измерение без объекта видит ту же обрезку, measure_text нет
+    let text = "one two three four five six seven eight nine ten";
+    let (_, full_height) = text_engine.measure_text(text, font_id, 20.0, 120.0);
+    let (width, height) = text_engine.measure_text_params(text, ellipsis);
+
+    assert!(full_height > height);
+    assert!(width > 0.0 && width <= 120.0);
+    assert_eq!(text_engine.measure_text_params(text, params), text_engine.measure_buffer(1));
+}
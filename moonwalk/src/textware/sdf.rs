@@ -0,0 +1,393 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use cosmic_text::{CacheKey, SwashCache};
+use swash::scale::image::Content;
+use std::collections::HashMap;
+
+use crate::textware::font::FontSystem;
+
+/// [!] Размер атласа и SDF_SPREAD захардкожены в shape.wgsl, при изменении
+/// нужно поменять и шейдер
+pub const SDF_ATLAS_SIZE: u32 = 2048;
+
+/// Размер шрифта в пикселях с которым глифы один раз растеризуются в атлас.
+/// При рендеринге квад глифа масштабируется под реальный размер шрифта
+pub const SDF_BASE_SIZE: f32 = 48.0;
+
+/// Максимальная дистанция (в пикселях базового размера) которую хранит поле.
+/// Также это отступ вокруг глифа, поэтому эффекты вроде обводки и тени не
+/// могут быть шире этого значения (в масштабе базового размера), то есть
+/// четверти размера шрифта
+pub const SDF_SPREAD: u32 = 12;
+
+const PADDING: u32 = 1;
+
+/// Глиф в SDF атласе. Все размеры указаны для SDF_BASE_SIZE и уже включают
+/// отступ SDF_SPREAD с каждой стороны
+#[derive(Debug, Clone, Copy)]
+pub struct SdfGlyph {
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+    pub uv: (f32, f32, f32, f32),
+}
+
+/// Кэш глифов в виде поля расстояний (signed distance field). В отличии от
+/// GlyphCache глиф растеризуется один раз независимо от размера шрифта, а
+/// край восстанавливается в шейдере, поэтому текст остаётся чётким при
+/// любом масштабе и анимации размера
+pub struct SdfCache {
+    swash_cache: SwashCache,
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    next_x: u32,
+    next_y: u32,
+    row_height: u32,
+    glyphs: HashMap<CacheKey, Option<SdfGlyph>>,
+    pending_uploads: Vec<(u32, u32, u32, u32, Vec<u8>)>,
+}
+
+impl SdfCache {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: SDF_ATLAS_SIZE,
+                height: SDF_ATLAS_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label: Some("SDF Glyph Atlas"),
+            view_formats: &[],
+        });
+
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // В отличии от обычного атласа тут нужна линейная фильтрация, именно
+        // интерполяция расстояния между текселями даёт гладкий край при
+        // увеличении
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("SDF Atlas Layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: Some("SDF Atlas Bind Group"),
+        });
+
+        Self {
+            swash_cache: SwashCache::new(),
+            texture,
+            bind_group,
+            next_x: PADDING,
+            next_y: PADDING,
+            row_height: 0,
+            glyphs: HashMap::new(),
+            pending_uploads: Vec::new(),
+        }
+    }
+
+    pub fn get_bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    pub(crate) fn memory_bytes(&self) -> u64 {
+        crate::rendering::memory::texture_bytes(&self.texture)
+    }
+
+    pub fn upload_pending(&mut self, queue: &wgpu::Queue) {
+        for (x, y, w, h, data) in self.pending_uploads.drain(..) {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d { x, y, z: 0 },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(w),
+                    rows_per_image: None,
+                },
+                wgpu::Extent3d { width: w, height: h, depth_or_array_layers: 1 },
+            );
+        }
+    }
+
+    /// Возвращает SDF глиф для ключа из раскладки cosmic-text. Размер шрифта
+    /// и субпиксельное смещение из ключа игнорируются, поэтому один глиф
+    /// переиспользуется для всех размеров. Цветные глифы (эмодзи) не
+    /// поддерживаются и возвращают None
+    pub fn get_glyph(&mut self, key: CacheKey, font_system: &mut FontSystem) -> Option<SdfGlyph> {
+        let (base_key, _, _) = CacheKey::new(
+            key.font_id,
+            key.glyph_id,
+            SDF_BASE_SIZE,
+            (0.0, 0.0),
+            key.flags,
+        );
+
+        if let Some(glyph) = self.glyphs.get(&base_key) {
+            return *glyph;
+        }
+
+        let glyph = self.rasterize(base_key, font_system);
+        self.glyphs.insert(base_key, glyph);
+
+        glyph
+    }
+
+    fn rasterize(&mut self, key: CacheKey, font_system: &mut FontSystem) -> Option<SdfGlyph> {
+        let image = self.swash_cache.get_image_uncached(&mut font_system.sys, key)?;
+
+        if image.content != Content::Mask {
+            return None;
+        }
+
+        let w = image.placement.width;
+        let h = image.placement.height;
+
+        if w == 0 || h == 0 {
+            return None;
+        }
+
+        let (data, out_w, out_h) = generate_sdf(&image.data, w, h, SDF_SPREAD);
+        let (x, y) = self.allocate(out_w, out_h)?;
+
+        self.pending_uploads.push((x, y, out_w, out_h, data));
+
+        let atlas = SDF_ATLAS_SIZE as f32;
+
+        Some(SdfGlyph {
+            left: image.placement.left as f32 - SDF_SPREAD as f32,
+            top: image.placement.top as f32 + SDF_SPREAD as f32,
+            width: out_w as f32,
+            height: out_h as f32,
+            uv: (
+                x as f32 / atlas,
+                y as f32 / atlas,
+                out_w as f32 / atlas,
+                out_h as f32 / atlas,
+            ),
+        })
+    }
+
+    fn allocate(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        if self.next_x + w + PADDING > SDF_ATLAS_SIZE {
+            self.next_x = PADDING;
+            self.next_y += self.row_height + PADDING;
+            self.row_height = 0;
+        }
+
+        if self.next_y + h + PADDING > SDF_ATLAS_SIZE {
+            log::warn!("SDF glyph atlas is full");
+            return None;
+        }
+
+        let position = (self.next_x, self.next_y);
+
+        self.next_x += w + PADDING;
+        self.row_height = self.row_height.max(h);
+
+        Some(position)
+    }
+}
+
+/// Строит поле расстояний из маски покрытия. Результат больше маски на
+/// spread с каждой стороны. Значение 0.5 (127) это край глифа, больше
+/// это внутренняя часть, меньше внешняя
+pub fn generate_sdf(mask: &[u8], width: u32, height: u32, spread: u32) -> (Vec<u8>, u32, u32) {
+    let out_w = width + spread * 2;
+    let out_h = height + spread * 2;
+    let len = (out_w * out_h) as usize;
+
+    let mut inside = vec![false; len];
+
+    for y in 0..height {
+        for x in 0..width {
+            let coverage = mask[(y * width + x) as usize];
+            inside[((y + spread) * out_w + x + spread) as usize] = coverage >= 128;
+        }
+    }
+
+    // Квадраты расстояний до ближайшего внутреннего и внешнего пикселя
+    let to_inside = squared_distance_transform(&inside, out_w, out_h, true);
+    let to_outside = squared_distance_transform(&inside, out_w, out_h, false);
+
+    let mut data = Vec::with_capacity(len);
+
+    for i in 0..len {
+        let distance = if inside[i] {
+            -(to_outside[i].sqrt() - 0.5)
+        } else {
+            to_inside[i].sqrt() - 0.5
+        };
+
+        let value = 0.5 - distance / (2.0 * spread as f32);
+        data.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+    }
+
+    (data, out_w, out_h)
+}
+
+/// Точное евклидово преобразование расстояний (Felzenszwalb, Huttenlocher).
+/// Считает для каждого пикселя квадрат расстояния до ближайшего пикселя у
+/// которого inside == target
+fn squared_distance_transform(inside: &[bool], width: u32, height: u32, target: bool) -> Vec<f32> {
+    const INF: f32 = 1e20;
+
+    let w = width as usize;
+    let h = height as usize;
+    let n = w.max(h);
+
+    let mut grid: Vec<f32> = inside.iter()
+        .map(|&value| if value == target { 0.0 } else { INF })
+        .collect();
+
+    let mut f = vec![0.0f32; n];
+    let mut d = vec![0.0f32; n];
+    let mut v = vec![0usize; n];
+    let mut z = vec![0.0f32; n + 1];
+
+    // Столбцы
+    for x in 0..w {
+        for y in 0..h {
+            f[y] = grid[y * w + x];
+        }
+
+        distance_transform_1d(&f[..h], &mut d[..h], &mut v, &mut z);
+
+        for y in 0..h {
+            grid[y * w + x] = d[y];
+        }
+    }
+
+    // Строки
+    for y in 0..h {
+        f[..w].copy_from_slice(&grid[y * w..(y + 1) * w]);
+        distance_transform_1d(&f[..w], &mut d[..w], &mut v, &mut z);
+        grid[y * w..(y + 1) * w].copy_from_slice(&d[..w]);
+    }
+
+    grid
+}
+
+fn distance_transform_1d(f: &[f32], d: &mut [f32], v: &mut [usize], z: &mut [f32]) {
+    let n = f.len();
+    if n == 0 {
+        return;
+    }
+
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = f32::NEG_INFINITY;
+    z[1] = f32::INFINITY;
+
+    for q in 1..n {
+        let mut s = intersect(f, q, v[k]);
+
+        // z[0] равен минус бесконечности, поэтому k не уходит ниже нуля
+        while s <= z[k] {
+            k -= 1;
+            s = intersect(f, q, v[k]);
+        }
+
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f32::INFINITY;
+    }
+
+    k = 0;
+    for (q, distance) in d.iter_mut().enumerate().take(n) {
+        let qf = q as f32;
+
+        while z[k + 1] < qf {
+            k += 1;
+        }
+
+        let p = v[k] as f32;
+        *distance = (qf - p) * (qf - p) + f[v[k]];
+    }
+}
+
+/// Точка пересечения парабол с вершинами в q и p
+#[inline(always)]
+fn intersect(f: &[f32], q: usize, p: usize) -> f32 {
+    let qf = q as f32;
+    let pf = p as f32;
+
+    ((f[q] + qf * qf) - (f[p] + pf * pf)) / (2.0 * qf - 2.0 * pf)
+}
+
+#[test]
+fn generate_sdf_test() {
+    // Квадрат 4x4 в маске 8x8
+    let mask: Vec<u8> = (0..64)
+        .map(|i| if (2..6).contains(&(i % 8)) && (2..6).contains(&(i / 8)) { 255 } else { 0 })
+        .collect();
+
+    let (data, w, h) = generate_sdf(&mask, 8, 8, SDF_SPREAD);
+    let spread = SDF_SPREAD as usize;
+
+    assert_eq!((w, h), (8 + SDF_SPREAD * 2, 8 + SDF_SPREAD * 2));
+
+    // Внутри глифа значения больше середины, снаружи меньше
+    for y in 0..8 {
+        for x in 0..8 {
+            let value = data[(y + spread) * w as usize + x + spread];
+            assert_eq!(value > 127, mask[y * 8 + x] == 255, "pixel {} {}", x, y);
+        }
+    }
+
+    // Квадрат в выходе начинается с x = spread + 2. Дальше spread от края
+    // поле равно нулю, ближе ещё нет
+    let row = (spread + 3) * w as usize;
+    assert!(data[row + 2] > 0);
+    assert_eq!(data[row + 1], 0);
+    assert_eq!(data[0], 0);
+}
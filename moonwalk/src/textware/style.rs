@@ -0,0 +1,333 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Типографика текста: высота строки, межбуквенный интервал, начертание и
+// OpenType фичи. Высота строки и начертание передаются в cosmic-text как
+// есть, а межбуквенный интервал и табличные цифры cosmic-text 0.11 не умеет,
+// поэтому они применяются после раскладки: для каждого глифа считается итоговый
+// прямоугольник (GlyphBox), и все кто читает раскладку (рендер, каретка,
+// выделение, измерение) берут позиции оттуда, а не из LayoutGlyph
+
+use cosmic_text::{Attrs, Buffer, Stretch, Style, Weight};
+
+/// Максимальное количество OpenType фич у одного текста
+pub const MAX_FONT_FEATURES: usize = 8;
+
+// Фичи которые влияют на раскладку. cosmic-text 0.11 не передаёт фичи в
+// шейпинг, поэтому работают только те что применяются после раскладки
+const SUPPORTED_FONT_FEATURES: &[[u8; 4]] = &[*b"tnum"];
+
+/// Набор OpenType фич (тег и значение). Хранится массивом фиксированного
+/// размера чтобы TextStyle оставался Copy и дёшево сравнивался в кэше
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct FontFeatures {
+    items: [([u8; 4], u16); MAX_FONT_FEATURES],
+    len: u8,
+}
+
+impl FontFeatures {
+    /// Разбирает фичи в формате CSS/HarfBuzz: "tnum", "tnum=1", "liga=0",
+    /// "+kern", "-liga". Некорректные записи пропускаются с предупреждением.
+    /// Фичи не из SUPPORTED_FONT_FEATURES сохраняются, но ни на что не
+    /// влияют, о каждой такой пишется предупреждение
+    pub fn parse(features: &[&str]) -> Self {
+        let mut result = Self::default();
+
+        for feature in features {
+            let feature = feature.trim();
+
+            let (tag, value) = if let Some(tag) = feature.strip_prefix('-') {
+                (tag, Some(0))
+            } else if let Some(tag) = feature.strip_prefix('+') {
+                (tag, Some(1))
+            } else if let Some((tag, value)) = feature.split_once('=') {
+                (tag.trim(), value.trim().parse::<u16>().ok())
+            } else {
+                (feature, Some(1))
+            };
+
+            let (Ok(tag), Some(value)) = (<[u8; 4]>::try_from(tag.as_bytes()), value) else {
+                log::warn!("Invalid font feature: {:?}", feature);
+                continue;
+            };
+
+            if !SUPPORTED_FONT_FEATURES.contains(&tag) {
+                log::warn!("Font feature {:?} is not supported and will be ignored, only tnum works", feature);
+            }
+
+            result.set(tag, value);
+        }
+
+        result
+    }
+
+    /// Значение фичи, None если фича не задана
+    pub fn get(&self, tag: &[u8; 4]) -> Option<u16> {
+        self.iter().find(|(t, _)| t == tag).map(|(_, value)| value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ([u8; 4], u16)> + '_ {
+        self.items[..self.len as usize].iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn set(&mut self, tag: [u8; 4], value: u16) {
+        if let Some(item) = self.items[..self.len as usize].iter_mut().find(|(t, _)| *t == tag) {
+            item.1 = value;
+            return;
+        }
+
+        if (self.len as usize) < MAX_FONT_FEATURES {
+            self.items[self.len as usize] = (tag, value);
+            self.len += 1;
+        } else {
+            log::warn!("Too many font features, max is {}", MAX_FONT_FEATURES);
+        }
+    }
+}
+
+/// Типографика текстового объекта. Входит в TextParams, поэтому любое
+/// изменение сбрасывает кэш раскладки
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextStyle {
+    /// Высота строки в пикселях, 0.0 означает 1.2 от размера шрифта
+    pub line_height: f32,
+
+    /// Дополнительное расстояние после каждого глифа в пикселях
+    pub letter_spacing: f32,
+
+    /// Насыщенность (ось wght), 400 обычный, 700 жирный
+    pub weight: u16,
+
+    /// 0 - обычный, 1 - курсив, 2 - наклонный
+    pub style: u8,
+
+    /// Ширина (ось wdth) в процентах, 100 обычная
+    pub width: f32,
+
+    pub features: FontFeatures,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            line_height: 0.0,
+            letter_spacing: 0.0,
+            weight: 400,
+            style: 0,
+            width: 100.0,
+            features: FontFeatures::default(),
+        }
+    }
+}
+
+impl TextStyle {
+    pub fn line_height(&self, font_size: f32) -> f32 {
+        if self.line_height > 0.0 {
+            self.line_height
+        } else {
+            font_size * 1.2
+        }
+    }
+
+    /// Табличные цифры (tnum): все цифры одной ширины
+    pub fn tabular_numbers(&self) -> bool {
+        self.features.get(b"tnum").is_some_and(|value| value != 0)
+    }
+
+    /// Нужна ли раскладке правка позиций глифов после cosmic-text
+    pub fn needs_placement(&self) -> bool {
+        self.letter_spacing != 0.0 || self.tabular_numbers()
+    }
+
+    /// Ось вариативного шрифта: wght это насыщенность, wdth ширина в
+    /// процентах. Остальные оси не поддерживаются
+    pub fn set_variation(&mut self, axis: &str, value: f32) {
+        match axis {
+            "wght" => self.weight = value.round().clamp(1.0, 1000.0) as u16,
+            "wdth" => self.width = value.max(1.0),
+            _ => log::warn!("Unsupported font variation axis: {:?}", axis),
+        }
+    }
+
+    /// Применяет насыщенность, начертание и ширину к атрибутам. Для
+    /// вариативных шрифтов ближайшее начертание выбирается через fontdb
+    pub fn apply<'a>(&self, attrs: Attrs<'a>) -> Attrs<'a> {
+        let style = match self.style {
+            1 => Style::Italic,
+            2 => Style::Oblique,
+            _ => Style::Normal,
+        };
+
+        attrs
+            .weight(Weight(self.weight))
+            .style(style)
+            .stretch(stretch_from_width(self.width))
+    }
+}
+
+/// Ближайшее значение Stretch для ширины в процентах (ось wdth)
+fn stretch_from_width(width: f32) -> Stretch {
+    const STRETCHES: [(f32, Stretch); 9] = [
+        (50.0, Stretch::UltraCondensed),
+        (62.5, Stretch::ExtraCondensed),
+        (75.0, Stretch::Condensed),
+        (87.5, Stretch::SemiCondensed),
+        (100.0, Stretch::Normal),
+        (112.5, Stretch::SemiExpanded),
+        (125.0, Stretch::Expanded),
+        (150.0, Stretch::ExtraExpanded),
+        (200.0, Stretch::UltraExpanded),
+    ];
+
+    STRETCHES.iter()
+        .min_by(|a, b| (a.0 - width).abs().total_cmp(&(b.0 - width).abs()))
+        .map_or(Stretch::Normal, |(_, stretch)| *stretch)
+}
+
+/// Итоговое положение глифа: прямоугольник для каретки и выделения (x, w) и
+/// x с которого глиф рисуется
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlyphBox {
+    pub x: f32,
+    pub w: f32,
+    pub draw_x: f32,
+}
+
+/// Итоговая раскладка одной визуальной строки, глифы в том же порядке что
+/// и в LayoutRun::glyphs
+#[derive(Clone, Debug, Default)]
+pub struct RunPlacement {
+    pub boxes: Vec<GlyphBox>,
+    pub line_w: f32,
+}
+
+/// Доля сдвига строки при её расширении: 0 строка растёт вправо, 1 влево,
+/// 0.5 в обе стороны. Повторяет привязку выравнивания из shape_buffer: Left
+/// и Right абсолютные, Start и End зависят от направления строки
+pub(crate) fn anchor_factor(align: u8, rtl: bool) -> f32 {
+    match (align, rtl) {
+        (1, _) => 0.5,
+        (2, _) => 1.0,
+        (4, true) => 1.0,
+        (5, false) => 1.0,
+        _ => 0.0,
+    }
+}
+
+/// Глиф пробела, у выравнивания по ширине растягиваются только они
+fn is_blank(text: &str, start: usize, end: usize) -> bool {
+    text.get(start..end)
+        .is_some_and(|s| !s.is_empty() && s.chars().all(|c| c == ' ' || c == '\u{a0}'))
+}
+
+/// Считает итоговые позиции глифов всех строк буфера. digit_width это
+/// ширина табличной цифры (None если tnum выключен), wrap_correction это
+/// на сколько была уменьшена ширина переноса чтобы строки с интервалом
+/// влезли в границы, на эту величину строки сдвигаются обратно по выравниванию.
+/// Текст по ширине cosmic-text в этом случае раскладывает влево, а строки
+/// (кроме последней в абзаце) растягиваются тут до max_width. Последняя
+/// строка прижимается к началу: у RTL абзаца это правый край
+pub fn place_runs(
+    buffer: &Buffer,
+    style: &TextStyle,
+    align: u8,
+    max_width: f32,
+    digit_width: Option<f32>,
+    wrap_correction: f32,
+) -> Vec<RunPlacement> {
+    let mut placements = Vec::new();
+    let mut runs = buffer.layout_runs().peekable();
+
+    while let Some(run) = runs.next() {
+        if !style.needs_placement() {
+            placements.push(RunPlacement {
+                boxes: run.glyphs.iter().map(|g| GlyphBox { x: g.x, w: g.w, draw_x: g.x }).collect(),
+                line_w: run.line_w,
+            });
+
+            continue;
+        }
+
+        let last_in_paragraph = runs.peek().is_none_or(|next| next.line_i != run.line_i);
+
+        // Ширина каждого глифа после интервала и табличных цифр
+        let advances: Vec<(f32, f32)> = run.glyphs.iter()
+            .map(|glyph| {
+                let is_digit = run.text.get(glyph.start..glyph.end)
+                    .is_some_and(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()));
+
+                // Табличная цифра занимает фиксированную ширину, сам глиф по центру
+                let (advance, inset) = match digit_width {
+                    Some(width) if is_digit => (width, (width - glyph.w) * 0.5),
+                    _ => (glyph.w, 0.0),
+                };
+
+                (advance + style.letter_spacing, inset)
+            })
+            .collect();
+
+        let grown: f32 = run.glyphs.iter().zip(advances.iter()).map(|(g, (w, _))| w - g.w).sum();
+
+        // Для строки по ширине остаток до max_width делится между пробелами.
+        // Если строка с интервалом не влезает, пробелы не сжимаются: тогда
+        // process_text уменьшит ширину переноса
+        let blanks = run.glyphs.iter().filter(|g| is_blank(run.text, g.start, g.end)).count();
+        let justify = align == 3 && !last_in_paragraph && blanks > 0;
+
+        let expansion = if justify {
+            ((max_width - run.line_w - grown) / blanks as f32).max(0.0)
+        } else {
+            0.0
+        };
+
+        // Глифы RTL строк лежат в логическом порядке, а сдвиги копятся
+        // слева направо, поэтому идём по визуальному порядку
+        let mut order: Vec<usize> = (0..run.glyphs.len()).collect();
+        order.sort_by(|&a, &b| run.glyphs[a].x.total_cmp(&run.glyphs[b].x));
+
+        let mut boxes = vec![GlyphBox { x: 0.0, w: 0.0, draw_x: 0.0 }; run.glyphs.len()];
+        let mut shift = 0.0;
+
+        for &i in order.iter() {
+            let glyph = &run.glyphs[i];
+            let (mut w, inset) = advances[i];
+
+            if justify && is_blank(run.text, glyph.start, glyph.end) {
+                w += expansion;
+            }
+
+            boxes[i] = GlyphBox {
+                x: glyph.x + shift,
+                w,
+                draw_x: glyph.x + shift + inset,
+            };
+
+            shift += w - glyph.w;
+        }
+
+        let offset = if align == 3 {
+            let left = order.first().map_or(0.0, |&i| run.glyphs[i].x);
+            let target = if run.rtl && !justify { max_width - run.line_w - shift } else { 0.0 };
+
+            target - left
+        } else {
+            anchor_factor(align, run.rtl) * (wrap_correction - shift)
+        };
+
+        for glyph_box in boxes.iter_mut() {
+            glyph_box.x += offset;
+            glyph_box.draw_x += offset;
+        }
+
+        placements.push(RunPlacement {
+            boxes,
+            line_w: run.line_w + shift,
+        });
+    }
+
+    placements
+}
@@ -0,0 +1,122 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Текст вдоль пути. Путь один раз переводится в ломаную (lyon flattening), а
+// при сборке глифов каждый глиф ставится серединой своей ширины на ломаную и
+// поворачивается по касательной. Раскладка (перенос, интервал, табличные
+// цифры) остаётся обычной, меняется только то куда глифы рисуются, поэтому
+// кэш буферов про путь ничего не знает
+
+use glam::Vec2;
+use lyon::path::iterator::PathIterator;
+use lyon::path::{Event, Path};
+
+/// Путь для текста: ломаная с накопленной длиной
+#[derive(Clone, Debug)]
+pub struct TextPath {
+    points: Vec<Vec2>,
+
+    // Длина пути от начала до каждой точки
+    lengths: Vec<f32>,
+    closed: bool,
+
+    /// Сдвиг начала текста вдоль пути в пикселях
+    pub start_offset: f32,
+
+    /// Сдвиг базовой линии от пути, положительный поднимает текст
+    pub baseline_offset: f32,
+}
+
+impl TextPath {
+    /// Переводит путь в ломаную с точностью tolerance. Учитывается только
+    /// первый подпуть, путь замкнут если первый подпуть закрыт
+    pub fn new(path: &Path, tolerance: f32) -> Self {
+        let mut points: Vec<Vec2> = Vec::new();
+        let mut closed = false;
+
+        for event in path.iter().flattened(tolerance.max(0.01)) {
+            match event {
+                Event::Begin { at } => {
+                    if !points.is_empty() {
+                        break;
+                    }
+
+                    points.push(Vec2::new(at.x, at.y));
+                }
+
+                Event::Line { to, .. } => points.push(Vec2::new(to.x, to.y)),
+
+                Event::End { first, close, .. } => {
+                    if close {
+                        points.push(Vec2::new(first.x, first.y));
+                        closed = true;
+                    }
+
+                    break;
+                }
+
+                _ => {}
+            }
+        }
+
+        // Нулевые отрезки не дают направления, их выкидываем
+        points.dedup_by(|a, b| a.distance_squared(*b) < 1e-8);
+
+        let mut lengths = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                total += point.distance(points[i - 1]);
+            }
+
+            lengths.push(total);
+        }
+
+        Self {
+            points,
+            lengths,
+            closed,
+            start_offset: 0.0,
+            baseline_offset: 0.0,
+        }
+    }
+
+    /// Длина пути в пикселях
+    pub fn length(&self) -> f32 {
+        self.lengths.last().copied().unwrap_or(0.0)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Точка и угол касательной на расстоянии distance от начала. На
+    /// замкнутом пути расстояние берётся по модулю длины, на открытом
+    /// за пределами пути возвращается None
+    pub fn sample(&self, distance: f32) -> Option<(Vec2, f32)> {
+        let length = self.length();
+
+        if self.points.len() < 2 || length <= 0.0 {
+            return None;
+        }
+
+        let distance = if self.closed {
+            distance.rem_euclid(length)
+        } else if (0.0..=length).contains(&distance) {
+            distance
+        } else {
+            return None;
+        };
+
+        let segment = self.lengths.partition_point(|&l| l <= distance).clamp(1, self.points.len() - 1);
+
+        let (a, b) = (self.points[segment - 1], self.points[segment]);
+        let (la, lb) = (self.lengths[segment - 1], self.lengths[segment]);
+
+        let t = ((distance - la) / (lb - la).max(f32::EPSILON)).clamp(0.0, 1.0);
+        let direction = b - a;
+
+        Some((a + direction * t, direction.y.atan2(direction.x)))
+    }
+}
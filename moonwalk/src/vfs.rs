@@ -0,0 +1,313 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+// Виртуальная файловая система для ресурсов. В неё монтируются папки, zip
+// архивы (.pak) и наборы файлов в памяти (include_bytes!). Каждая точка
+// монтирования имеет приоритет: при чтении сначала спрашиваются источники с
+// большим приоритетом, при равном приоритете побеждает смонтированный позже.
+// Так моды и патчи перекрывают файлы основного архива без его пересборки.
+// Логический путь везде одинаковый: разделитель "/", без "." и "..", регистр
+// учитывается
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::error::MoonWalkError;
+
+/// Айди точки монтирования, нужен для unmount
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MountId(pub u32);
+
+/// Источник файлов для монтирования. Пути внутри источника относительные и
+/// уже нормализованы (через normalize_path)
+pub trait VfsSource: Send + Sync {
+    /// Читает файл. None если в источнике такого файла нет
+    fn read(&self, path: &str) -> Option<Result<Vec<u8>, MoonWalkError>>;
+
+    fn exists(&self, path: &str) -> bool;
+
+    /// Все файлы источника (без папок)
+    fn files(&self) -> Vec<String>;
+
+    /// Путь файла в настоящей файловой системе, если он там есть. Нужен
+    /// для слежения за изменениями (hot-reload)
+    fn real_path(&self, _path: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Папка реальной файловой системы
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl VfsSource for DirectorySource {
+    fn read(&self, path: &str) -> Option<Result<Vec<u8>, MoonWalkError>> {
+        let full = self.root.join(path);
+
+        if !full.is_file() {
+            return None;
+        }
+
+        Some(std::fs::read(full).map_err(|e| MoonWalkError::IOError(e.to_string())))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.root.join(path).is_file()
+    }
+
+    fn files(&self) -> Vec<String> {
+        let mut files = Vec::new();
+        let mut stack = vec![(self.root.clone(), String::new())];
+
+        while let Some((dir, prefix)) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let logical = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+
+                match entry.file_type() {
+                    Ok(kind) if kind.is_dir() => stack.push((entry.path(), logical)),
+                    Ok(_) => files.push(logical),
+                    Err(_) => {}
+                }
+            }
+        }
+
+        files
+    }
+
+    fn real_path(&self, path: &str) -> Option<PathBuf> {
+        Some(self.root.join(path))
+    }
+}
+
+/// Zip архив целиком в памяти. Оглавление читается один раз при создании,
+/// файлы распаковываются при каждом чтении
+pub struct ZipSource {
+    archive: Mutex<zip::ZipArchive<Cursor<Vec<u8>>>>,
+
+    // Нормализованные имена файлов и их индексы в архиве
+    entries: HashMap<String, usize>,
+}
+
+impl ZipSource {
+    pub fn new(bytes: Vec<u8>) -> Result<Self, MoonWalkError> {
+        let archive = zip::ZipArchive::new(Cursor::new(bytes))
+            .map_err(|e| MoonWalkError::IOError(format!("Invalid zip archive: {}", e)))?;
+
+        let entries = archive.file_names()
+            .filter(|name| !name.ends_with('/'))
+            .filter_map(|name| Some((normalize_path(name), archive.index_for_name(name)?)))
+            .collect();
+
+        Ok(Self { archive: Mutex::new(archive), entries })
+    }
+}
+
+impl VfsSource for ZipSource {
+    fn read(&self, path: &str) -> Option<Result<Vec<u8>, MoonWalkError>> {
+        let index = *self.entries.get(path)?;
+        let mut archive = self.archive.lock();
+
+        let result = archive.by_index(index)
+            .map_err(|e| MoonWalkError::IOError(e.to_string()))
+            .and_then(|mut file| {
+                let mut bytes = Vec::with_capacity(file.size() as usize);
+
+                file.read_to_end(&mut bytes)
+                    .map(|_| bytes)
+                    .map_err(|e| MoonWalkError::IOError(e.to_string()))
+            });
+
+        Some(result)
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    fn files(&self) -> Vec<String> {
+        self.entries.keys().cloned().collect()
+    }
+}
+
+/// Файлы в памяти. Удобно для ассетов вшитых через include_bytes! (они не
+/// копируются) и для тестов
+#[derive(Default)]
+pub struct MemorySource {
+    files: HashMap<String, Cow<'static, [u8]>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет файл, существующий файл с тем же путём заменяется
+    pub fn insert(&mut self, path: &str, bytes: impl Into<Cow<'static, [u8]>>) {
+        self.files.insert(normalize_path(path), bytes.into());
+    }
+
+    /// То же что insert, но в стиле билдера
+    pub fn with_file(mut self, path: &str, bytes: impl Into<Cow<'static, [u8]>>) -> Self {
+        self.insert(path, bytes);
+        self
+    }
+}
+
+impl VfsSource for MemorySource {
+    fn read(&self, path: &str) -> Option<Result<Vec<u8>, MoonWalkError>> {
+        self.files.get(path).map(|bytes| Ok(bytes.to_vec()))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+
+    fn files(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+}
+
+#[derive(Clone)]
+struct Mount {
+    id: MountId,
+    point: String,
+    priority: i32,
+    source: Arc<dyn VfsSource>,
+}
+
+/// Набор точек монтирования. Клонирование дешёвое (источники общие), так
+/// что снимок можно отдать в другой поток
+#[derive(Clone, Default)]
+pub struct Vfs {
+    // Отсортированы в порядке опроса: приоритет по убыванию, потом новые
+    mounts: Vec<Mount>,
+    next_id: u32,
+}
+
+impl Vfs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Монтирует источник в папку point ("" это корень)
+    pub fn mount(&mut self, point: &str, source: impl VfsSource + 'static, priority: i32) -> MountId {
+        let id = MountId(self.next_id);
+        self.next_id += 1;
+
+        self.mounts.push(Mount {
+            id,
+            point: normalize_path(point),
+            priority,
+            source: Arc::new(source),
+        });
+
+        self.mounts.sort_by(|a, b| b.priority.cmp(&a.priority).then(b.id.0.cmp(&a.id.0)));
+
+        id
+    }
+
+    /// Убирает точку монтирования. false если её уже нет
+    pub fn unmount(&mut self, id: MountId) -> bool {
+        let count = self.mounts.len();
+        self.mounts.retain(|m| m.id != id);
+
+        self.mounts.len() != count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mounts.is_empty()
+    }
+
+    /// Читает файл из первого источника где он есть. None если файла нет ни
+    /// в одной точке монтирования
+    pub fn read(&self, path: &str) -> Option<Result<Vec<u8>, MoonWalkError>> {
+        let path = normalize_path(path);
+
+        self.mounts.iter()
+            .filter_map(|m| Some((m, relative(&m.point, &path)?)))
+            .find_map(|(m, rel)| m.source.read(rel))
+    }
+
+    pub fn exists(&self, path: &str) -> bool {
+        let path = normalize_path(path);
+
+        self.mounts.iter()
+            .any(|m| relative(&m.point, &path).is_some_and(|rel| m.source.exists(rel)))
+    }
+
+    /// Путь в настоящей файловой системе для файла который читает read.
+    /// Внешний None значит что файла нет в точках монтирования, внутренний
+    /// что файл есть, но лежит в архиве или в памяти
+    pub fn real_path(&self, path: &str) -> Option<Option<PathBuf>> {
+        let path = normalize_path(path);
+
+        self.mounts.iter()
+            .filter_map(|m| Some((m, relative(&m.point, &path)?)))
+            .find(|(m, rel)| m.source.exists(rel))
+            .map(|(m, rel)| m.source.real_path(rel))
+    }
+
+    /// Все файлы в папке dir и её подпапках по всем точкам монтирования,
+    /// без повторов и по алфавиту. "" перечисляет всё
+    pub fn list(&self, dir: &str) -> Vec<String> {
+        let dir = normalize_path(dir);
+        let mut result = BTreeSet::new();
+
+        for mount in &self.mounts {
+            for file in mount.source.files() {
+                let logical = match mount.point.is_empty() {
+                    true => file,
+                    false => format!("{}/{}", mount.point, file),
+                };
+
+                if relative(&dir, &logical).is_some() {
+                    result.insert(logical);
+                }
+            }
+        }
+
+        result.into_iter().collect()
+    }
+}
+
+/// Приводит путь к логическому виду: "\" и "/" одинаковы, пустые части и
+/// "." убираются, ".." поднимается на уровень вверх (но не выше корня)
+pub fn normalize_path(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+
+    for part in path.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => { parts.pop(); }
+            part => parts.push(part),
+        }
+    }
+
+    parts.join("/")
+}
+
+// Путь внутри точки монтирования point или None если путь не в ней
+fn relative<'a>(point: &str, path: &'a str) -> Option<&'a str> {
+    if point.is_empty() {
+        return Some(path);
+    }
+
+    path.strip_prefix(point)?.strip_prefix('/')
+}
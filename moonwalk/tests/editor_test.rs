@@ -0,0 +1,134 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use moonwalk::FontAsset;
+use moonwalk::public::TextEditor;
+
+fn editor() -> TextEditor {
+    TextEditor::new(FontAsset(0), 16.0)
+}
+
+#[test]
+fn test_insert_and_delete() {
+    let mut editor = editor();
+
+    editor.insert("Hello");
+    editor.insert(" world");
+    assert_eq!(editor.text(), "Hello world");
+    assert_eq!(editor.cursor().index, 11);
+
+    editor.backspace();
+    assert_eq!(editor.text(), "Hello worl");
+
+    editor.set_cursor(0);
+    editor.delete();
+    assert_eq!(editor.text(), "ello worl");
+    assert_eq!(editor.cursor().index, 0);
+}
+
+#[test]
+fn test_graphemes_are_deleted_whole() {
+    let mut editor = editor();
+
+    // e + комбинируемый акцент и эмодзи с модификатором это одна графема
+    editor.insert("ae\u{301}👍🏽");
+    editor.backspace();
+    assert_eq!(editor.text(), "ae\u{301}");
+
+    editor.backspace();
+    assert_eq!(editor.text(), "a");
+}
+
+#[test]
+fn test_selection_replace_and_clipboard() {
+    let mut editor = editor();
+    editor.insert("Hello world");
+
+    editor.set_selection(6..11);
+    assert_eq!(editor.selected_text(), Some("world"));
+    assert_eq!(editor.copy(), Some("world".to_string()));
+
+    editor.insert("there");
+    assert_eq!(editor.text(), "Hello there");
+    assert!(editor.selection().is_none());
+
+    editor.select_all();
+    assert_eq!(editor.cut(), Some("Hello there".to_string()));
+    assert_eq!(editor.text(), "");
+
+    editor.on_paste = Some(Box::new(|| Some("pasted".to_string())));
+    editor.paste();
+    assert_eq!(editor.text(), "pasted");
+}
+
+#[test]
+fn test_undo_redo_coalesces_typing() {
+    let mut editor = editor();
+
+    for c in ["a", "b", "c"] {
+        editor.insert(c);
+    }
+
+    editor.paste_text(" def");
+    assert_eq!(editor.text(), "abc def");
+
+    editor.undo();
+    assert_eq!(editor.text(), "abc");
+
+    // Набор по одному символу отменяется одним шагом
+    editor.undo();
+    assert_eq!(editor.text(), "");
+    assert!(!editor.can_undo());
+
+    editor.redo();
+    editor.redo();
+    assert_eq!(editor.text(), "abc def");
+    assert!(!editor.can_redo());
+}
+
+#[test]
+fn test_max_length_and_single_line() {
+    let mut editor = editor();
+    editor.set_max_length(Some(5));
+
+    editor.insert("one\ntwo");
+    assert_eq!(editor.text(), "one t");
+
+    editor.insert("x");
+    assert_eq!(editor.text(), "one t");
+
+    editor.set_multiline(true);
+    editor.set_max_length(None);
+    editor.insert("\r\nthree");
+    assert_eq!(editor.text(), "one t\nthree");
+}
+
+#[test]
+fn test_password_mask() {
+    let mut editor = editor();
+    editor.set_password_mask(Some('•'));
+    editor.insert("pa\u{301}ss");
+
+    assert_eq!(editor.display_text(), "••••");
+
+    // Пароль не копируется, но вырезание всё равно удаляет выделение
+    editor.select_all();
+    assert_eq!(editor.copy(), None);
+    assert_eq!(editor.cut(), None);
+    assert_eq!(editor.text(), "");
+}
+
+#[test]
+fn test_preedit_is_not_part_of_text() {
+    let mut editor = editor();
+    editor.insert("ab");
+    editor.set_cursor(1);
+
+    editor.set_preedit("にほ", None);
+    assert_eq!(editor.text(), "ab");
+    assert_eq!(editor.display_text(), "aにほb");
+
+    editor.commit("日本");
+    assert_eq!(editor.preedit(), None);
+    assert_eq!(editor.text(), "a日本b");
+}
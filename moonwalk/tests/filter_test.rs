@@ -0,0 +1,75 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use moonwalk::{FilterChain, FilterInput, FilterOp};
+
+#[test]
+fn test_filter_chain_steps() {
+    let mut chain = FilterChain::new();
+    assert!(chain.is_empty());
+    assert_eq!(chain.output(), None);
+
+    let blur = chain.push(FilterOp::blur(4.0));
+    let dark = chain.push(FilterOp::brightness(0.5));
+
+    assert_eq!(chain.len(), 2);
+    assert_eq!(blur.index(), 0);
+    assert_eq!(chain.output(), Some(dark));
+    assert_eq!(chain.step(1), Some(dark));
+    assert_eq!(chain.step(2), None);
+
+    // Параметры меняются без пересборки цепочки
+    assert!(chain.set(blur, FilterOp::blur(8.0)));
+    assert_eq!(chain.op(blur), Some(&FilterOp::Blur { radius: 8.0 }));
+
+    if let Some(FilterOp::Blur { radius }) = chain.op_mut(blur) {
+        *radius = 2.0;
+    }
+
+    assert_eq!(chain.op(blur), Some(&FilterOp::blur(2.0)));
+
+    // Ветка от исходной текстуры и явный выход
+    let mask = chain.push_from(FilterInput::Source, FilterOp::mask(FilterInput::Step(dark), true));
+    chain.set_output(blur);
+
+    assert_eq!(chain.output(), Some(blur));
+    assert_eq!(chain.op(mask), Some(&FilterOp::Mask { mask: FilterInput::Step(dark), invert: true }));
+
+    let built = FilterChain::new()
+        .then(FilterOp::saturation(0.0))
+        .then(FilterOp::contrast(1.5));
+
+    assert_eq!(built.len(), 2);
+}
+
+#[test]
+fn test_filter_effects() {
+    use glam::{Vec2, Vec4};
+
+    assert_eq!(
+        FilterOp::bloom(0.8, 1.5),
+        FilterOp::Bloom { threshold: 0.8, intensity: 1.5, radius: 2.0, levels: 4 }
+    );
+
+    assert_eq!(
+        FilterOp::vignette(0.5, 0.3),
+        FilterOp::Vignette { strength: 0.5, radius: 0.3, softness: 0.45, color: Vec4::new(0.0, 0.0, 0.0, 1.0) }
+    );
+
+    // Эффекты ставятся в цепочку как и остальные операции
+    let mut chain = FilterChain::new()
+        .then(FilterOp::drop_shadow(Vec2::new(4.0, 4.0), 3.0, Vec4::new(0.0, 0.0, 0.0, 0.6)))
+        .then(FilterOp::sharpen(1.0, 1.0))
+        .then(FilterOp::pixelate(8.0))
+        .then(FilterOp::film_grain(0.1, 0.0))
+        .then(FilterOp::edge_detect(1.0));
+
+    let grain = chain.step(3).unwrap();
+
+    if let Some(FilterOp::FilmGrain { seed, .. }) = chain.op_mut(grain) {
+        *seed = 42.0;
+    }
+
+    assert_eq!(chain.len(), 5);
+    assert_eq!(chain.op(grain), Some(&FilterOp::film_grain(0.1, 42.0)));
+}
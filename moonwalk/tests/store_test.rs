@@ -5,6 +5,7 @@ use glam::{Vec2, Vec4};
 use std::time::Instant;
 use moonwalk::objects::ObjectType;
 use moonwalk::objects::store::ObjectStore;
+use moonwalk::path::PathBuilder;
 
 #[test]
 fn test_rect_creation_and_config() {
@@ -155,4 +156,99 @@ fn test_texture_id_assignment() {
     
     assert_eq!(store.texture_ids[idx], 5);
     assert!(store.dirty);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_text_direction_reset_on_reuse() {
+    let mut store = ObjectStore::new();
+    let id = store.new_rect();
+
+    store.dirty = false;
+    store.set_text_direction(id, 2);
+
+    assert!(store.dirty);
+    assert_eq!(store.get_text_direction(id), 2);
+    assert_eq!(store.text_params(id.index()).direction, 2);
+
+    // Переиспользованный слот не должен унаследовать направление
+    store.remove(id);
+    let reused = store.new_rect();
+
+    assert_eq!(reused.index(), id.index());
+    assert_eq!(store.get_text_direction(reused), 0);
+}
+
+#[test]
+fn test_text_effects() {
+    let mut store = ObjectStore::new();
+    let id = store.new_rect();
+    let idx = id.index();
+
+    assert!(!store.has_text_effects(idx));
+
+    store.set_text_outline(id, Some((2.0, Vec4::new(0.0, 0.0, 0.0, 1.0))));
+    store.set_text_shadow(id, Some((Vec2::new(3.0, 4.0), 1.5, Vec4::new(0.0, 0.0, 0.0, 0.5))));
+    store.set_text_glow(id, Some((6.0, Vec4::new(1.0, 1.0, 0.0, 1.0))));
+
+    assert!(store.has_text_effects(idx));
+    assert_eq!(store.text_shadows[idx], Some((Vec2::new(3.0, 4.0), 1.5, Vec4::new(0.0, 0.0, 0.0, 0.5))));
+
+    // Тень убирается отдельно от остальных эффектов
+    store.dirty = false;
+    store.set_text_shadow(id, None);
+
+    assert!(store.dirty);
+    assert_eq!(store.text_shadows[idx], None);
+    assert_eq!(store.text_outlines[idx], Some((2.0, Vec4::new(0.0, 0.0, 0.0, 1.0))));
+    assert!(store.has_text_effects(idx));
+
+    store.set_text_outline(id, None);
+    store.set_text_glow(id, None);
+    assert!(!store.has_text_effects(idx));
+
+    // Переиспользованный слот приходит без эффектов
+    store.set_text_glow(id, Some((6.0, Vec4::ONE)));
+    store.remove(id);
+    let reused = store.new_rect();
+
+    assert_eq!(reused.index(), idx);
+    assert!(!store.has_text_effects(idx));
+}
+
+#[test]
+fn test_path_object_bounds_and_reuse() {
+    let mut store = ObjectStore::new();
+
+    let mut builder = PathBuilder::new();
+    builder.move_to(10.0, 20.0);
+    builder.line_to(50.0, 20.0);
+    builder.line_to(50.0, 80.0);
+    builder.close();
+
+    let id = store.new_path(builder.into_shape());
+    assert_eq!(id.get_type(), Some(ObjectType::Path));
+
+    // Объект стоит на месте рамки пути
+    assert_eq!(store.get_position(id), Vec2::new(10.0, 20.0));
+    assert_eq!(store.get_size(id), Vec2::new(40.0, 60.0));
+
+    // Новая геометрия не меняет рамку объекта
+    let mut smaller = PathBuilder::new();
+    smaller.move_to(0.0, 0.0);
+    smaller.line_to(5.0, 5.0);
+    smaller.line_to(0.0, 5.0);
+    smaller.close();
+
+    store.dirty = false;
+    store.set_path_shape(id, smaller.into_shape());
+
+    assert!(store.dirty);
+    assert_eq!(store.get_path_shape(id).unwrap().bounds(), (Vec2::new(10.0, 20.0), Vec2::new(40.0, 60.0)));
+
+    // Переиспользованный слот не должен унаследовать путь
+    store.remove(id);
+    let reused = store.new_rect();
+
+    assert_eq!(reused.index(), id.index());
+    assert!(store.get_path_shape(reused).is_none());
+}
@@ -25,4 +25,151 @@ let mut img_buf = Vec::new();
     let loaded = image::load_from_memory(&img_buf).unwrap();
     assert_eq!(loaded.width(), 1);
     assert_eq!(loaded.height(), 1);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_atlas_packing() {
+    use moonwalk::TextureAtlas;
+    use moonwalk::rendering::atlas::parse_atlas_json;
+
+    let mut atlas = TextureAtlas::new().max_size(64).padding(2).extrude(1);
+
+    for i in 0..12u8 {
+        atlas.add_image(&format!("icon{}", i), image::RgbaImage::from_pixel(16, 16, image::Rgba([i, 0, 0, 255])));
+    }
+
+    atlas.add_image("wide", image::RgbaImage::from_pixel(40, 8, image::Rgba([255, 255, 0, 255])));
+
+    let layout = atlas.pack().unwrap();
+
+    // 12 иконок 16x16 с краями по 20 пикселей: 9 на первой странице 64x64
+    assert_eq!(layout.pages.len(), 2);
+    assert!(layout.pages.iter().all(|page| page.width() <= 64 && page.height() <= 64));
+
+    let (page, [x, y, w, h]) = layout.sprites["icon3"];
+    let image = &layout.pages[page];
+    assert_eq!((w, h), (16, 16));
+    assert_eq!(image.get_pixel(x, y)[0], 3);
+
+    // Крайние пиксели повторены на 1 пиксель, дальше прозрачный зазор
+    assert_eq!(image.get_pixel(x - 1, y - 1)[0], 3);
+    assert_eq!(image.get_pixel(x + w, y)[3], 255);
+
+    if x + w + 1 < image.width() {
+        assert_eq!(image.get_pixel(x + w + 1, y)[3], 0);
+    }
+
+    // Прямоугольники спрайтов с краями не пересекаются
+    let rects: Vec<_> = layout.sprites.values().collect();
+    for (i, (pa, a)) in rects.iter().enumerate() {
+        for (pb, b) in &rects[i + 1..] {
+            let apart = a[0] + a[2] + 1 < b[0] || b[0] + b[2] + 1 < a[0]
+                || a[1] + a[3] + 1 < b[1] || b[1] + b[3] + 1 < a[1];
+            assert!(pa != pb || apart, "{:?} overlaps {:?}", a, b);
+        }
+    }
+
+    let files: Vec<String> = (0..layout.pages.len()).map(|i| format!("ui_{}.png", i)).collect();
+    let (pages, sprites) = parse_atlas_json(&layout.to_json(&files).to_string()).unwrap();
+    assert_eq!(pages[0].0, "ui_0.png");
+    assert_eq!(sprites["wide"], layout.sprites["wide"]);
+
+    let too_big = {
+        let mut atlas = TextureAtlas::new().max_size(32);
+        atlas.add_image("huge", image::RgbaImage::new(31, 31));
+        atlas.pack()
+    };
+    assert!(too_big.is_err());
+}
+
+#[test]
+fn test_uv_packing() {
+    use moonwalk::rendering::vertex::ObjectInstance;
+    use moonwalk::{TextureOptions, TextureWrap};
+
+    // Та же распаковка что unpack_uv в shape.wgsl
+    let unpack = |packed: u16| {
+        let value = (packed & 0x7FFF) as f32;
+        if packed & 0x8000 != 0 { value / 128.0 - 128.0 } else { value / 32767.0 }
+    };
+
+    // Обычные uv в 0..1 точнее четверти текселя атласа 4096
+    for uv in [0.0, 0.25, 0.123_456, 0.999, 1.0] {
+        let packed = ObjectInstance::pack_uv([uv; 4]);
+        assert!((unpack(packed[0]) - uv).abs() < 0.25 / 4096.0, "{}", uv);
+    }
+
+    // Тайлинг и смещение за пределы 0..1
+    let packed = ObjectInstance::pack_uv([-0.5, 1.25, 4.0, 100.0]);
+    let unpacked: Vec<f32> = packed.iter().map(|p| unpack(*p)).collect();
+    assert_eq!(unpacked, vec![-0.5, 1.25, 4.0, 100.0]);
+
+    // Слишком большие значения упираются в край диапазона
+    assert!(unpack(ObjectInstance::pack_uv([1000.0; 4])[0]) < 128.0);
+
+    let options = TextureOptions::default();
+    assert!(options.srgb && !options.mipmaps);
+    assert_eq!(options.wrap_u, TextureWrap::ClampToEdge);
+
+    let repeat = options.wrap(TextureWrap::Repeat);
+    assert_eq!((repeat.wrap_u, repeat.wrap_v), (TextureWrap::Repeat, TextureWrap::Repeat));
+}
+
+#[test]
+fn test_texture_formats() {
+    use moonwalk::TextureFormat;
+
+    assert_eq!(TextureFormat::default(), TextureFormat::Rgba8UnormSrgb);
+    assert_eq!(TextureFormat::Rgba8Unorm.bytes_per_pixel(), 4);
+    assert_eq!(TextureFormat::R8Unorm.bytes_per_pixel(), 1);
+    assert_eq!(TextureFormat::Rg8Unorm.bytes_per_pixel(), 2);
+    assert_eq!(TextureFormat::Rgba16Float.bytes_per_pixel(), 8);
+}
+
+#[test]
+fn test_animation_decoding() {
+    use image::codecs::gif::GifEncoder;
+    use moonwalk::rendering::animated::decode_animation;
+
+    let mut gif = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut gif);
+
+        for (color, ms) in [([255u8, 0, 0, 255], 100), ([0, 0, 255, 255], 0)] {
+            let image = image::RgbaImage::from_pixel(4, 2, image::Rgba(color));
+            encoder.encode_frame(image::Frame::from_parts(image, 0, 0, image::Delay::from_numer_denom_ms(ms, 1))).unwrap();
+        }
+    }
+
+    let frames = decode_animation(&gif).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].image.dimensions(), (4, 2));
+    assert_eq!(frames[1].image.get_pixel(1, 1)[2], 255);
+    assert!((frames[0].delay - 0.1).abs() < 1e-4);
+
+    // Нулевая задержка как в браузерах превращается в 100 мс
+    assert!((frames[1].delay - 0.1).abs() < 1e-4);
+
+    assert!(decode_animation(b"not an image").is_err());
+}
+
+#[test]
+fn test_memory_stats() {
+    use moonwalk::MemoryStats;
+
+    let stats = MemoryStats {
+        textures: 4096,
+        texture_count: 2,
+        render_targets: 1024,
+        filters: 512,
+        glyph_atlases: 256,
+        staging: 128,
+        budget: Some(8192),
+        ..Default::default()
+    };
+
+    // Бюджет и счётчики в сумму не входят
+    assert_eq!(stats.total(), 4096 + 1024 + 512 + 256 + 128);
+    assert_eq!(MemoryStats::default().total(), 0);
+    assert_eq!(MemoryStats::default().budget, None);
+}
@@ -90,3 +90,189 @@ fn test_path_builder_mixed_commands() {
         _ => panic!("Expected Cubic event"),
     }
 }
+
+#[test]
+fn test_open_path_into_text_path() {
+    // Открытый путь из двух подпутей: для текста берётся только первый
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(100.0, 0.0);
+    pb.move_to(0.0, 50.0);
+    pb.line_to(10.0, 50.0);
+
+    let path = pb.into_text_path();
+
+    assert!(!path.is_closed());
+    assert!((path.length() - 100.0).abs() < 1e-3);
+
+    let (point, angle) = path.sample(25.0).unwrap();
+    assert!((point.x - 25.0).abs() < 1e-3 && point.y.abs() < 1e-3);
+    assert!(angle.abs() < 1e-3);
+
+    // За пределами открытого пути глифы не рисуются
+    assert!(path.sample(-1.0).is_none());
+    assert!(path.sample(101.0).is_none());
+}
+
+#[test]
+fn test_closed_text_path_wraps() {
+    let mut pb = PathBuilder::new();
+    moonwalk::path::svg::parse_svg_path(&mut pb, "M 0 0 L 10 0 L 10 10 L 0 10 Z").unwrap();
+
+    let path = pb.into_text_path();
+
+    assert!(path.is_closed());
+    assert!((path.length() - 40.0).abs() < 1e-3);
+
+    // Вторая сторона квадрата идёт вниз, расстояние берётся по модулю длины
+    let (point, angle) = path.sample(55.0).unwrap();
+    assert!((point.x - 10.0).abs() < 1e-3 && (point.y - 5.0).abs() < 1e-3);
+    assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-3);
+}
+
+#[test]
+fn test_paint_helpers() {
+    use glam::{Vec2, Vec4};
+    use moonwalk::path::Paint;
+
+    let paint = Paint::linear(Vec2::ZERO, Vec2::new(100.0, 0.0), Vec4::ONE, Vec4::ZERO);
+
+    match &paint {
+        Paint::Linear { stops, .. } => {
+            assert_eq!(stops.len(), 2);
+            assert_eq!(stops[0].offset, 0.0);
+            assert_eq!(stops[1].color, Vec4::ZERO);
+        }
+
+        _ => panic!("expected linear paint"),
+    }
+
+    assert_eq!(paint.texture_id(), None);
+
+    let pattern = Paint::Texture { texture_id: 7, origin: Vec2::ZERO, size: Vec2::ZERO };
+    assert_eq!(pattern.texture_id(), Some(7));
+}
+
+#[test]
+fn test_path_measure_sample_and_split() {
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(100.0, 0.0);
+    pb.line_to(100.0, 50.0);
+
+    let measure = pb.measure();
+    assert!((measure.length() - 150.0).abs() < 1e-2);
+
+    let (point, tangent) = measure.sample(125.0).unwrap();
+    assert!((point.x - 100.0).abs() < 1e-2 && (point.y - 25.0).abs() < 1e-2);
+    assert!(tangent.x.abs() < 1e-3 && (tangent.y - 1.0).abs() < 1e-3);
+
+    // Билдер не расходуется измерением
+    assert!((pb.measure().length() - 150.0).abs() < 1e-2);
+
+    let (head, tail) = measure.split(40.0);
+    assert!((head.measure().length() - 40.0).abs() < 1e-2);
+    assert!((tail.measure().length() - 110.0).abs() < 1e-2);
+
+    let (start, _) = tail.measure().sample(0.0).unwrap();
+    assert!((start.x - 40.0).abs() < 1e-2 && start.y.abs() < 1e-2);
+
+    let middle = measure.segment(90.0, 110.0);
+    assert!((middle.measure().length() - 20.0).abs() < 1e-2);
+}
+
+#[test]
+fn test_path_boolean_ops_and_svg_string() {
+    let square = |x: f32, y: f32| {
+        let mut pb = PathBuilder::new();
+        pb.move_to(x, y);
+        pb.line_to(x + 10.0, y);
+        pb.line_to(x + 10.0, y + 10.0);
+        pb.line_to(x, y + 10.0);
+        pb.close();
+        pb
+    };
+
+    let a = square(0.0, 0.0);
+    let b = square(5.0, 5.0);
+
+    // Периметры результатов: пересечение 5x5, объединение и разность
+    // повторяют контур ступенькой
+    assert!((a.intersection(&b).measure().length() - 20.0).abs() < 1e-2);
+    assert!((a.union(&b).measure().length() - 60.0).abs() < 1e-2);
+    assert!((a.difference(&b).measure().length() - 40.0).abs() < 1e-2);
+    assert!((a.xor(&b).measure().length() - 80.0).abs() < 1e-2);
+
+    // Смещение квадрата с острыми углами
+    assert!((a.offset(2.0, moonwalk::path::LineJoin::Miter).measure().length() - 56.0).abs() < 1e-2);
+    assert!((a.offset(-2.0, moonwalk::path::LineJoin::Miter).measure().length() - 24.0).abs() < 1e-2);
+
+    // Точки на прямой убираются упрощением
+    let mut line = PathBuilder::new();
+    line.move_to(0.0, 0.0);
+    line.line_to(5.0, 0.01);
+    line.line_to(10.0, 0.0);
+    assert_eq!(line.simplify(0.1).to_svg_path_string(), "M0 0 L10 0");
+
+    let mut curve = PathBuilder::new();
+    curve.move_to(0.0, 0.0);
+    curve.quadratic_bezier_to(5.0, 10.0, 10.0, 0.0);
+    curve.close();
+    assert_eq!(curve.to_svg_path_string(), "M0 0 Q5 10 10 0 Z");
+}
+
+#[test]
+fn test_lottie_player_frames() {
+    // Квадрат 20x20 едет по x от 16 до 36 за 20 кадров, под ним линия с
+    // обрезкой от 0 до 100% за 10 кадров
+    let json = r#"{"fr":30,"ip":0,"op":30,"w":64,"h":64,"layers":[
+        {"ty":4,"ind":1,"ip":0,"op":30,"st":0,"ks":{},"shapes":[{"ty":"gr","it":[
+            {"ty":"rc","p":{"a":0,"k":[0,0]},"s":{"a":0,"k":[20,20]},"r":{"a":0,"k":0}},
+            {"ty":"fl","c":{"a":0,"k":[1,0,0,1]},"o":{"a":0,"k":100}},
+            {"ty":"tr","p":{"a":1,"k":[{"t":0,"s":[16,16]},{"t":20,"s":[36,16]}]}}
+        ]}]},
+        {"ty":4,"ind":2,"ip":0,"op":30,"st":0,"ks":{},"shapes":[
+            {"ty":"sh","ks":{"a":0,"k":{"c":false,"v":[[0,50],[40,50]],"i":[[0,0],[0,0]],"o":[[0,0],[0,0]]}}},
+            {"ty":"st","c":{"a":0,"k":[0,1,0,1]},"o":{"a":0,"k":100},"w":{"a":0,"k":4}},
+            {"ty":"tm","s":{"a":0,"k":0},"e":{"a":1,"k":[{"t":0,"s":[0]},{"t":10,"s":[100]}]},"o":{"a":0,"k":0}}
+        ]}
+    ]}"#;
+
+    let mut player = moonwalk::LottiePlayer::from_json(json).unwrap();
+    assert_eq!(player.size(), glam::Vec2::new(64.0, 64.0));
+    assert!((player.duration() - 1.0).abs() < 1e-5);
+
+    player.seek(5.0);
+    let paths = player.frame_paths(glam::Vec2::ZERO, glam::Vec2::new(64.0, 64.0));
+
+    // Нижний слой (линия) рисуется первым и обрезан до половины
+    assert_eq!(paths.len(), 2);
+    assert!((paths[0].measure().length() - 20.0).abs() < 1e-2);
+
+    // Квадрат начинается в правом верхнем углу: центр на кадре 5 в x = 21
+    let (start, _) = paths[1].measure().sample(0.0).unwrap();
+    assert!((start - glam::Vec2::new(31.0, 6.0)).length() < 1e-3);
+
+    // Масштаб под размер цели
+    let scaled = player.frame_paths(glam::Vec2::new(100.0, 0.0), glam::Vec2::new(128.0, 128.0));
+    let (start, _) = scaled[1].measure().sample(0.0).unwrap();
+    assert!((start - glam::Vec2::new(162.0, 12.0)).length() < 1e-3);
+
+    // Без повтора плеер останавливается на последнем кадре
+    player.seek(0.0);
+    player.set_loop(false);
+    player.play();
+    assert!(player.update(0.5));
+    assert_eq!(player.frame(), 15.0);
+    player.update(1.0);
+    assert_eq!(player.frame(), 29.0);
+    assert!(!player.is_playing());
+
+    // С повтором время заворачивается в начало
+    player.set_loop(true);
+    player.play();
+    player.update(0.1);
+    assert!((player.frame() - 2.0).abs() < 1e-3);
+
+    assert!(moonwalk::LottiePlayer::from_json("{}").is_err());
+}
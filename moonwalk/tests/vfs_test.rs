@@ -0,0 +1,53 @@
+// Часть проекта MoonWalk с открытым исходным кодом.
+// Лицензия EPL 2.0, подробнее в файле LICENSE. Copyright (c) 2026 MoonWalk
+
+use moonwalk::resource_manager::ResourceManager;
+use moonwalk::vfs::{normalize_path, MemorySource};
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(normalize_path("textures\\ui//./button.png"), "textures/ui/button.png");
+    assert_eq!(normalize_path("/a/b/../c/"), "a/c");
+    assert_eq!(normalize_path("../../x"), "x");
+}
+
+#[test]
+fn test_mount_priority_overlay() {
+    let mut resources = ResourceManager::new();
+
+    let base = MemorySource::new()
+        .with_file("ui/button.png", &b"base"[..])
+        .with_file("ui/icon.png", &b"icon"[..]);
+
+    let patch = MemorySource::new().with_file("button.png", &b"patch"[..]);
+
+    resources.mount_memory("", base, 0);
+    let patch_id = resources.mount_memory("ui", patch, 10);
+
+    // Патч с большим приоритетом перекрывает файл, остальные берутся из базы
+    assert_eq!(resources.read_bytes("ui\\button.png").unwrap(), b"patch");
+    assert_eq!(resources.read_bytes("./ui/icon.png").unwrap(), b"icon");
+    assert!(resources.exists("ui/icon.png"));
+    assert_eq!(resources.list_files("ui"), vec!["ui/button.png", "ui/icon.png"]);
+
+    assert!(resources.unmount(patch_id));
+    assert!(!resources.unmount(patch_id));
+    assert_eq!(resources.read_bytes("ui/button.png").unwrap(), b"base");
+}
+
+#[test]
+fn test_mount_dir_and_zip() {
+    let dir = std::env::temp_dir().join(format!("moonwalk_vfs_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("fonts")).unwrap();
+    std::fs::write(dir.join("fonts/main.ttf"), b"font").unwrap();
+
+    let mut resources = ResourceManager::new();
+    resources.mount_dir("assets", dir.to_str().unwrap(), 0).unwrap();
+
+    assert_eq!(resources.read_bytes("assets/fonts/main.ttf").unwrap(), b"font");
+    assert_eq!(resources.list_files("assets"), vec!["assets/fonts/main.ttf"]);
+    assert!(resources.mount_dir("", dir.join("missing").to_str().unwrap(), 0).is_err());
+    assert!(resources.mount_zip_bytes("", b"not a zip".to_vec(), 0).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}